@@ -0,0 +1,57 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use eolib::encrypt::{decrypt_packet, encrypt_packet, swap_multiples};
+
+const SIZES: [usize; 3] = [64, 512, 4096];
+
+fn packet(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 31 % 251) as u8).collect()
+}
+
+fn bench_encrypt_packet(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt_packet");
+    for size in SIZES {
+        let data = packet(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            let mut buf = data.clone();
+            b.iter(|| encrypt_packet(black_box(&mut buf), 6));
+        });
+    }
+    group.finish();
+}
+
+fn bench_decrypt_packet(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decrypt_packet");
+    for size in SIZES {
+        let data = packet(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            let mut buf = data.clone();
+            b.iter(|| decrypt_packet(black_box(&mut buf), 6));
+        });
+    }
+    group.finish();
+}
+
+fn bench_swap_multiples(c: &mut Criterion) {
+    let mut group = c.benchmark_group("swap_multiples");
+    for size in SIZES {
+        let data = packet(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            let mut buf = data.clone();
+            b.iter(|| swap_multiples(black_box(&mut buf), 6));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_encrypt_packet,
+    bench_decrypt_packet,
+    bench_swap_multiples
+);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use eolib::encrypt::{encrypt_files_parallel, encrypt_packet};
+
+// roughly the size of a large map file
+const FILE_SIZE: usize = 16 * 1024;
+
+const FILE_COUNTS: [usize; 3] = [10, 100, 500];
+
+fn files(count: usize) -> Vec<Vec<u8>> {
+    (0..count)
+        .map(|file| {
+            (0..FILE_SIZE)
+                .map(|i| ((i + file) * 31 % 251) as u8)
+                .collect()
+        })
+        .collect()
+}
+
+fn bench_encrypt_files(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt_files");
+    group.sample_size(20);
+    for count in FILE_COUNTS {
+        let data = files(count);
+        group.throughput(Throughput::Bytes((count * FILE_SIZE) as u64));
+        group.bench_with_input(BenchmarkId::new("sequential", count), &data, |b, data| {
+            let mut files = data.clone();
+            b.iter(|| {
+                for file in black_box(&mut files).iter_mut() {
+                    encrypt_packet(file, 6);
+                }
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", count), &data, |b, data| {
+            let mut files = data.clone();
+            b.iter(|| encrypt_files_parallel(black_box(&mut files), 6));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_encrypt_files);
+criterion_main!(benches);
@@ -0,0 +1,116 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use eolib::{
+    data::{EoSerialize, EoWriter},
+    protocol::net::server::{
+        WelcomeCode, WelcomeReplyServerPacket, WelcomeReplyServerPacketWelcomeCodeData,
+        WelcomeReplyServerPacketWelcomeCodeDataSelectCharacter,
+    },
+};
+
+// counts allocations so the benchmarks can check the writer doesn't make any of its own
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const CAPACITY: usize = 256;
+
+// the character's name, stats and equipment are left at their defaults
+#[allow(clippy::needless_update)]
+fn welcome_reply() -> WelcomeReplyServerPacket {
+    WelcomeReplyServerPacket {
+        welcome_code: WelcomeCode::SelectCharacter,
+        welcome_code_data: Some(WelcomeReplyServerPacketWelcomeCodeData::SelectCharacter(
+            WelcomeReplyServerPacketWelcomeCodeDataSelectCharacter {
+                session_id: 1234,
+                character_id: 56_789,
+                map_id: 5,
+                map_rid: [1_000, 2_000],
+                map_file_size: 120_000,
+                eif_rid: [3_000, 4_000],
+                eif_length: 500,
+                enf_rid: [5_000, 6_000],
+                enf_length: 300,
+                esf_rid: [7_000, 8_000],
+                esf_length: 100,
+                ecf_rid: [9_000, 10_000],
+                ecf_length: 15,
+                ..Default::default()
+            },
+        )),
+    }
+}
+
+fn assert_no_allocations(packet: &WelcomeReplyServerPacket) {
+    let mut writer = EoWriter::with_capacity(CAPACITY);
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    packet.serialize(&mut writer).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    assert_eq!(
+        allocations, 0,
+        "serializing allocated {} times",
+        allocations
+    );
+}
+
+fn bench_numbers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_number");
+    group.bench_function("char", |b| {
+        b.iter_batched_ref(
+            || EoWriter::with_capacity(CAPACITY),
+            |writer| {
+                for i in 0..CAPACITY as i32 {
+                    writer.add_char(black_box(i % 253)).unwrap();
+                }
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.bench_function("int", |b| {
+        b.iter_batched_ref(
+            || EoWriter::with_capacity(CAPACITY * 4),
+            |writer| {
+                for i in 0..CAPACITY as i32 {
+                    writer.add_int(black_box(i * 1_000_003)).unwrap();
+                }
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+fn bench_welcome_reply(c: &mut Criterion) {
+    let packet = welcome_reply();
+    assert_no_allocations(&packet);
+
+    c.bench_function("serialize_welcome_reply", |b| {
+        b.iter_batched_ref(
+            || EoWriter::with_capacity(CAPACITY),
+            |writer| black_box(&packet).serialize(writer).unwrap(),
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(benches, bench_numbers, bench_welcome_reply);
+criterion_main!(benches);
@@ -1,6 +1,5 @@
 use convert_case::Casing;
 use glob::glob;
-use serde::Deserialize;
 use std::{
     collections::HashSet,
     fs::File,
@@ -8,168 +7,21 @@ use std::{
     path::{Path, PathBuf},
 };
 
-#[derive(Debug, Deserialize)]
-struct Protocol {
-    #[serde(rename = "$value", default)]
-    pub elements: Vec<Element>,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "lowercase")]
-enum Element {
-    Enum(Enum),
-    Struct(Struct),
-    Packet(Packet),
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Enum {
-    #[serde(rename = "@name")]
-    pub name: String,
-    #[serde(rename = "@type")]
-    pub data_type: String,
-    #[serde(rename = "$value", default)]
-    pub elements: Vec<EnumElement>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-enum EnumElement {
-    #[serde(rename = "comment")]
-    Comment(String),
-    #[serde(rename = "value")]
-    Value(EnumValue),
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct EnumValue {
-    #[serde(rename = "@name")]
-    pub name: String,
-    #[serde(rename = "comment")]
-    pub comment: Option<String>,
-    #[serde(rename = "$text")]
-    pub value: i32,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Struct {
-    #[serde(rename = "@name")]
-    pub name: String,
-    #[serde(rename = "$value", default)]
-    pub elements: Vec<StructElement>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-#[serde(rename_all = "lowercase")]
-enum StructElement {
-    Break,
-    Chunked(Chunked),
-    Comment(String),
-    Dummy(Dummy),
-    Field(Field),
-    Array(Array),
-    Length(Length),
-    Switch(Switch),
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Chunked {
-    #[serde(rename = "$value", default)]
-    pub elements: Vec<StructElement>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Field {
-    #[serde(rename = "@name")]
-    pub name: Option<String>,
-    #[serde(rename = "@type")]
-    pub data_type: String,
-    #[serde(rename = "$value", default)]
-    pub value: Option<String>,
-    pub comment: Option<String>,
-    #[serde(rename = "@padded")]
-    pub padded: Option<bool>,
-    #[serde(rename = "@optional")]
-    pub optional: Option<bool>,
-    #[serde(rename = "@length")]
-    pub length: Option<String>,
-}
-
-fn default_as_true() -> bool {
-    true
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Array {
-    #[serde(rename = "@name")]
-    pub name: String,
-    #[serde(rename = "@type")]
-    pub data_type: String,
-    #[serde(rename = "@length")]
-    pub length: Option<String>,
-    #[serde(rename = "@optional")]
-    pub optional: Option<bool>,
-    #[serde(rename = "@delimited")]
-    pub delimited: Option<bool>,
-    #[serde(rename = "@trailing-delimiter")]
-    #[serde(default = "default_as_true")]
-    pub trailing_delimiter: bool,
-    pub comment: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Length {
-    #[serde(rename = "@name")]
-    pub name: String,
-    #[serde(rename = "@type")]
-    pub data_type: String,
-    #[serde(rename = "@length")]
-    pub optional: Option<bool>,
-    #[serde(rename = "@offset")]
-    pub offset: Option<i32>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Dummy {
-    #[serde(rename = "@type")]
-    pub data_type: String,
-    #[serde(rename = "$value", default)]
-    pub value: String,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Switch {
-    #[serde(rename = "@field")]
-    pub field: String,
-    #[serde(rename = "$value", default)]
-    pub cases: Vec<Case>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-#[serde(rename = "case")]
-struct Case {
-    #[serde(rename = "@default")]
-    pub default: Option<bool>,
-    #[serde(rename = "@value")]
-    pub value: Option<String>,
-    #[serde(rename = "$value", default)]
-    pub elements: Option<Vec<StructElement>>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Packet {
-    #[serde(rename = "@action")]
-    pub action: String,
-    #[serde(rename = "@family")]
-    pub family: String,
-    #[serde(rename = "$value", default)]
-    pub elements: Vec<StructElement>,
-}
+#[path = "src/protocol_schema/xml.rs"]
+mod xml;
+use xml::{
+    is_primitive, parse_protocol, Array, Case, Dummy, Element, Enum, EnumElement, EnumValue, Field,
+    Length, Packet, Protocol, Struct, StructElement, Switch, PRIMITIVE_TYPES,
+};
 
 static CODEGEN_WARNING: &str = r"// This file is automatically generated by build.rs
 // Please do not edit it directly
 
 ";
 
+// structs with at least this many fields also get a generated builder
+const BUILDER_MIN_FIELDS: usize = 6;
+
 static RUST_KEYWORDS: [&str; 52] = [
     "abstract", "alignof", "as", "become", "box", "break", "const", "continue", "crate", "do",
     "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
@@ -180,6 +32,10 @@ static RUST_KEYWORDS: [&str; 52] = [
 
 fn main() {
     println!("cargo:rerun-if-changed=eo-protocol/xml");
+    println!("cargo:rerun-if-changed=src/protocol_schema/xml.rs");
+    println!("cargo:rerun-if-env-changed=EOLIB_GENERATED_DIR");
+    println!("cargo:rerun-if-env-changed=EOLIB_EXTRA_PROTOCOL_DIR");
+    println!("cargo:rerun-if-env-changed=EOLIB_PROTOCOL_OVERRIDE_DIR");
 
     let mut protocols = Vec::new();
     // find all protocol.xml files in the xml directory recursively
@@ -201,6 +57,17 @@ fn main() {
         }
     }
 
+    // definitions for another protocol version, replacing the eo-protocol ones
+    if let Some(override_dir) = std::env::var_os("EOLIB_PROTOCOL_OVERRIDE_DIR") {
+        println!("cargo:rerun-if-changed={}", override_dir.to_string_lossy());
+        merge_extra_protocols(&mut protocols, Path::new(&override_dir), true);
+    }
+
+    if let Some(extra_dir) = std::env::var_os("EOLIB_EXTRA_PROTOCOL_DIR") {
+        println!("cargo:rerun-if-changed={}", extra_dir.to_string_lossy());
+        merge_extra_protocols(&mut protocols, Path::new(&extra_dir), false);
+    }
+
     let enums: Vec<Enum> = protocols
         .iter()
         .flat_map(|(protocol, _)| {
@@ -260,6 +127,20 @@ fn main() {
             }
         }
 
+        let packets: Vec<&Packet> = protocol
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                Element::Packet(packet) => Some(packet),
+                _ => None,
+            })
+            .collect();
+        if !packets.is_empty() {
+            generate_describe_packet_code(&packets, &output_dir, &mut mod_code);
+            generate_packet_size_code(&packets, &mut mod_code, &enums, &structs);
+            generate_packet_name_code(&packets, &mut mod_code);
+        }
+
         let is_root = path.parent().unwrap() == Path::new("eo-protocol/xml");
         if is_root {
             mod_code.push_str("pub mod map;\n");
@@ -281,6 +162,67 @@ fn main() {
         let mut mod_file = File::create(output_dir.join("mod.rs")).unwrap();
         mod_file.write_all(mod_code.as_bytes()).unwrap();
     }
+
+    // copy of the generated code that can be checked in and diffed across eo-protocol updates
+    if let Some(generated_dir) = std::env::var_os("EOLIB_GENERATED_DIR") {
+        let out_dir = std::env::var_os("OUT_DIR").unwrap();
+        export_generated_code(Path::new(&out_dir), Path::new(&generated_dir)).unwrap();
+    }
+}
+
+fn export_generated_code(out_dir: &Path, generated_dir: &Path) -> std::io::Result<()> {
+    copy_generated_files(out_dir, generated_dir)?;
+    remove_stale_generated_files(out_dir, generated_dir)?;
+
+    // rustfmt follows the mod declarations so formatting the root formats everything
+    let rustfmt = std::env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+    let formatted = std::process::Command::new(rustfmt)
+        .args(["--edition", "2021"])
+        .arg(generated_dir.join("mod.rs"))
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false);
+
+    if !formatted {
+        println!(
+            "cargo:warning=Failed to format generated code in {}",
+            generated_dir.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn copy_generated_files(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let path = entry?.path();
+        let target = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_generated_files(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+// only files written by this script are removed in case the directory holds anything else
+fn remove_stale_generated_files(out_dir: &Path, generated_dir: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(generated_dir)? {
+        let path = entry?.path();
+        let source = out_dir.join(path.file_name().unwrap());
+        if path.is_dir() {
+            remove_stale_generated_files(&source, &path)?;
+        } else if !source.exists()
+            && std::fs::read_to_string(&path)
+                .map(|code| code.starts_with(CODEGEN_WARNING))
+                .unwrap_or(false)
+        {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
 }
 
 fn generate_enum_file(
@@ -304,7 +246,7 @@ fn generate_enum_file(
     };
 
     append_doc_comments(&mut code, comments);
-    code.push_str("#[derive(Debug, PartialEq, Eq, Copy, Clone)]\n");
+    code.push_str("#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Default)]\n");
     code.push_str("#[cfg_attr(feature = \"serde\", derive(Serialize, Deserialize))]\n");
     code.push_str(&format!("pub enum {} {{\n", protocol_enum.name));
 
@@ -317,15 +259,19 @@ fn generate_enum_file(
         })
         .collect();
 
-    for variant in &variants {
+    for (i, variant) in variants.iter().enumerate() {
         let comments = match &variant.comment {
             Some(comment) => get_comments(comment),
             None => vec![],
         };
 
         append_doc_comments(&mut code, comments);
+        if i == 0 {
+            code.push_str("    #[default]\n");
+        }
         code.push_str(&format!("    {},\n", replace_keyword(&variant.name)));
     }
+    code.push_str("    /// A value that doesn't match any known variant\n");
     code.push_str(&format!(
         "    Unrecognized({}),\n",
         get_field_type(&protocol_enum.data_type)
@@ -383,12 +329,22 @@ fn generate_enum_file(
     code.push_str("    }\n");
     code.push_str("}\n\n");
 
-    code.push_str(&format!("impl Default for {} {{\n", protocol_enum.name));
-
-    code.push_str("    fn default() -> Self {\n");
+    // only known variants are generated since unrecognized values may not survive a round trip
+    code.push_str("#[cfg(feature = \"testing\")]\n");
+    code.push_str(&format!(
+        "impl proptest::arbitrary::Arbitrary for {} {{\n",
+        protocol_enum.name
+    ));
+    code.push_str("    type Parameters = ();\n");
+    code.push_str("    type Strategy = proptest::sample::Select<Self>;\n\n");
+    code.push_str("    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {\n");
     code.push_str(&format!(
-        "        Self::{}\n",
-        replace_keyword(&variants[0].name)
+        "        proptest::sample::select(vec![{}])\n",
+        variants
+            .iter()
+            .map(|variant| format!("Self::{}", replace_keyword(&variant.name)))
+            .collect::<Vec<_>>()
+            .join(", ")
     ));
     code.push_str("    }\n");
     code.push_str("}\n");
@@ -437,13 +393,13 @@ fn generate_struct_file(
         structs,
     );
 
-    for switch in protocol_struct.elements.iter().filter_map(|e| match e {
-        StructElement::Switch(switch) => Some(switch),
-        _ => None,
-    }) {
-        let name = get_field_type(&format!("{}_{}_data", protocol_struct.name, switch.field));
-        generate_switch_code(&name, &mut code, switch, enums, structs);
-    }
+    generate_switches_code(
+        &protocol_struct.name,
+        &mut code,
+        &protocol_struct.elements,
+        enums,
+        structs,
+    );
 
     code.push_str(CODEGEN_WARNING);
 
@@ -480,6 +436,26 @@ fn generate_packet_file(
         code.push('\n');
     }
 
+    let name = get_packet_struct_name(packet, path);
+
+    write_struct(&name, &packet.elements, &mut code, enums, structs);
+
+    generate_switches_code(&name, &mut code, &packet.elements, enums, structs);
+
+    code.push_str(CODEGEN_WARNING);
+
+    let snake_name = name.to_case(convert_case::Case::Snake);
+    let mut file = File::create(path.join(format!("{}.rs", snake_name)))?;
+
+    file.write_all(code.as_bytes())?;
+
+    mod_code.push_str(&format!("mod {};\n", snake_name));
+    mod_code.push_str(&format!("pub use {}::*;\n", snake_name));
+
+    Ok(())
+}
+
+fn get_packet_struct_name(packet: &Packet, path: &Path) -> String {
     let path_name = path.to_str().unwrap();
 
     // either Server or Client
@@ -489,43 +465,292 @@ fn generate_packet_file(
         "Client"
     };
 
-    let name = format!("{}{}{}Packet", packet.family, packet.action, source);
+    format!("{}{}{}Packet", packet.family, packet.action, source)
+}
 
-    write_struct(&name, &packet.elements, &mut code, enums, structs);
+// used by debug::dump_packet to deserialize a packet without knowing its type up front
+fn generate_describe_packet_code(packets: &[&Packet], path: &Path, mod_code: &mut String) {
+    mod_code.push_str(
+        "\n/// Deserializes the packet with a family and action and returns it pretty printed\n",
+    );
+    mod_code.push_str("///\n");
+    mod_code.push_str("/// Returns `None` if there is no packet with the family and action\n");
+    mod_code.push_str("pub(crate) fn describe_packet(\n");
+    mod_code.push_str("    family: crate::protocol::net::PacketFamily,\n");
+    mod_code.push_str("    action: crate::protocol::net::PacketAction,\n");
+    mod_code.push_str("    reader: &crate::data::EoReader,\n");
+    mod_code.push_str(") -> Option<Result<String, crate::data::EoReaderError>> {\n");
+    mod_code.push_str("    use crate::data::EoSerialize;\n");
+    mod_code.push_str("    use crate::protocol::net::{PacketAction, PacketFamily};\n\n");
+    mod_code.push_str("    match (family, action) {\n");
+    for packet in packets {
+        mod_code.push_str(&format!(
+            "        (PacketFamily::{}, PacketAction::{}) => Some({}::deserialize(reader).map(|packet| format!(\"{{:#?}}\", packet))),\n",
+            replace_keyword(&packet.family),
+            replace_keyword(&packet.action),
+            get_packet_struct_name(packet, path),
+        ));
+    }
+    mod_code.push_str("        _ => None,\n");
+    mod_code.push_str("    }\n");
+    mod_code.push_str("}\n");
+}
 
-    for switch in packet.elements.iter().filter_map(|e| match e {
-        StructElement::Switch(switch) => Some(switch),
-        _ => None,
-    }) {
-        let name = get_field_type(&format!("{}_{}_data", name, switch.field));
-        generate_switch_code(&name, &mut code, switch, enums, structs);
+fn generate_packet_size_code(
+    packets: &[&Packet],
+    mod_code: &mut String,
+    enums: &[Enum],
+    structs: &[Struct],
+) {
+    mod_code.push_str(
+        "\n/// Returns the smallest and largest size of the packet with a family and action\n",
+    );
+    mod_code.push_str("///\n");
+    mod_code.push_str("/// Returns `None` if there is no packet with the family and action\n");
+    mod_code.push_str("pub fn packet_size(\n");
+    mod_code.push_str("    family: crate::protocol::net::PacketFamily,\n");
+    mod_code.push_str("    action: crate::protocol::net::PacketAction,\n");
+    mod_code.push_str(") -> Option<crate::packet::PacketSize> {\n");
+    mod_code.push_str("    use crate::packet::PacketSize;\n");
+    mod_code.push_str("    use crate::protocol::net::{PacketAction, PacketFamily};\n\n");
+    mod_code.push_str("    match (family, action) {\n");
+    for packet in packets {
+        let size = get_elements_size_bounds(&packet.elements, enums, structs);
+        mod_code.push_str(&format!(
+            "        (PacketFamily::{}, PacketAction::{}) => Some(PacketSize {{ min: {}, max: {:?} }}),\n",
+            replace_keyword(&packet.family),
+            replace_keyword(&packet.action),
+            size.min,
+            size.max,
+        ));
     }
+    mod_code.push_str("        _ => None,\n");
+    mod_code.push_str("    }\n");
+    mod_code.push_str("}\n");
 
-    // Assumes no nested chunked elements
-    for chunked in packet.elements.iter().filter_map(|e| match e {
-        StructElement::Chunked(chunked) => Some(chunked),
-        _ => None,
-    }) {
-        for switch in chunked.elements.iter().filter_map(|e| match e {
-            StructElement::Switch(switch) => Some(switch),
-            _ => None,
-        }) {
-            let name = get_field_type(&format!("{}_{}_data", name, switch.field));
-            generate_switch_code(&name, &mut code, switch, enums, structs);
+    mod_code.push_str(
+        "\n/// Returns an error if the packet with a family and action can't be `length` bytes long\n",
+    );
+    mod_code.push_str("///\n");
+    mod_code.push_str("/// `length` doesn't include the family, action and sequence bytes\n");
+    mod_code.push_str("pub fn validate_size(\n");
+    mod_code.push_str("    family: crate::protocol::net::PacketFamily,\n");
+    mod_code.push_str("    action: crate::protocol::net::PacketAction,\n");
+    mod_code.push_str("    length: usize,\n");
+    mod_code.push_str(") -> Result<(), crate::packet::PacketSizeError> {\n");
+    mod_code.push_str("    match packet_size(family, action) {\n");
+    mod_code.push_str("        Some(size) => size.validate(length),\n");
+    mod_code.push_str(
+        "        None => Err(crate::packet::PacketSizeError::UnknownPacket(family, action)),\n",
+    );
+    mod_code.push_str("    }\n");
+    mod_code.push_str("}\n");
+}
+
+fn generate_packet_name_code(packets: &[&Packet], mod_code: &mut String) {
+    mod_code.push_str(
+        "\n/// Returns the name of the packet with a family and action, like `Walk_Player`\n",
+    );
+    mod_code.push_str("///\n");
+    mod_code.push_str("/// Returns `None` if there is no packet with the family and action\n");
+    mod_code.push_str("pub fn packet_name(\n");
+    mod_code.push_str("    family: crate::protocol::net::PacketFamily,\n");
+    mod_code.push_str("    action: crate::protocol::net::PacketAction,\n");
+    mod_code.push_str(") -> Option<&'static str> {\n");
+    mod_code.push_str("    use crate::protocol::net::{PacketAction, PacketFamily};\n\n");
+    mod_code.push_str("    match (family, action) {\n");
+    for packet in packets {
+        mod_code.push_str(&format!(
+            "        (PacketFamily::{}, PacketAction::{}) => Some(\"{}_{}\"),\n",
+            replace_keyword(&packet.family),
+            replace_keyword(&packet.action),
+            packet.family,
+            packet.action,
+        ));
+    }
+    mod_code.push_str("        _ => None,\n");
+    mod_code.push_str("    }\n");
+    mod_code.push_str("}\n");
+
+    mod_code.push_str(
+        "\n/// Returns the family and action of the packet with a name from [packet_name]\n",
+    );
+    mod_code.push_str("pub fn packet_id(\n");
+    mod_code.push_str("    name: &str,\n");
+    mod_code.push_str(
+        ") -> Option<(crate::protocol::net::PacketFamily, crate::protocol::net::PacketAction)> {\n",
+    );
+    mod_code.push_str("    use crate::protocol::net::{PacketAction, PacketFamily};\n\n");
+    mod_code.push_str("    match name {\n");
+    for packet in packets {
+        mod_code.push_str(&format!(
+            "        \"{}_{}\" => Some((PacketFamily::{}, PacketAction::{})),\n",
+            packet.family,
+            packet.action,
+            replace_keyword(&packet.family),
+            replace_keyword(&packet.action),
+        ));
+    }
+    mod_code.push_str("        _ => None,\n");
+    mod_code.push_str("    }\n");
+    mod_code.push_str("}\n");
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SizeBounds {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl SizeBounds {
+    fn exact(size: usize) -> Self {
+        Self {
+            min: size,
+            max: Some(size),
         }
     }
 
-    code.push_str(CODEGEN_WARNING);
+    fn unbounded() -> Self {
+        Self { min: 0, max: None }
+    }
 
-    let snake_name = name.to_case(convert_case::Case::Snake);
-    let mut file = File::create(path.join(format!("{}.rs", snake_name)))?;
+    // one after the other
+    fn then(self, other: Self) -> Self {
+        Self {
+            min: self.min + other.min,
+            max: self.max.zip(other.max).and_then(|(a, b)| a.checked_add(b)),
+        }
+    }
 
-    file.write_all(code.as_bytes())?;
+    // one or the other
+    fn or(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.zip(other.max).map(|(a, b)| a.max(b)),
+        }
+    }
 
-    mod_code.push_str(&format!("mod {};\n", snake_name));
-    mod_code.push_str(&format!("pub use {}::*;\n", snake_name));
+    fn times(self, count: usize) -> Self {
+        Self {
+            min: self.min * count,
+            max: self.max.and_then(|max| max.checked_mul(count)),
+        }
+    }
 
-    Ok(())
+    fn optional(self, optional: Option<bool>) -> Self {
+        match optional {
+            Some(true) => Self::exact(0).or(self),
+            _ => self,
+        }
+    }
+}
+
+fn get_type_size_bounds(
+    data_type: &str,
+    length: &Option<String>,
+    enums: &[Enum],
+    structs: &[Struct],
+) -> SizeBounds {
+    // `Enum:short` and `bool:short` are written as the type after the colon
+    if let Some((_, data_type)) = data_type.split_once(':') {
+        return get_type_size_bounds(data_type, length, enums, structs);
+    }
+
+    if let Some(protocol_enum) = enums.iter().find(|e| e.name == data_type) {
+        return get_type_size_bounds(&protocol_enum.data_type, length, enums, structs);
+    }
+
+    if let Some(protocol_struct) = structs.iter().find(|s| s.name == data_type) {
+        return get_elements_size_bounds(&protocol_struct.elements, enums, structs);
+    }
+
+    match data_type {
+        "byte" | "char" | "bool" => SizeBounds::exact(1),
+        "short" => SizeBounds::exact(2),
+        "three" => SizeBounds::exact(3),
+        "int" => SizeBounds::exact(4),
+        "string" | "encoded_string" if is_static_length(length) => {
+            SizeBounds::exact(length.as_ref().unwrap().parse().unwrap())
+        }
+        _ => SizeBounds::unbounded(),
+    }
+}
+
+fn get_elements_size_bounds(
+    elements: &[StructElement],
+    enums: &[Enum],
+    structs: &[Struct],
+) -> SizeBounds {
+    let mut size = SizeBounds::exact(0);
+    for element in elements {
+        let element_size = match element {
+            StructElement::Break => SizeBounds::exact(1),
+            StructElement::Dummy(dummy) => match dummy.data_type.as_str() {
+                "string" | "encoded_string" => SizeBounds::exact(dummy.value.len()),
+                data_type => get_type_size_bounds(data_type, &None, enums, structs),
+            },
+            StructElement::Field(field) => match (field.data_type.as_str(), &field.value) {
+                ("string" | "encoded_string", Some(value)) if field.length.is_none() => {
+                    SizeBounds::exact(value.len())
+                }
+                _ => get_type_size_bounds(&field.data_type, &field.length, enums, structs)
+                    .optional(field.optional),
+            },
+            StructElement::Length(length) => {
+                get_type_size_bounds(&length.data_type, &None, enums, structs)
+                    .optional(length.optional)
+            }
+            StructElement::Array(array) => {
+                let mut element_size =
+                    get_type_size_bounds(&array.data_type, &None, enums, structs);
+                if matches!(array.delimited, Some(true)) {
+                    element_size = element_size.then(SizeBounds::exact(1));
+                }
+
+                let array_size = if is_static_length(&array.length) {
+                    let count: usize = array.length.as_ref().unwrap().parse().unwrap();
+                    let mut array_size = element_size.times(count);
+                    if count > 0
+                        && !array.trailing_delimiter
+                        && matches!(array.delimited, Some(true))
+                    {
+                        array_size.min -= 1;
+                        array_size.max = array_size.max.map(|max| max - 1);
+                    }
+                    array_size
+                } else {
+                    SizeBounds::unbounded()
+                };
+                array_size.optional(array.optional)
+            }
+            StructElement::Switch(switch) => {
+                let has_default = switch.cases.iter().any(|c| matches!(c.default, Some(true)));
+                let mut switch_size: Option<SizeBounds> = if has_default {
+                    None
+                } else {
+                    // values without a case have no data
+                    Some(SizeBounds::exact(0))
+                };
+                for case in &switch.cases {
+                    let case_size = match &case.elements {
+                        Some(elements) => get_elements_size_bounds(elements, enums, structs),
+                        None => SizeBounds::exact(0),
+                    };
+                    switch_size = Some(match switch_size {
+                        Some(switch_size) => switch_size.or(case_size),
+                        None => case_size,
+                    });
+                }
+                switch_size.unwrap_or_else(|| SizeBounds::exact(0))
+            }
+            StructElement::Chunked(chunked) => {
+                get_elements_size_bounds(&chunked.elements, enums, structs)
+            }
+            StructElement::Comment(_) => SizeBounds::exact(0),
+        };
+        size = size.then(element_size);
+    }
+    size
 }
 
 fn generate_switch_code(
@@ -535,10 +760,26 @@ fn generate_switch_code(
     enums: &[Enum],
     structs: &[Struct],
 ) {
-    code.push_str("#[derive(Debug, PartialEq, Eq, Clone)]\n");
+    code.push_str(&format!(
+        "/// Data associated with different values of the `{}` field\n",
+        switch.field
+    ));
+    code.push_str("#[derive(Debug, PartialEq, Eq, Hash, Clone)]\n");
     code.push_str("#[cfg_attr(feature = \"serde\", derive(Serialize, Deserialize))]\n");
     code.push_str(&format!("pub enum {} {{\n", name));
     for case in switch.cases.iter().filter(|c| c.elements.is_some()) {
+        if let Some(StructElement::Comment(comment)) = case
+            .elements
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|e| matches!(e, StructElement::Comment(_)))
+        {
+            for comment in get_comments(comment) {
+                code.push_str(&format!("    /// {}\n", comment));
+            }
+        }
+
         match case.default {
             Some(true) => {
                 code.push_str(&format!(
@@ -555,89 +796,854 @@ fn generate_switch_code(
             }
         }
     }
-    code.push_str("}\n\n");
+    code.push_str("}\n\n");
+
+    // the first case is the default so structs holding the data can be built incrementally
+    if let Some(case) = switch.cases.iter().find(|c| c.elements.is_some()) {
+        let variant = match case.default {
+            Some(true) => "Default".to_owned(),
+            _ => replace_keyword(case.value.as_ref().unwrap()),
+        };
+        code.push_str(&format!("impl Default for {} {{\n", name));
+        code.push_str("    fn default() -> Self {\n");
+        code.push_str(&format!("        Self::{}(Default::default())\n", variant));
+        code.push_str("    }\n");
+        code.push_str("}\n\n");
+    }
+
+    for case in switch.cases.iter().filter(|c| c.elements.is_some()) {
+        let elements = case.elements.as_ref().unwrap();
+        let name = match case.default {
+            Some(true) => get_field_type(&format!("{}_default", name)),
+            _ => get_field_type(&format!("{}_{}", name, case.value.as_ref().unwrap())),
+        };
+
+        write_struct(&name, elements, code, enums, structs);
+        generate_switches_code(&name, code, elements, enums, structs);
+    }
+}
+
+// Generates the data enums for every switch in a struct, including switches inside chunked
+// sections since they are still fields of the same struct
+fn generate_switches_code(
+    struct_name: &str,
+    code: &mut String,
+    elements: &[StructElement],
+    enums: &[Enum],
+    structs: &[Struct],
+) {
+    for element in elements {
+        match element {
+            StructElement::Switch(switch) => {
+                let name = get_field_type(&format!("{}_{}_data", struct_name, switch.field));
+                generate_switch_code(&name, code, switch, enums, structs);
+            }
+            StructElement::Chunked(chunked) => {
+                generate_switches_code(struct_name, code, &chunked.elements, enums, structs);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn write_struct(
+    name: &str,
+    elements: &[StructElement],
+    code: &mut String,
+    enums: &[Enum],
+    structs: &[Struct],
+) {
+    let comments = match elements
+        .iter()
+        .find(|e| matches!(e, StructElement::Comment(_)))
+    {
+        Some(StructElement::Comment(comment)) => get_comments(comment),
+        _ => vec![],
+    };
+
+    for comment in &comments {
+        code.push_str(&format!("/// {}\n", comment));
+    }
+
+    let mut derives = vec!["Debug", "Default", "PartialEq", "Eq", "Hash", "Clone"];
+    if name == "Coords" {
+        derives.push("Copy");
+    }
+
+    code.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+    code.push_str("#[cfg_attr(feature = \"serde\", derive(Serialize, Deserialize))]\n");
+    code.push_str(&format!("pub struct {} {{\n", name));
+    let field_count = write_struct_fields(code, name, elements, 0);
+    code.push_str("}\n\n");
+
+    let builder_fields = get_builder_fields(name, elements);
+    let has_builder = builder_fields.len() >= BUILDER_MIN_FIELDS;
+
+    code.push_str(&format!("impl {} {{\n", name));
+    code.push_str("    pub fn new() -> Self {\n");
+    code.push_str("        Self::default()\n");
+    code.push_str("    }\n");
+    if has_builder {
+        code.push_str(&format!(
+            "\n    /// Returns a [{0}Builder] for setting the fields of a [{0}] one by one\n",
+            name
+        ));
+        code.push_str(&format!("    pub fn builder() -> {}Builder {{\n", name));
+        code.push_str(&format!("        {}Builder::new()\n", name));
+        code.push_str("    }\n");
+    }
+    code.push_str("}\n\n");
+
+    if has_builder {
+        write_struct_builder(name, &builder_fields, code);
+    }
+
+    code.push_str(&format!("impl EoSerialize for {} {{\n", name));
+    code.push_str(&format!(
+        "    /// Serializes a [{}] into the given [EoWriter] instance\n",
+        name
+    ));
+    code.push_str(
+        "    fn serialize(&self, writer: &mut EoWriter) -> Result<(), EoSerializeError> {\n",
+    );
+    write_struct_serialize(code, name, elements, enums, structs);
+
+    code.push_str("    }\n");
+    code.push_str(&format!(
+        "    /// Deserializes a [{}] from an [EoReader] instance\n",
+        name
+    ));
+    if field_count > 0 {
+        code.push_str("    fn deserialize(reader: &EoReader) -> Result<Self, EoReaderError> {\n");
+        write_struct_deserialize(code, name, elements, enums, structs);
+    } else {
+        code.push_str("    fn deserialize(_reader: &EoReader) -> Result<Self, EoReaderError> {\n");
+        code.push_str("        Ok(Self::default())\n");
+    }
+    code.push_str("    }\n");
+    code.push_str(&format!(
+        "    /// Returns the number of bytes a [{}] is serialized as\n",
+        name
+    ));
+    code.push_str("    fn encoded_size(&self) -> usize {\n");
+    write_struct_encoded_size(code, name, elements, enums, structs);
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    write_struct_arbitrary(code, name, elements, enums, structs);
+}
+
+// A value generated as part of a struct's Arbitrary strategy, with the pattern it is bound to
+// and the statements that copy it into the struct
+struct ArbitraryPart {
+    pattern: String,
+    strategy: String,
+    assignments: Vec<String>,
+}
+
+// Generated values are kept to what the struct can serialize and read back: strings are
+// printable ASCII, lengths fit their length fields, switch data matches its field and
+// optional elements are either all present or all missing since they're read until the data
+// runs out
+fn write_struct_arbitrary(
+    code: &mut String,
+    name: &str,
+    elements: &[StructElement],
+    enums: &[Enum],
+    structs: &[Struct],
+) {
+    let elements: Vec<&StructElement> = elements
+        .iter()
+        .flat_map(|e| match e {
+            StructElement::Chunked(chunked) => chunked.elements.iter().collect(),
+            e => vec![e],
+        })
+        .collect();
+
+    let switch_fields: Vec<&str> = elements
+        .iter()
+        .filter_map(|e| match e {
+            StructElement::Switch(switch) => Some(switch.field.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut parts = Vec::new();
+    let has_optional = elements.iter().any(|e| match e {
+        StructElement::Field(field) => field.name.is_some() && matches!(field.optional, Some(true)),
+        StructElement::Array(array) => matches!(array.optional, Some(true)),
+        _ => false,
+    });
+    if has_optional {
+        parts.push(ArbitraryPart {
+            pattern: "present".to_owned(),
+            strategy: "proptest::arbitrary::any::<bool>()".to_owned(),
+            assignments: vec![],
+        });
+    }
+
+    for element in &elements {
+        match element {
+            StructElement::Field(field) => {
+                let field_name = match &field.name {
+                    Some(field_name) if !switch_fields.contains(&field_name.as_str()) => field_name,
+                    _ => continue,
+                };
+
+                let optional = matches!(field.optional, Some(true));
+                let strategy = match &field.value {
+                    Some(value) => format!(
+                        "proptest::strategy::Just({})",
+                        get_arbitrary_value(&field.data_type, value, enums)
+                    ),
+                    None => get_arbitrary_strategy(
+                        &field.data_type,
+                        field.length.as_deref(),
+                        field.padded.unwrap_or_default(),
+                        get_arbitrary_min_length(field.length.as_deref(), optional, &elements),
+                        enums,
+                        structs,
+                    ),
+                };
+
+                let pattern = format!("{}_value", field_name);
+                let value = if optional {
+                    format!("if present {{ Some({}) }} else {{ None }}", pattern)
+                } else {
+                    pattern.clone()
+                };
+                parts.push(ArbitraryPart {
+                    pattern,
+                    strategy,
+                    assignments: vec![format!("data.{} = {};", replace_keyword(field_name), value)],
+                });
+            }
+            StructElement::Array(array) => {
+                let optional = matches!(array.optional, Some(true));
+                let delimited = matches!(array.delimited, Some(true));
+                let item_strategy = get_arbitrary_strategy(
+                    &array.data_type,
+                    None,
+                    false,
+                    usize::from(delimited),
+                    enums,
+                    structs,
+                );
+
+                let strategy = if is_static_length(&array.length) {
+                    let length = array.length.as_ref().unwrap();
+                    format!(
+                        "proptest::collection::vec({}, {}).prop_map(|items| <[{}; {}]>::try_from(items).unwrap())",
+                        item_strategy,
+                        length,
+                        get_field_type(&array.data_type),
+                        length
+                    )
+                } else {
+                    format!(
+                        "proptest::collection::vec({}, {}..4)",
+                        item_strategy,
+                        get_arbitrary_min_length(array.length.as_deref(), optional, &elements)
+                    )
+                };
+
+                let pattern = format!("{}_value", array.name);
+                let value = if optional {
+                    format!("if present {{ {} }} else {{ Vec::new() }}", pattern)
+                } else {
+                    pattern.clone()
+                };
+                parts.push(ArbitraryPart {
+                    pattern,
+                    strategy,
+                    assignments: vec![format!(
+                        "data.{} = {};",
+                        replace_keyword(&array.name),
+                        value
+                    )],
+                });
+            }
+            StructElement::Switch(switch) => {
+                let field = elements.iter().find_map(|e| match e {
+                    StructElement::Field(field) if field.name.as_ref() == Some(&switch.field) => {
+                        Some(field)
+                    }
+                    _ => None,
+                });
+                let field = match field {
+                    Some(field) => field,
+                    None => panic!("Switch field not found! {}", name),
+                };
+                let (enum_name, enum_data_type) = split_data_type(&field.data_type);
+                let switch_enum = enums
+                    .iter()
+                    .find(|e| e.name == enum_name)
+                    .expect("Switch enum not found!");
+
+                let data_name = get_field_type(&format!("{}_{}_data", name, switch.field));
+                let cases =
+                    get_arbitrary_switch_cases(switch, switch_enum, enum_data_type, &data_name);
+
+                let strategy = if cases.is_empty() {
+                    format!("proptest::strategy::Just(({}::default(), None))", enum_name)
+                } else {
+                    format!("proptest::strategy::Union::new(vec![{}])", cases.join(", "))
+                };
+
+                let field_name = replace_keyword(&switch.field);
+                parts.push(ArbitraryPart {
+                    pattern: format!("({0}_value, {0}_data_value)", switch.field),
+                    strategy,
+                    assignments: vec![
+                        format!("data.{} = {}_value;", field_name, switch.field),
+                        format!("data.{}_data = {}_data_value;", field_name, switch.field),
+                    ],
+                });
+            }
+            _ => {}
+        }
+    }
+
+    code.push_str("#[cfg(feature = \"testing\")]\n");
+    code.push_str(&format!(
+        "impl proptest::arbitrary::Arbitrary for {} {{\n",
+        name
+    ));
+    code.push_str("    type Parameters = ();\n");
+    code.push_str("    type Strategy = proptest::strategy::BoxedStrategy<Self>;\n\n");
+    code.push_str("    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {\n");
+    code.push_str("        use proptest::strategy::Strategy;\n\n");
+
+    if parts.is_empty() {
+        code.push_str("        proptest::strategy::Just(Self::default()).boxed()\n");
+    } else {
+        // parts are nested as pairs so structs aren't limited by the size of proptest's tuples
+        let mut strategy = "proptest::strategy::Just(())".to_owned();
+        let mut pattern = "()".to_owned();
+        for part in parts.iter().rev() {
+            strategy = format!("({}, {})", part.strategy, strategy);
+            pattern = format!("({}, {})", part.pattern, pattern);
+        }
+
+        code.push_str(&format!("        {}\n", strategy));
+        code.push_str(&format!("            .prop_map(|{}| {{\n", pattern));
+        code.push_str("                let mut data = Self::default();\n");
+        for part in &parts {
+            for assignment in &part.assignments {
+                code.push_str(&format!("                {}\n", assignment));
+            }
+        }
+        code.push_str("                data\n");
+        code.push_str("            })\n");
+        code.push_str("            .boxed()\n");
+    }
+
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+}
+
+fn get_arbitrary_strategy(
+    data_type: &str,
+    length: Option<&str>,
+    padded: bool,
+    min_length: usize,
+    enums: &[Enum],
+    structs: &[Struct],
+) -> String {
+    let (data_type, _) = split_data_type(data_type);
+    if enums.iter().any(|e| e.name == data_type) || structs.iter().any(|s| s.name == data_type) {
+        return format!(
+            "proptest::arbitrary::any::<{}>()",
+            get_field_type(data_type)
+        );
+    }
+
+    match data_type {
+        "byte" | "char" | "short" | "three" | "int" => {
+            format!("crate::testing::eo_{}()", data_type)
+        }
+        "bool" => "proptest::arbitrary::any::<bool>()".to_owned(),
+        "string" | "encoded_string" => match length {
+            Some(length) if is_static_length(&Some(length.to_owned())) && padded => {
+                format!("crate::testing::eo_string(0..={})", length)
+            }
+            Some(length) if is_static_length(&Some(length.to_owned())) => {
+                format!("crate::testing::eo_string({})", length)
+            }
+            _ => format!("crate::testing::eo_string({}..=32)", min_length),
+        },
+        "blob" => format!("crate::testing::eo_blob({}..=32)", min_length),
+        _ => panic!("Unexpected data type: {}", data_type),
+    }
+}
+
+// Lengths written with a positive offset have to be at least that long, and optional strings
+// and arrays have to be written as something to be read back
+fn get_arbitrary_min_length(
+    length: Option<&str>,
+    optional: bool,
+    elements: &[&StructElement],
+) -> usize {
+    let offset = length
+        .and_then(|length| {
+            elements.iter().find_map(|e| match e {
+                StructElement::Length(l) if l.name == length => l.offset,
+                _ => None,
+            })
+        })
+        .unwrap_or(0);
+
+    (offset.max(0) as usize).max(usize::from(optional))
+}
+
+fn get_arbitrary_value(data_type: &str, value: &str, enums: &[Enum]) -> String {
+    let (data_type, _) = split_data_type(data_type);
+    if enums.iter().any(|e| e.name == data_type) {
+        return format!("{}::{}", get_field_type(data_type), replace_keyword(value));
+    }
+
+    match data_type {
+        "string" | "encoded_string" => format!("String::from(\"{}\")", value),
+        _ => value.to_owned(),
+    }
+}
+
+// Each case generates a value of the switch field along with the data it is read with. The
+// default case uses a value past every known variant so it can't be mistaken for one of them
+fn get_arbitrary_switch_cases(
+    switch: &Switch,
+    switch_enum: &Enum,
+    enum_data_type: &str,
+    data_name: &str,
+) -> Vec<String> {
+    let variants: Vec<&EnumValue> = switch_enum
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            EnumElement::Value(value) => Some(value),
+            _ => None,
+        })
+        .collect();
+
+    let data_type = if enum_data_type.is_empty() {
+        switch_enum.data_type.as_str()
+    } else {
+        enum_data_type
+    };
+    let unrecognized = variants.iter().map(|v| v.value).max().unwrap_or(0) + 1;
+    let fits = match data_type {
+        "byte" => unrecognized <= 255,
+        "char" => unrecognized < 253,
+        "short" => unrecognized < 64009,
+        _ => true,
+    };
+
+    // values without a case are read as the default case's data when there is one
+    let has_default_data = switch
+        .cases
+        .iter()
+        .any(|c| matches!(c.default, Some(true)) && c.elements.is_some());
+
+    let mut cases = Vec::new();
+    for case in &switch.cases {
+        if case.elements.is_none() && has_default_data {
+            continue;
+        }
+
+        let (value, variant) = match (&case.value, case.default) {
+            (_, Some(true)) if fits => (
+                format!("{}::Unrecognized({})", switch_enum.name, unrecognized),
+                "Default".to_owned(),
+            ),
+            (_, Some(true)) => continue,
+            (Some(value), _) if variants.iter().any(|v| v.name == *value) => (
+                format!("{}::{}", switch_enum.name, replace_keyword(value)),
+                replace_keyword(value),
+            ),
+            _ => continue,
+        };
+
+        cases.push(match &case.elements {
+            Some(_) => {
+                let case_name = match case.default {
+                    Some(true) => get_field_type(&format!("{}_default", data_name)),
+                    _ => get_field_type(&format!("{}_{}", data_name, case.value.as_ref().unwrap())),
+                };
+                format!(
+                    "proptest::arbitrary::any::<{}>().prop_map(|data| ({}, Some({}::{}(data)))).boxed()",
+                    case_name, value, data_name, variant
+                )
+            }
+            None => format!("proptest::strategy::Just(({}, None)).boxed()", value),
+        });
+    }
+
+    cases
+}
+
+// mirrors write_struct_serialize, adding up the size of everything it writes
+fn write_struct_encoded_size(
+    code: &mut String,
+    name: &str,
+    elements: &[StructElement],
+    enums: &[Enum],
+    structs: &[Struct],
+) {
+    let mut body = String::new();
+    generate_encoded_size_elements(&mut body, name, elements, elements, enums, structs);
+    if body.is_empty() {
+        code.push_str("        0\n");
+        return;
+    }
+
+    code.push_str("        let mut size = 0;\n");
+    code.push_str(&body);
+    code.push_str("        size\n");
+}
+
+fn generate_encoded_size_elements(
+    code: &mut String,
+    struct_name: &str,
+    elements: &[StructElement],
+    all_elements: &[StructElement],
+    enums: &[Enum],
+    structs: &[Struct],
+) {
+    for element in elements {
+        match element {
+            StructElement::Break => code.push_str("        size += 1;\n"),
+            StructElement::Dummy(dummy) => {
+                let size = match get_number_size(&dummy.data_type) {
+                    Some(size) => size,
+                    None => dummy.value.len(),
+                };
+                code.push_str(&format!("        size += {};\n", size));
+            }
+            StructElement::Field(field) => {
+                let optional = matches!(field.optional, Some(true));
+                let length = match &field.length {
+                    Some(length) if field.padded.unwrap_or_default() => Some(length),
+                    _ => None,
+                };
+                let value = match (&field.value, &field.name) {
+                    (Some(value), _) => {
+                        code.push_str(&format!(
+                            "        size += {};\n",
+                            get_value_size(&field.data_type, value, enums)
+                        ));
+                        continue;
+                    }
+                    // the value is only bound when its size depends on it
+                    (None, Some(name))
+                        if optional && has_fixed_encoded_size(&field.data_type, length, enums) =>
+                    {
+                        code.push_str(&format!(
+                            "        if self.{}.is_some() {{\n",
+                            replace_keyword(name)
+                        ));
+                        replace_keyword(name)
+                    }
+                    (None, Some(name)) if optional => {
+                        code.push_str(&format!(
+                            "        if let Some({0}) = &self.{0} {{\n",
+                            replace_keyword(name)
+                        ));
+                        replace_keyword(name)
+                    }
+                    (None, Some(name)) => format!("&self.{}", replace_keyword(name)),
+                    (None, None) => panic!("Field name is required for fields without a value!"),
+                };
+
+                code.push_str(&format!(
+                    "        size += {};\n",
+                    get_encoded_size_expression(&field.data_type, &value, length, enums, structs)
+                ));
+
+                if optional {
+                    code.push_str("        }\n");
+                }
+            }
+            StructElement::Array(array) => {
+                let name = replace_keyword(&array.name);
+                let (data_type, _) = split_data_type(&array.data_type);
+                match get_type_number_size(&array.data_type, enums) {
+                    Some(size) => code.push_str(&format!(
+                        "        size += self.{}.len() * {};\n",
+                        name, size
+                    )),
+                    None => {
+                        code.push_str(&format!("        for array_item in &self.{} {{\n", name));
+                        code.push_str(&format!(
+                            "            size += {};\n",
+                            get_encoded_size_expression(
+                                data_type,
+                                "array_item",
+                                None,
+                                enums,
+                                structs
+                            )
+                        ));
+                        code.push_str("        }\n");
+                    }
+                }
+
+                if matches!(array.delimited, Some(true)) {
+                    if array.trailing_delimiter {
+                        code.push_str(&format!("        size += self.{}.len();\n", name));
+                    } else {
+                        code.push_str(&format!(
+                            "        size += self.{}.len().saturating_sub(1);\n",
+                            name
+                        ));
+                    }
+                }
+            }
+            StructElement::Length(length) => {
+                let size = get_number_size(&length.data_type).unwrap();
+                if matches!(length.optional, Some(true)) {
+                    let condition =
+                        match get_element_that_uses_this_length(&length.name, all_elements) {
+                            StructElement::Array(array) => {
+                                format!("!self.{}.is_empty()", replace_keyword(&array.name))
+                            }
+                            StructElement::Field(field) => format!(
+                                "self.{}.is_some()",
+                                replace_keyword(field.name.as_ref().unwrap())
+                            ),
+                            _ => unreachable!(),
+                        };
+                    code.push_str(&format!("        if {} {{\n", condition));
+                    code.push_str(&format!("            size += {};\n", size));
+                    code.push_str("        }\n");
+                } else {
+                    code.push_str(&format!("        size += {};\n", size));
+                }
+            }
+            StructElement::Switch(switch) => {
+                let cases: Vec<&Case> = switch
+                    .cases
+                    .iter()
+                    .filter(|c| c.elements.is_some())
+                    .collect();
+                if cases.is_empty() {
+                    continue;
+                }
+
+                let data_type = get_field_type(&format!("{}_{}_data", struct_name, switch.field));
+                code.push_str(&format!(
+                    "        size += match &self.{}_data {{\n",
+                    replace_keyword(&switch.field)
+                ));
+                for case in cases {
+                    let variant = match (&case.value, case.default) {
+                        (Some(value), _) => replace_keyword(value),
+                        (None, Some(true)) => "Default".to_owned(),
+                        _ => panic!("Unnamed switch case with default=false"),
+                    };
+                    code.push_str(&format!(
+                        "            Some({}::{}(data)) => data.encoded_size(),\n",
+                        data_type, variant
+                    ));
+                }
+                code.push_str("            _ => 0,\n");
+                code.push_str("        };\n");
+            }
+            StructElement::Chunked(chunked) => generate_encoded_size_elements(
+                code,
+                struct_name,
+                &chunked.elements,
+                all_elements,
+                enums,
+                structs,
+            ),
+            StructElement::Comment(_) => {}
+        }
+    }
+}
+
+// `Enum:short` and `bool:short` are written as the type after the colon
+fn split_data_type(data_type: &str) -> (&str, &str) {
+    match data_type.split_once(':') {
+        Some((data_type, written_as)) => (data_type, written_as),
+        None => (data_type, ""),
+    }
+}
+
+fn get_number_size(data_type: &str) -> Option<usize> {
+    match data_type {
+        "byte" | "char" => Some(1),
+        "short" => Some(2),
+        "three" => Some(3),
+        "int" => Some(4),
+        _ => None,
+    }
+}
+
+// the size of numbers, bools and enums, which doesn't depend on their value
+fn get_type_number_size(data_type: &str, enums: &[Enum]) -> Option<usize> {
+    let (data_type, written_as) = split_data_type(data_type);
+    if !written_as.is_empty() {
+        return get_number_size(written_as);
+    }
+
+    if let Some(protocol_enum) = enums.iter().find(|e| e.name == data_type) {
+        return get_number_size(&protocol_enum.data_type);
+    }
 
-    for case in switch.cases.iter().filter(|c| c.elements.is_some()) {
-        let elements = case.elements.as_ref().unwrap();
-        let name = match case.default {
-            Some(true) => get_field_type(&format!("{}_default", name)),
-            _ => get_field_type(&format!("{}_{}", name, case.value.as_ref().unwrap())),
-        };
+    match data_type {
+        "bool" => Some(1),
+        data_type => get_number_size(data_type),
+    }
+}
 
-        write_struct(&name, elements, code, enums, structs);
+fn get_value_size(data_type: &str, value: &str, enums: &[Enum]) -> usize {
+    match get_type_number_size(data_type, enums) {
+        Some(size) => size,
+        None => value.len(),
+    }
+}
 
-        for switch in elements.iter().filter_map(|e| match e {
-            StructElement::Switch(switch) => Some(switch),
-            _ => None,
-        }) {
-            let name = get_field_type(&format!("{}_{}_data", name, switch.field));
-            generate_switch_code(&name, code, switch, enums, structs);
-        }
+fn has_fixed_encoded_size(data_type: &str, fixed_length: Option<&String>, enums: &[Enum]) -> bool {
+    if get_type_number_size(data_type, enums).is_some() {
+        return true;
     }
+
+    let (data_type, _) = split_data_type(data_type);
+    matches!(data_type, "string" | "encoded_string") && fixed_length.is_some()
 }
 
-fn write_struct(
-    name: &str,
-    elements: &[StructElement],
-    code: &mut String,
+fn get_encoded_size_expression(
+    data_type: &str,
+    value: &str,
+    fixed_length: Option<&String>,
     enums: &[Enum],
     structs: &[Struct],
-) {
-    let comments = match elements
-        .iter()
-        .find(|e| matches!(e, StructElement::Comment(_)))
-    {
-        Some(StructElement::Comment(comment)) => get_comments(comment),
-        _ => vec![],
-    };
+) -> String {
+    if let Some(size) = get_type_number_size(data_type, enums) {
+        return size.to_string();
+    }
 
-    for comment in &comments {
-        code.push_str(&format!("/// {}\n", comment));
+    let (data_type, _) = split_data_type(data_type);
+    if structs.iter().any(|s| s.name == data_type) {
+        return format!("EoSerialize::encoded_size({})", value);
     }
 
-    let mut derives = vec!["Debug", "Default", "PartialEq", "Eq", "Clone"];
-    if name == "Coords" {
-        derives.push("Copy");
+    match (data_type, fixed_length) {
+        ("string" | "encoded_string", Some(length)) => length.to_owned(),
+        ("string" | "encoded_string", None) => format!("crate::data::string_size({})", value),
+        ("blob", _) => format!("{}.len()", value),
+        _ => panic!("Unknown type {}", data_type),
     }
+}
 
-    code.push_str(&format!("#[derive({})]\n", derives.join(", ")));
-    code.push_str("#[cfg_attr(feature = \"serde\", derive(Serialize, Deserialize))]\n");
-    code.push_str(&format!("pub struct {} {{\n", name));
-    let field_count = write_struct_fields(code, name, elements, 0);
+// setters are generated by name so the caller doesn't need to know the field order
+fn write_struct_builder(name: &str, fields: &[BuilderField], code: &mut String) {
+    code.push_str(&format!("/// Builder for a [{}]\n", name));
+    code.push_str("///\n");
+    code.push_str("/// Fields that aren't set keep their default value. [build](Self::build) serializes the\n");
+    code.push_str("/// result to check every field is in range before returning it.\n");
+    code.push_str("#[derive(Debug, Default, Clone)]\n");
+    code.push_str(&format!("pub struct {}Builder {{\n", name));
+    code.push_str(&format!("    inner: {},\n", name));
     code.push_str("}\n\n");
 
-    code.push_str(&format!("impl {} {{\n", name));
+    code.push_str(&format!("impl {}Builder {{\n", name));
     code.push_str("    pub fn new() -> Self {\n");
     code.push_str("        Self::default()\n");
     code.push_str("    }\n");
-    code.push_str("}\n\n");
 
-    code.push_str(&format!("impl EoSerialize for {} {{\n", name));
+    for field in fields {
+        let (param_type, value) = if field.field_type == "String" {
+            (
+                "impl Into<String>".to_owned(),
+                format!("{}.into()", field.name),
+            )
+        } else {
+            (field.field_type.clone(), field.name.clone())
+        };
+        let value = if field.optional {
+            format!("Some({})", value)
+        } else {
+            value
+        };
+
+        code.push_str(&format!(
+            "\n    /// Sets the `{}` field\n",
+            field.name.trim_start_matches("r#")
+        ));
+        code.push_str(&format!(
+            "    pub fn {0}(mut self, {0}: {1}) -> Self {{\n",
+            field.name, param_type
+        ));
+        code.push_str(&format!("        self.inner.{} = {};\n", field.name, value));
+        code.push_str("        self\n");
+        code.push_str("    }\n");
+    }
+
     code.push_str(&format!(
-        "    /// Serializes a [{}] into the given [EoWriter] instance\n",
+        "\n    /// Returns the [{}], or an error if any field can't be serialized\n",
         name
     ));
-    code.push_str(
-        "    fn serialize(&self, writer: &mut EoWriter) -> Result<(), EoSerializeError> {\n",
-    );
-    write_struct_serialize(code, name, elements, enums, structs);
-
-    code.push_str("    }\n");
     code.push_str(&format!(
-        "    /// Deserializes a [{}] from an [EoReader] instance\n",
+        "    pub fn build(self) -> Result<{}, EoSerializeError> {{\n",
         name
     ));
-    if field_count > 0 {
-        code.push_str("    fn deserialize(reader: &EoReader) -> Result<Self, EoReaderError> {\n");
-        write_struct_deserialize(code, name, elements, enums, structs);
-    } else {
-        code.push_str("    fn deserialize(_reader: &EoReader) -> Result<Self, EoReaderError> {\n");
-        code.push_str("        Ok(Self::default())\n");
-    }
+    code.push_str("        let mut writer = EoWriter::new();\n");
+    code.push_str("        EoSerialize::serialize(&self.inner, &mut writer)?;\n");
+    code.push_str("        Ok(self.inner)\n");
     code.push_str("    }\n");
     code.push_str("}\n\n");
 }
 
+struct BuilderField {
+    name: String,
+    field_type: String,
+    optional: bool,
+}
+
+fn get_builder_fields(struct_name: &str, elements: &[StructElement]) -> Vec<BuilderField> {
+    let mut fields = Vec::new();
+    for element in elements {
+        match element {
+            StructElement::Field(field) => {
+                if let Some(name) = &field.name {
+                    fields.push(BuilderField {
+                        name: replace_keyword(name),
+                        field_type: get_field_type(&field.data_type),
+                        optional: matches!(field.optional, Some(true)),
+                    });
+                }
+            }
+            StructElement::Array(array) => {
+                let field_type = if is_static_length(&array.length) {
+                    format!(
+                        "[{}; {}]",
+                        get_field_type(&array.data_type),
+                        array.length.as_ref().unwrap()
+                    )
+                } else {
+                    format!("Vec<{}>", get_field_type(&array.data_type))
+                };
+                fields.push(BuilderField {
+                    name: replace_keyword(&array.name),
+                    field_type,
+                    optional: false,
+                });
+            }
+            StructElement::Switch(switch) => fields.push(BuilderField {
+                name: format!("{}_data", replace_keyword(&switch.field)),
+                field_type: get_field_type(&format!("{}_{}_data", struct_name, switch.field)),
+                optional: true,
+            }),
+            StructElement::Chunked(chunked) => {
+                fields.extend(get_builder_fields(struct_name, &chunked.elements));
+            }
+            _ => {}
+        }
+    }
+    fields
+}
+
 fn write_struct_serialize(
     code: &mut String,
     name: &str,
@@ -662,7 +1668,7 @@ fn write_struct_serialize(
             StructElement::Array(array) => generate_serialize_array(code, array, enums, structs),
             StructElement::Length(length) => generate_serialize_length(
                 code,
-                get_name_of_field_that_uses_this_length(&length.name, elements),
+                get_element_that_uses_this_length(&length.name, elements),
                 length,
             ),
             StructElement::Switch(switch) => {
@@ -686,7 +1692,7 @@ fn write_struct_serialize(
                         }
                         StructElement::Length(length) => generate_serialize_length(
                             code,
-                            get_name_of_field_that_uses_this_length(&length.name, elements),
+                            get_element_that_uses_this_length(&length.name, elements),
                             length,
                         ),
                         StructElement::Switch(switch) => {
@@ -705,47 +1711,23 @@ fn write_struct_serialize(
     code.push_str("        Ok(())\n");
 }
 
-fn get_name_of_field_that_uses_this_length(
+fn get_element_that_uses_this_length<'a>(
     length_name: &str,
-    elements: &[StructElement],
-) -> String {
-    let field = elements.iter().find_map(|e| match e {
-        StructElement::Array(array) => {
-            if array.length == Some(length_name.to_owned()) {
-                Some(array.name.clone())
-            } else {
-                None
-            }
-        }
-        StructElement::Field(field) => {
-            if field.length == Some(length_name.to_owned()) {
-                Some(field.name.as_ref().unwrap().clone())
-            } else {
-                None
-            }
-        }
-        StructElement::Chunked(chunked) => chunked.elements.iter().find_map(|e| match e {
-            StructElement::Array(array) => {
-                if array.length == Some(length_name.to_owned()) {
-                    Some(array.name.clone())
-                } else {
-                    None
-                }
-            }
-            StructElement::Field(field) => {
-                if field.length == Some(length_name.to_owned()) {
-                    Some(field.name.as_ref().unwrap().clone())
-                } else {
-                    None
-                }
-            }
-            _ => None,
+    elements: &'a [StructElement],
+) -> &'a StructElement {
+    let element = elements.iter().find_map(|e| match e {
+        StructElement::Array(array) if array.length.as_deref() == Some(length_name) => Some(e),
+        StructElement::Field(field) if field.length.as_deref() == Some(length_name) => Some(e),
+        StructElement::Chunked(chunked) => chunked.elements.iter().find(|e| match e {
+            StructElement::Array(array) => array.length.as_deref() == Some(length_name),
+            StructElement::Field(field) => field.length.as_deref() == Some(length_name),
+            _ => false,
         }),
         _ => None,
     });
 
-    match field {
-        Some(field) => field,
+    match element {
+        Some(element) => element,
         None => panic!("Length field not found! {}", length_name),
     }
 }
@@ -757,6 +1739,11 @@ fn write_struct_deserialize(
     enums: &[Enum],
     structs: &[Struct],
 ) {
+    code.push_str("        #[cfg(feature = \"tracing\")]\n");
+    code.push_str(&format!(
+        "        let _span = tracing::trace_span!(\"deserialize\", name = \"{}\", size = reader.remaining()).entered();\n",
+        name
+    ));
     code.push_str(
         "        let current_chunked_reading_mode = reader.get_chunked_reading_mode();\n",
     );
@@ -769,7 +1756,10 @@ fn write_struct_deserialize(
                 for element in &chunked.elements {
                     match element {
                         StructElement::Break => {
-                            code.push_str("        reader.next_chunk()?;\n");
+                            code.push_str(&format!(
+                                "        reader.next_chunk(){}?;\n",
+                                reader_error_context(name, None)
+                            ));
                         }
                         StructElement::Chunked(_) => {
                             panic!("Nested chunked elements are not supported! {}", name);
@@ -781,10 +1771,10 @@ fn write_struct_deserialize(
                             code.push_str(&format!("        reader.get_{}();\n", dummy.data_type));
                         }
                         StructElement::Field(field) => {
-                            generate_deserialize_field(code, field, enums, structs)
+                            generate_deserialize_field(code, name, field, enums, structs)
                         }
                         StructElement::Array(array) => {
-                            generate_deserialize_array(code, array, enums, structs)
+                            generate_deserialize_array(code, name, array, enums, structs)
                         }
                         StructElement::Switch(switch) => {
                             let field = match elements.iter().find(|e| match e {
@@ -832,8 +1822,12 @@ fn write_struct_deserialize(
             StructElement::Length(length) => {
                 generate_deserialize_length(code, length);
             }
-            StructElement::Field(field) => generate_deserialize_field(code, field, enums, structs),
-            StructElement::Array(array) => generate_deserialize_array(code, array, enums, structs),
+            StructElement::Field(field) => {
+                generate_deserialize_field(code, name, field, enums, structs)
+            }
+            StructElement::Array(array) => {
+                generate_deserialize_array(code, name, array, enums, structs)
+            }
             StructElement::Switch(switch) => {
                 let field = match elements.iter().find(|e| match e {
                     StructElement::Field(field) => field.name == Some(switch.field.clone()),
@@ -865,12 +1859,48 @@ fn write_struct_deserialize(
             _ => {}
         }
     }
+    code.push_str(&format!(
+        "        reader.check_errors(){}?;\n",
+        reader_error_context(name, None)
+    ));
     code.push_str("        reader.set_chunked_reading_mode(current_chunked_reading_mode);\n");
     code.push_str("        Ok(data)\n");
 }
 
+// Attaches the struct and field names to reader errors so a failed deserialize can be traced
+// back to the field that caused it
+fn reader_error_context(struct_name: &str, field: Option<&str>) -> String {
+    match field {
+        Some(field) => format!(
+            ".map_err(|e| e.with_field(\"{}\").with_packet(\"{}\"))",
+            field, struct_name
+        ),
+        None => format!(".map_err(|e| e.with_packet(\"{}\"))", struct_name),
+    }
+}
+
+// Strings are checked against the reader's limits before they're read. `length` is the length
+// field for fixed strings, otherwise the string is the rest of the chunk or data
+fn generate_string_length_check(
+    code: &mut String,
+    struct_name: &str,
+    field: Option<&str>,
+    data_type: &str,
+    length: Option<&str>,
+) {
+    if !matches!(data_type, "string" | "encoded_string") {
+        return;
+    }
+
+    code.push_str(&format!(
+        "        reader.check_string_length({}){}?;\n",
+        length.unwrap_or("reader.remaining()"),
+        reader_error_context(struct_name, field)
+    ));
+}
+
 fn needs_result(data_type: &str) -> bool {
-    !matches!(data_type, "byte" | "string" | "encoded_string")
+    data_type != "byte"
 }
 
 fn generate_serialize_break(code: &mut String) {
@@ -907,7 +1937,10 @@ fn generate_serialize_field(code: &mut String, field: &Field, enums: &[Enum], st
             "        if let Some({}) = self.{}{} {{\n",
             replace_keyword(name),
             replace_keyword(name),
-            if is_primitive(&field.data_type) || enums.iter().any(|e| e.name == field.data_type) {
+            if (is_primitive(&field.data_type)
+                && !matches!(field.data_type.as_str(), "string" | "encoded_string"))
+                || enums.iter().any(|e| e.name == field.data_type)
+            {
                 ""
             } else {
                 ".as_ref()"
@@ -1032,24 +2065,20 @@ fn generate_inner_field_serialize(
                     None => "",
                 };
 
-                let padded = match field.padded {
-                    Some(padded) => padded,
-                    _ => false,
-                };
+                let padded = field.padded.unwrap_or_default();
 
-                if padded
-                    && !length.is_empty()
-                    && matches!(field.data_type.as_str(), "string" | "encoded_string")
-                {
+                if padded && !length.is_empty() && field.data_type == "string" {
                     code.push_str(&format!(
-                        "      let padding_length = {} - {}.len();\n",
-                        length, name
+                        "        writer.add_fixed_string(&{}, {}, true)?;\n",
+                        name, length
                     ));
-                    code.push_str("        let padding = \"ÿ\".repeat(padding_length);\n");
+                    return;
+                }
+
+                if padded && !length.is_empty() && field.data_type == "encoded_string" {
                     code.push_str(&format!(
-                        "        writer.add_{}(&format!(\"{{}}{{}}\", {}, padding));\n",
-                        replace_keyword(&field.data_type),
-                        name
+                        "        writer.add_fixed_encoded_string(&{}, {})?;\n",
+                        name, length
                     ));
                     return;
                 }
@@ -1066,7 +2095,7 @@ fn generate_inner_field_serialize(
                         "*"
                     } else if matches!(field.data_type.as_str(), "string" | "encoded_string")
                         && !name.starts_with('"')
-                        && name != "array_item"
+                        && !optional
                     {
                         "&"
                     } else {
@@ -1085,11 +2114,6 @@ fn generate_inner_field_serialize(
 }
 
 fn generate_serialize_array(code: &mut String, array: &Array, enums: &[Enum], structs: &[Struct]) {
-    let optional = matches!(array.optional, Some(true));
-    if optional {
-        panic!("Optional array not yet supported because I'm lazy");
-    }
-
     let delimited = matches!(array.delimited, Some(true));
 
     if delimited && !array.trailing_delimiter {
@@ -1129,27 +2153,41 @@ fn generate_serialize_array(code: &mut String, array: &Array, enums: &[Enum], st
     code.push_str("        }\n");
 }
 
-fn generate_serialize_length(code: &mut String, field_name: String, length: &Length) {
+fn generate_serialize_length(code: &mut String, element: &StructElement, length: &Length) {
     let optional = matches!(length.optional, Some(true));
     let offset = length.offset.unwrap_or(0);
 
-    if optional {
-        code.push_str(&format!(
-            "        if let Some(length) = &self.{} {{\n",
-            length.name
-        ));
-    }
-
+    // the length is written as the i32 it is stored as so a positive offset can't underflow
     let offset_operation = match offset.cmp(&0) {
         std::cmp::Ordering::Less => format!(" + {}", offset.abs()),
         std::cmp::Ordering::Greater => format!(" - {}", offset.abs()),
         std::cmp::Ordering::Equal => "".to_owned(),
     };
 
+    let (value, is_array) = match element {
+        StructElement::Array(array) => (replace_keyword(&array.name), true),
+        StructElement::Field(field) => (replace_keyword(field.name.as_ref().unwrap()), false),
+        _ => unreachable!(),
+    };
+
+    // optional arrays are omitted when empty, optional fields when they are None
+    let length_value = if optional && is_array {
+        code.push_str(&format!("        if !self.{}.is_empty() {{\n", value));
+        format!("self.{}.len()", value)
+    } else if optional {
+        code.push_str(&format!(
+            "        if let Some({}) = self.{}.as_ref() {{\n",
+            value, value
+        ));
+        format!("{}.len()", value)
+    } else {
+        format!("self.{}.len()", value)
+    };
+
     code.push_str(&format!(
-        "        writer.add_{}((self.{}.len(){}) as i32){};\n",
+        "        writer.add_{}({} as i32{}){};\n",
         length.data_type,
-        field_name,
+        length_value,
         offset_operation,
         if needs_result(&length.data_type) {
             "?"
@@ -1201,34 +2239,52 @@ fn generate_deserialize_length(code: &mut String, length: &Length) {
     let optional = matches!(length.optional, Some(true));
     let offset = length.offset.unwrap_or(0);
 
-    if optional {
-        code.push_str("if reader.remaining() > 0 {{\n");
-    }
-
-    let offset_operation = match offset.cmp(&0) {
-        std::cmp::Ordering::Greater => format!(" + {}", offset.abs()),
-        std::cmp::Ordering::Less => format!(" - {}", offset.abs()),
-        _ => "".to_owned(),
+    // a negative offset is clamped so a malformed length can't wrap around to usize::MAX
+    let length_value = match offset.cmp(&0) {
+        std::cmp::Ordering::Greater => format!("reader.get_{}() + {}", length.data_type, offset),
+        std::cmp::Ordering::Less => format!(
+            "(reader.get_{}() - {}).max(0)",
+            length.data_type,
+            offset.abs()
+        ),
+        _ => format!("reader.get_{}()", length.data_type),
     };
 
-    code.push_str(&format!(
-        "        let {} = (reader.get_{}(){}) as usize;\n",
-        replace_keyword(&length.name),
-        length.data_type,
-        offset_operation,
-    ));
-
     if optional {
-        code.push_str("}\n");
+        code.push_str(&format!(
+            "        let {} = if reader.remaining() > 0 {{\n",
+            replace_keyword(&length.name)
+        ));
+        code.push_str(&format!("            ({}) as usize\n", length_value));
+        code.push_str("        } else {\n");
+        code.push_str("            0\n");
+        code.push_str("        };\n");
+    } else {
+        code.push_str(&format!(
+            "        let {} = ({}) as usize;\n",
+            replace_keyword(&length.name),
+            length_value,
+        ));
     }
 }
 
 fn generate_deserialize_field(
     code: &mut String,
+    struct_name: &str,
     field: &Field,
     enums: &[Enum],
     structs: &[Struct],
 ) {
+    if !is_static_length(&field.length) {
+        generate_string_length_check(
+            code,
+            struct_name,
+            field.name.as_deref(),
+            &field.data_type,
+            field.length.as_deref(),
+        );
+    }
+
     let optional = matches!(field.optional, Some(true));
     if optional {
         let name = match field.name {
@@ -1241,7 +2297,7 @@ fn generate_deserialize_field(
             replace_keyword(name)
         ));
         code.push_str("            Some(");
-        generate_inner_field_deserialize(code, field, enums, structs);
+        generate_inner_field_deserialize(code, struct_name, field, enums, structs);
         code.push_str(")\n");
         code.push_str("        } else {\n");
         code.push_str("            None\n");
@@ -1250,24 +2306,25 @@ fn generate_deserialize_field(
         if let Some(name) = &field.name {
             code.push_str(&format!("        data.{} = ", replace_keyword(name)));
         }
-        generate_inner_field_deserialize(code, field, enums, structs);
+        generate_inner_field_deserialize(code, struct_name, field, enums, structs);
         code.push_str(";\n");
     }
 }
 
 fn generate_deserialize_array(
     code: &mut String,
+    struct_name: &str,
     array: &Array,
     enums: &[Enum],
     structs: &[Struct],
 ) {
     let optional = matches!(array.optional, Some(true));
     if optional {
-        code.push_str("        if reader.remaining() > 0 {{\n");
-        generate_inner_array_deserialize(code, array, enums, structs);
+        code.push_str("        if reader.remaining() > 0 {\n");
+        generate_inner_array_deserialize(code, struct_name, array, enums, structs);
         code.push_str("        }\n");
     } else {
-        generate_inner_array_deserialize(code, array, enums, structs);
+        generate_inner_array_deserialize(code, struct_name, array, enums, structs);
     }
 }
 
@@ -1283,6 +2340,7 @@ fn generate_deserialize_switch(
         get_field_type(&switch_enum.data_type),
         replace_keyword(&switch.field)
     ));
+    let context = reader_error_context(struct_name, Some(&format!("{}_data", switch.field)));
     for case in switch.cases.iter().filter(|c| c.elements.is_some()) {
         match case.value {
             Some(ref value) => {
@@ -1293,25 +2351,28 @@ fn generate_deserialize_switch(
                     })
                 {
                     code.push_str(&format!(
-                        "            {} => Some({}::{}(EoSerialize::deserialize(reader)?)),\n",
+                        "            {} => Some({}::{}(EoSerialize::deserialize(reader){}?)),\n",
                         enum_value.value,
                         get_field_type(&format!("{}_{}_data", struct_name, switch.field)),
                         replace_keyword(value),
+                        context,
                     ));
                 } else {
                     code.push_str(&format!(
-                        "            {} => Some({}::{}(EoSerialize::deserialize(reader)?)),\n",
+                        "            {} => Some({}::{}(EoSerialize::deserialize(reader){}?)),\n",
                         value,
                         get_field_type(&format!("{}_{}_data", struct_name, switch.field)),
                         replace_keyword(value),
+                        context,
                     ));
                 }
             }
             None => match case.default {
                 Some(true) => {
                     code.push_str(&format!(
-                        "            _ => Some({}::Default(EoSerialize::deserialize(reader)?)),\n",
+                        "            _ => Some({}::Default(EoSerialize::deserialize(reader){}?)),\n",
                         get_field_type(&format!("{}_{}_data", struct_name, switch.field)),
+                        context,
                     ));
                 }
                 _ => panic!("Unnamed switch case with default=false"),
@@ -1327,6 +2388,7 @@ fn generate_deserialize_switch(
 
 fn generate_inner_field_deserialize(
     code: &mut String,
+    struct_name: &str,
     field: &Field,
     enums: &[Enum],
     structs: &[Struct],
@@ -1344,13 +2406,33 @@ fn generate_inner_field_deserialize(
         } else {
             enum_data_type.to_string()
         };
+        let name = get_field_type(data_type);
+        let value = match get_field_type(&protocol_enum.data_type).as_str() {
+            "i32" => "value",
+            _ => "i32::from(value)",
+        };
+        code.push_str("{\n");
+        code.push_str("            let position = reader.position();\n");
         code.push_str(&format!(
-            "{}::from(reader.get_{}())",
-            get_field_type(data_type),
-            enum_data_type,
+            "            let value = {}::from(reader.get_{}());\n",
+            name, enum_data_type,
+        ));
+        code.push_str(&format!(
+            "            if let {}::Unrecognized(value) = value {{\n",
+            name
+        ));
+        code.push_str(&format!(
+            "                reader.unrecognized_enum(position, \"{}\", {});\n",
+            name, value
         ));
+        code.push_str("            }\n");
+        code.push_str("            value\n");
+        code.push_str("        }");
     } else if structs.iter().any(|s| s.name == data_type) {
-        code.push_str("EoSerialize::deserialize(reader)?");
+        code.push_str(&format!(
+            "EoSerialize::deserialize(reader){}?",
+            reader_error_context(struct_name, field.name.as_deref())
+        ));
     } else if let Some(length) = &field.length {
         match data_type {
             "string" => code.push_str(&format!("        reader.get_fixed_string({})", length)),
@@ -1380,6 +2462,7 @@ fn generate_inner_field_deserialize(
 
 fn generate_inner_array_deserialize(
     code: &mut String,
+    struct_name: &str,
     array: &Array,
     enums: &[Enum],
     structs: &[Struct],
@@ -1388,7 +2471,54 @@ fn generate_inner_array_deserialize(
     let need_guard = !array.trailing_delimiter && array.length.is_some();
     let is_static_length = is_static_length(&array.length);
 
+    let context = reader_error_context(struct_name, Some(&array.name));
+
+    // arrays of structs are read with the EoReader array helpers
+    let is_struct = structs.iter().any(|s| s.name == array.data_type);
+    if is_struct && !is_static_length {
+        match (&array.length, delimited) {
+            (Some(length), false) => {
+                if let Some(size) = get_fixed_type_size(&array.data_type, structs, enums) {
+                    code.push_str(&format!(
+                        "        reader.check_remaining({}.saturating_mul({})){}?;\n",
+                        length, size, context
+                    ));
+                }
+                code.push_str(&format!(
+                    "        data.{} = reader.get_array({}){}?;\n",
+                    array.name, length, context
+                ));
+                return;
+            }
+            (None, true) => {
+                code.push_str(&format!(
+                    "        data.{} = reader.get_delimited_array(){}?;\n",
+                    array.name, context
+                ));
+                return;
+            }
+            _ => {}
+        }
+    }
+
     if let Some(length) = &array.length {
+        if !is_static_length {
+            code.push_str(&format!(
+                "        reader.check_array_length({}){}?;\n",
+                length, context
+            ));
+
+            // a count that needs more data than is left can only come from a corrupt or
+            // hostile length field
+            if let Some(size) =
+                get_fixed_type_size(&array.data_type, structs, enums).filter(|_| !delimited)
+            {
+                code.push_str(&format!(
+                    "        reader.check_remaining({}.saturating_mul({})){}?;\n",
+                    length, size, context
+                ));
+            }
+        }
         code.push_str(&format!(
             "        for {} in 0..{} {{\n",
             if need_guard || is_static_length {
@@ -1398,7 +2528,9 @@ fn generate_inner_array_deserialize(
             },
             length
         ));
-    } else if let Some(size) = get_fixed_type_size(&array.data_type, structs, enums) {
+    } else if let Some(size) =
+        get_fixed_type_size(&array.data_type, structs, enums).filter(|_| !delimited)
+    {
         if size == 1 {
             code.push_str("        let num_items = reader.remaining();\n");
         } else {
@@ -1407,11 +2539,21 @@ fn generate_inner_array_deserialize(
                 size
             ));
         }
+        code.push_str(&format!(
+            "        reader.check_array_length(num_items){}?;\n",
+            context
+        ));
         code.push_str("        for _ in 0..num_items {\n");
     } else {
         code.push_str("        while reader.remaining() > 0 {\n");
+        code.push_str(&format!(
+            "            reader.check_array_length(data.{}.len() + 1){}?;\n",
+            array.name, context
+        ));
     }
 
+    generate_string_length_check(code, struct_name, Some(&array.name), &array.data_type, None);
+
     if is_static_length {
         code.push_str(&format!("            data.{}[i] = ", array.name));
     } else {
@@ -1419,6 +2561,7 @@ fn generate_inner_array_deserialize(
     }
     generate_inner_field_deserialize(
         code,
+        struct_name,
         &Field {
             name: Some(array.name.clone()),
             data_type: array.data_type.clone(),
@@ -1444,10 +2587,16 @@ fn generate_inner_array_deserialize(
                 None => panic!("Array length is required for non trailing- delimited arrays!"),
             };
             code.push_str(&format!("            if i + 1 < {} {{\n", length));
-            code.push_str("                reader.next_chunk()?;\n");
+            code.push_str(&format!(
+                "                reader.next_chunk(){}?;\n",
+                reader_error_context(struct_name, Some(&array.name))
+            ));
             code.push_str("            }\n");
         } else {
-            code.push_str("            reader.next_chunk()?;\n");
+            code.push_str(&format!(
+                "            reader.next_chunk(){}?;\n",
+                reader_error_context(struct_name, Some(&array.name))
+            ));
         }
     }
 
@@ -1567,6 +2716,10 @@ fn write_struct_fields(
             }
             StructElement::Switch(switch) => {
                 field_count += 1;
+                code.push_str(&format!(
+                    "    /// Data associated with the value of the `{}` field\n",
+                    switch.field
+                ));
                 code.push_str(&format!(
                     "    pub {}_data: Option<{}>,\n",
                     replace_keyword(&switch.field),
@@ -1609,22 +2762,6 @@ fn get_field_type(data_type: &str) -> String {
     }
 }
 
-static PRIMITIVE_TYPES: [&str; 9] = [
-    "byte",
-    "char",
-    "short",
-    "three",
-    "int",
-    "bool",
-    "string",
-    "encoded_string",
-    "blob",
-];
-
-fn is_primitive(data_type: &str) -> bool {
-    PRIMITIVE_TYPES.contains(&data_type)
-}
-
 fn get_imports(elements: &[StructElement], protocols: &[(Protocol, PathBuf)]) -> Vec<String> {
     let mut imports = vec![
         "use crate::data::{EoReader, EoReaderError, EoWriter, EoSerialize, EoSerializeError};"
@@ -1710,15 +2847,11 @@ fn find_protocol_for_type<'a>(
     for (protocol, path) in protocols {
         for element in &protocol.elements {
             match element {
-                Element::Struct(protocol_struct) => {
-                    if protocol_struct.name == data_type {
-                        return Some(path);
-                    }
+                Element::Struct(protocol_struct) if protocol_struct.name == data_type => {
+                    return Some(path);
                 }
-                Element::Enum(protocol_enum) => {
-                    if protocol_enum.name == data_type {
-                        return Some(path);
-                    }
+                Element::Enum(protocol_enum) if protocol_enum.name == data_type => {
+                    return Some(path);
                 }
                 _ => {}
             }
@@ -1756,7 +2889,12 @@ fn find_unique_types(elements: &[StructElement], unique_types: &mut HashSet<Stri
     }
 }
 fn get_comments(comment: &str) -> Vec<&str> {
-    comment.split('\n').map(|c| c.trim()).collect::<Vec<&str>>()
+    // the XML indentation around multi-line comments leaves blank lines at either end
+    comment
+        .trim()
+        .split('\n')
+        .map(|c| c.trim())
+        .collect::<Vec<&str>>()
 }
 
 fn append_doc_comments(code: &mut String, comments: Vec<&str>) {
@@ -1765,6 +2903,95 @@ fn append_doc_comments(code: &mut String, comments: Vec<&str>) {
     }
 }
 
+// Custom protocol files mirror the eo-protocol/xml layout and are merged into the file at the
+// same path, so their types end up in the same module. Values of an enum that already exists
+// are appended to it, everything else is added alongside the existing elements. With `replace`
+// (protocol overrides), an element with the same name or family and action replaces the
+// existing one instead
+fn merge_extra_protocols(protocols: &mut [(Protocol, PathBuf)], extra_dir: &Path, replace: bool) {
+    let pattern = extra_dir.join("**").join("protocol.xml");
+    for entry in glob(&pattern.to_string_lossy()).expect("Failed to read glob pattern") {
+        let path = match entry {
+            Ok(path) => path,
+            Err(e) => panic!("Failed to read protocol file: {}", e),
+        };
+
+        let extra = match parse_protocol_file(&path) {
+            Ok(protocol) => protocol,
+            Err(e) => panic!(
+                "Failed to parse protocol file: {}. {}",
+                e,
+                path.to_string_lossy()
+            ),
+        };
+
+        let target = Path::new("eo-protocol/xml").join(path.strip_prefix(extra_dir).unwrap());
+        let protocol = match protocols.iter_mut().find(|(_, path)| *path == target) {
+            Some((protocol, _)) => protocol,
+            None => panic!(
+                "No eo-protocol file matches custom protocol file {}",
+                path.to_string_lossy()
+            ),
+        };
+
+        for element in extra.elements {
+            merge_element(protocol, element, &path, replace);
+        }
+    }
+}
+
+fn merge_element(protocol: &mut Protocol, element: Element, path: &Path, replace: bool) {
+    let existing = protocol.elements.iter_mut().find(|e| match (e, &element) {
+        (Element::Enum(a), Element::Enum(b)) => a.name == b.name,
+        (Element::Struct(a), Element::Struct(b)) => a.name == b.name,
+        (Element::Packet(a), Element::Packet(b)) => a.family == b.family && a.action == b.action,
+        _ => false,
+    });
+
+    match (existing, element) {
+        (None, element) => protocol.elements.push(element),
+        (Some(existing), element) if replace => *existing = element,
+        (Some(Element::Enum(existing)), Element::Enum(extra)) => {
+            // a repeated name or value would generate duplicate variants or match arms
+            for element in extra.elements {
+                if let EnumElement::Value(value) = &element {
+                    let duplicate = existing.elements.iter().find_map(|e| match e {
+                        EnumElement::Value(v) if v.name == value.name || v.value == value.value => {
+                            Some(v)
+                        }
+                        _ => None,
+                    });
+                    if let Some(duplicate) = duplicate {
+                        panic!(
+                            "Custom enum value {}::{} = {} conflicts with {}::{} = {}! {}",
+                            existing.name,
+                            value.name,
+                            value.value,
+                            existing.name,
+                            duplicate.name,
+                            duplicate.value,
+                            path.to_string_lossy()
+                        );
+                    }
+                }
+                existing.elements.push(element);
+            }
+        }
+        (Some(Element::Struct(existing)), _) => panic!(
+            "Custom struct {} is already defined! {}",
+            existing.name,
+            path.to_string_lossy()
+        ),
+        (Some(Element::Packet(existing)), _) => panic!(
+            "Custom packet {}_{} is already defined! {}",
+            existing.family,
+            existing.action,
+            path.to_string_lossy()
+        ),
+        _ => unreachable!(),
+    }
+}
+
 fn get_output_directory(base: &Path) -> PathBuf {
     let out_dir = std::env::var_os("OUT_DIR").unwrap();
     Path::new(&out_dir).join(
@@ -1799,6 +3026,5 @@ fn parse_protocol_file(path: &std::path::Path) -> Result<Protocol, Box<dyn std::
     let mut file = File::open(path)?;
     let mut xml = String::new();
     file.read_to_string(&mut xml)?;
-    let protocol: Protocol = quick_xml::de::from_str(&xml)?;
-    Ok(protocol)
+    Ok(parse_protocol(&xml)?)
 }
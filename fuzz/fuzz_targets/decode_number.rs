@@ -0,0 +1,19 @@
+#![no_main]
+
+use eolib::data::{decode_number, decode_number_checked, decode_number_i64, encode_number};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let bytes = &data[..data.len().min(4)];
+    let number = decode_number(bytes);
+
+    // numbers that pass the checked decode must be read the same by every decoder and
+    // survive being encoded again
+    if let Ok(checked) = decode_number_checked(bytes) {
+        assert_eq!(number, checked);
+        assert_eq!(decode_number_i64(bytes), checked as i64);
+
+        let encoded = encode_number(checked).unwrap();
+        assert_eq!(decode_number(&encoded), checked);
+    }
+});
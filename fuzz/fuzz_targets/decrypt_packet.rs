@@ -0,0 +1,20 @@
+#![no_main]
+
+use eolib::encrypt::{decrypt_packet, encrypt_packet, SWAP_MULTIPLES};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    for swap_multiple in SWAP_MULTIPLES {
+        let mut buf = data.to_vec();
+        decrypt_packet(&mut buf, swap_multiple);
+        assert_eq!(buf.len(), data.len());
+
+        // packets starting with 0xFF 0xFF are Init_Init packets and are never encrypted
+        if buf.starts_with(&[0xff, 0xff]) && !data.starts_with(&[0xff, 0xff]) {
+            continue;
+        }
+
+        encrypt_packet(&mut buf, swap_multiple);
+        assert_eq!(buf, data, "swap multiple {}", swap_multiple);
+    }
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use eolib::{
+    data::{EoReader, EoSerialize},
+    protocol::map::Emf,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let emf = match Emf::deserialize(&EoReader::from_slice(data)) {
+        Ok(emf) => emf,
+        Err(_) => return,
+    };
+
+    // a map that was read must be written the same way every time
+    let bytes = match emf.to_bytes() {
+        Ok(bytes) => bytes,
+        Err(_) => return,
+    };
+    assert_eq!(emf.encoded_size(), bytes.len());
+
+    let reread = Emf::from_bytes(&bytes).unwrap();
+    assert_eq!(reread.to_bytes().unwrap(), bytes);
+});
@@ -0,0 +1,65 @@
+#![no_main]
+
+use eolib::data::EoReader;
+use libfuzzer_sys::fuzz_target;
+
+// the first byte is how many of the following bytes are reader operations, the rest is the
+// data being read
+fuzz_target!(|data: &[u8]| {
+    let (ops, input) = match data.split_first() {
+        Some((count, rest)) => rest.split_at((*count as usize).min(rest.len())),
+        None => return,
+    };
+
+    let reader = EoReader::from_slice(input);
+    reader.set_chunked_reading_mode(true);
+
+    for op in ops {
+        match op % 12 {
+            0 => {
+                reader.get_byte();
+            }
+            1 => {
+                reader.get_char();
+            }
+            2 => {
+                reader.get_short();
+            }
+            3 => {
+                reader.get_three();
+            }
+            4 => {
+                reader.get_int();
+            }
+            5 => {
+                reader.get_string();
+            }
+            6 => {
+                reader.get_encoded_string();
+            }
+            7 => {
+                reader.get_fixed_string((op / 12) as usize);
+            }
+            8 => {
+                let _ = reader.try_get_fixed_encoded_string((op / 12) as usize);
+            }
+            9 => {
+                let _ = reader.next_chunk();
+            }
+            10 => {
+                let _ = reader.next_chunk_or_end();
+            }
+            _ => reader.set_chunked_reading_mode(!reader.get_chunked_reading_mode()),
+        }
+
+        assert!(reader.position() <= input.len());
+        assert!(reader.remaining() <= input.len() - reader.position());
+    }
+
+    // chunks can be read from wherever the reader stopped
+    let position = reader.position();
+    for chunk in reader.chunks() {
+        assert!(chunk.as_bytes().len() <= input.len() - position);
+    }
+    assert_eq!(reader.remaining(), 0);
+});
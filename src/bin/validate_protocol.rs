@@ -0,0 +1,21 @@
+use std::process::ExitCode;
+
+use eolib::protocol_schema::validate;
+
+fn main() -> ExitCode {
+    let path = std::env::args_os()
+        .nth(1)
+        .unwrap_or_else(|| "eo-protocol/xml".into());
+
+    let errors = validate(&path);
+    if errors.is_empty() {
+        println!("{} is valid", path.to_string_lossy());
+        return ExitCode::SUCCESS;
+    }
+
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+    eprintln!("found {} problem(s)", errors.len());
+    ExitCode::FAILURE
+}
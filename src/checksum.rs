@@ -0,0 +1,395 @@
+use crate::{
+    data::{EoSerialize, EoSerializeError, SHORT_MAX},
+    protocol::{
+        map::Emf,
+        net::server::WelcomeReplyServerPacketWelcomeCodeDataSelectCharacter,
+        r#pub::{Ecf, Eif, Enf, Esf},
+    },
+};
+
+// reversed 0x04C11DB7, the polynomial used by zlib
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+const TABLE: [u32; 256] = make_table();
+
+const fn make_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// returns the CRC-32 checksum of `data`
+///
+/// this is the common (zlib, PNG) variant: reflected, polynomial `0x04C11DB7`, starting
+/// and final xor values of `0xFFFFFFFF`
+///
+/// # Examples
+///
+/// ```
+/// use eolib::checksum::crc32;
+///
+/// assert_eq!(crc32(b"123456789"), 0xCBF43926);
+/// ```
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, byte| {
+        TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// splits a checksum into the two shorts of a pub file rid
+///
+/// the checksum is written as a two digit number in base [SHORT_MAX] so each half fits in an
+/// EO short. the client only compares the rid with the one the server sends, so any stable
+/// value works, but this one changes whenever the file does.
+pub fn checksum_to_rid(checksum: u32) -> [i32; 2] {
+    let short_max = SHORT_MAX as u32;
+    let checksum = checksum % (short_max * short_max);
+    [(checksum / short_max) as i32, (checksum % short_max) as i32]
+}
+
+/// A pub file with a rid in its header
+pub trait PubFile: EoSerialize {
+    /// returns the rid of the file
+    fn rid(&self) -> [i32; 2];
+    /// sets the rid of the file
+    fn set_rid(&mut self, rid: [i32; 2]);
+    /// returns the number of records in the file
+    fn total_records(&self) -> i32;
+
+    /// maps the pub file at `path` into memory without parsing it, see
+    /// [MappedFile](crate::mmap::MappedFile)
+    ///
+    /// # Safety
+    ///
+    /// the file must not be modified or truncated while it is mapped, see
+    /// [MappedFile::open](crate::mmap::MappedFile::open)
+    #[cfg(feature = "mmap")]
+    unsafe fn open_readonly(
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<crate::mmap::MappedFile<Self>> {
+        crate::mmap::MappedFile::open(path)
+    }
+}
+
+macro_rules! impl_pub_file {
+    ($($file:ty => $total:ident),*) => {
+        $(
+            impl PubFile for $file {
+                fn rid(&self) -> [i32; 2] {
+                    self.rid
+                }
+
+                fn set_rid(&mut self, rid: [i32; 2]) {
+                    self.rid = rid;
+                }
+
+                fn total_records(&self) -> i32 {
+                    self.$total
+                }
+            }
+        )*
+    };
+}
+
+impl_pub_file!(
+    Eif => total_items_count,
+    Enf => total_npcs_count,
+    Esf => total_skills_count,
+    Ecf => total_classes_count
+);
+
+/// sets the rid of a pub file from the checksum of its contents
+///
+/// the checksum is taken with the rid set to `[0, 0]` so calling this again on an unchanged
+/// file gives the same rid
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     checksum::{update_rid, PubFile},
+///     protocol::r#pub::Eif,
+/// };
+///
+/// let mut eif = Eif::default();
+/// update_rid(&mut eif).unwrap();
+/// let rid = eif.rid();
+///
+/// eif.version = 1;
+/// update_rid(&mut eif).unwrap();
+/// assert_ne!(eif.rid(), rid);
+/// ```
+pub fn update_rid<T: PubFile>(file: &mut T) -> Result<(), EoSerializeError> {
+    file.set_rid([0, 0]);
+    let bytes = file.to_bytes()?;
+    file.set_rid(checksum_to_rid(crc32(&bytes)));
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// The rid and length of a file, which the client compares with its own copy to decide
+/// whether to download the file again
+pub struct FileVersion {
+    pub rid: [i32; 2],
+    /// the size of a map file in bytes, or the number of records in a pub file
+    pub length: i32,
+}
+
+impl FileVersion {
+    /// returns the version of a map file
+    pub fn of_map(emf: &Emf) -> Result<Self, EoSerializeError> {
+        Ok(Self {
+            rid: emf.rid,
+            length: emf.to_bytes()?.len() as i32,
+        })
+    }
+
+    /// returns the version of a pub file
+    pub fn of_pub_file<T: PubFile>(file: &T) -> Self {
+        Self {
+            rid: file.rid(),
+            length: file.total_records(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A file the server sends the version of when a character is selected
+pub enum WelcomeFile {
+    Map,
+    Eif,
+    Enf,
+    Esf,
+    Ecf,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// The versions of the map and pub files sent in the WELCOME_REPLY packet
+///
+/// The server builds them from its files with [new](FileVersions::new) and writes them into
+/// the reply. The client reads them back with [from_welcome](FileVersions::from_welcome) and
+/// downloads every file [outdated](FileVersions::outdated) returns.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     checksum::{FileVersion, FileVersions, WelcomeFile},
+///     protocol::{
+///         map::Emf,
+///         net::server::WelcomeReplyServerPacketWelcomeCodeDataSelectCharacter,
+///         r#pub::{Ecf, Eif, Enf, Esf},
+///     },
+/// };
+///
+/// let emf = Emf::default();
+/// let eif = Eif {
+///     rid: [1, 2],
+///     total_items_count: 400,
+///     ..Default::default()
+/// };
+///
+/// // server
+/// let versions = FileVersions::new(
+///     &emf,
+///     &eif,
+///     &Enf::default(),
+///     &Esf::default(),
+///     &Ecf::default(),
+/// )
+/// .unwrap();
+/// let mut reply = WelcomeReplyServerPacketWelcomeCodeDataSelectCharacter::default();
+/// versions.write_welcome(&mut reply);
+///
+/// // client, with an older item file
+/// let local = FileVersions {
+///     eif: FileVersion {
+///         rid: [1, 1],
+///         length: 399,
+///     },
+///     ..versions
+/// };
+/// assert_eq!(
+///     FileVersions::from_welcome(&reply).outdated(&local),
+///     [WelcomeFile::Eif]
+/// );
+/// ```
+pub struct FileVersions {
+    pub map: FileVersion,
+    pub eif: FileVersion,
+    pub enf: FileVersion,
+    pub esf: FileVersion,
+    pub ecf: FileVersion,
+}
+
+impl FileVersions {
+    /// returns the versions of the files
+    ///
+    /// fails if the map file can't be serialized to find its size
+    pub fn new(
+        emf: &Emf,
+        eif: &Eif,
+        enf: &Enf,
+        esf: &Esf,
+        ecf: &Ecf,
+    ) -> Result<Self, EoSerializeError> {
+        Ok(Self {
+            map: FileVersion::of_map(emf)?,
+            eif: FileVersion::of_pub_file(eif),
+            enf: FileVersion::of_pub_file(enf),
+            esf: FileVersion::of_pub_file(esf),
+            ecf: FileVersion::of_pub_file(ecf),
+        })
+    }
+
+    /// returns the versions sent in a WELCOME_REPLY packet
+    pub fn from_welcome(data: &WelcomeReplyServerPacketWelcomeCodeDataSelectCharacter) -> Self {
+        Self {
+            map: FileVersion {
+                rid: data.map_rid,
+                length: data.map_file_size,
+            },
+            eif: FileVersion {
+                rid: data.eif_rid,
+                length: data.eif_length,
+            },
+            enf: FileVersion {
+                rid: data.enf_rid,
+                length: data.enf_length,
+            },
+            esf: FileVersion {
+                rid: data.esf_rid,
+                length: data.esf_length,
+            },
+            ecf: FileVersion {
+                rid: data.ecf_rid,
+                length: data.ecf_length,
+            },
+        }
+    }
+
+    /// sets the file versions of a WELCOME_REPLY packet
+    pub fn write_welcome(&self, data: &mut WelcomeReplyServerPacketWelcomeCodeDataSelectCharacter) {
+        data.map_rid = self.map.rid;
+        data.map_file_size = self.map.length;
+        data.eif_rid = self.eif.rid;
+        data.eif_length = self.eif.length;
+        data.enf_rid = self.enf.rid;
+        data.enf_length = self.enf.length;
+        data.esf_rid = self.esf.rid;
+        data.esf_length = self.esf.length;
+        data.ecf_rid = self.ecf.rid;
+        data.ecf_length = self.ecf.length;
+    }
+
+    /// returns the files whose versions don't match `local`, in the order the client
+    /// requests them
+    pub fn outdated(&self, local: &FileVersions) -> Vec<WelcomeFile> {
+        [
+            (WelcomeFile::Map, self.map, local.map),
+            (WelcomeFile::Eif, self.eif, local.eif),
+            (WelcomeFile::Enf, self.enf, local.enf),
+            (WelcomeFile::Esf, self.esf, local.esf),
+            (WelcomeFile::Ecf, self.ecf, local.ecf),
+        ]
+        .into_iter()
+        .filter(|(_, server, local)| server != local)
+        .map(|(file, _, _)| file)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        checksum_to_rid, crc32, update_rid, FileVersion, FileVersions, PubFile, WelcomeFile,
+    };
+    use crate::{
+        data::{EoSerialize, SHORT_MAX},
+        protocol::{map::Emf, r#pub::Enf},
+    };
+
+    #[test]
+    fn crc32_check_values() {
+        assert_eq!(crc32(&[]), 0);
+        assert_eq!(crc32(b"a"), 0xE8B7BE43);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414FA339
+        );
+    }
+
+    #[test]
+    fn rid_fits_in_shorts() {
+        for checksum in [0, 1, SHORT_MAX as u32, u32::MAX] {
+            let [high, low] = checksum_to_rid(checksum);
+            assert!((0..SHORT_MAX).contains(&high));
+            assert!((0..SHORT_MAX).contains(&low));
+        }
+        assert_eq!(checksum_to_rid(SHORT_MAX as u32 + 5), [1, 5]);
+    }
+
+    #[test]
+    fn update_rid_is_stable() {
+        let mut enf = Enf {
+            rid: [1, 2],
+            version: 3,
+            ..Default::default()
+        };
+        update_rid(&mut enf).unwrap();
+        let rid = enf.rid();
+
+        update_rid(&mut enf).unwrap();
+        assert_eq!(enf.rid(), rid);
+    }
+
+    #[test]
+    fn map_version_is_file_size() {
+        let emf = Emf {
+            rid: [3, 4],
+            width: 10,
+            ..Default::default()
+        };
+        let version = FileVersion::of_map(&emf).unwrap();
+        assert_eq!(version.rid, [3, 4]);
+        assert_eq!(version.length, emf.to_bytes().unwrap().len() as i32);
+    }
+
+    #[test]
+    fn outdated_files() {
+        let server = FileVersions {
+            enf: FileVersion::of_pub_file(&Enf {
+                total_npcs_count: 5,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(server.outdated(&server).is_empty());
+
+        let local = FileVersions {
+            map: FileVersion {
+                rid: [0, 1],
+                length: 0,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            server.outdated(&local),
+            [WelcomeFile::Map, WelcomeFile::Enf]
+        );
+    }
+}
@@ -0,0 +1,2 @@
+mod world_view;
+pub use world_view::WorldView;
@@ -0,0 +1,344 @@
+use crate::protocol::net::{
+    server::{
+        AvatarRemoveServerPacket, ItemRemoveServerPacket, NpcKilledData, NpcSpecServerPacket,
+        PlayersAgreeServerPacket, RefreshReplyServerPacket, WarpAgreeServerPacket,
+    },
+    CharacterMapInfo, ItemMapInfo, NearbyInfo, NpcMapInfo,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// The characters, NPCs and items a client can currently see
+///
+/// Characters are keyed by player id, NPCs by map index and items by unique id. Applying an
+/// entity that is already in view replaces it, and the remove packets take them out again.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     client::WorldView,
+///     protocol::net::{server::RefreshReplyServerPacket, CharacterMapInfo, NearbyInfo},
+/// };
+///
+/// let mut view = WorldView::new();
+/// view.apply_refresh(&RefreshReplyServerPacket {
+///     nearby: NearbyInfo {
+///         characters: vec![CharacterMapInfo {
+///             name: "vulture".to_string(),
+///             player_id: 3,
+///             ..Default::default()
+///         }],
+///         ..Default::default()
+///     },
+/// });
+///
+/// assert_eq!(view.character(3).unwrap().name, "vulture");
+///
+/// view.remove_character(3);
+/// assert!(view.characters().is_empty());
+/// ```
+pub struct WorldView {
+    characters: Vec<CharacterMapInfo>,
+    npcs: Vec<NpcMapInfo>,
+    items: Vec<ItemMapInfo>,
+}
+
+impl WorldView {
+    /// creates a new, empty [WorldView]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// returns the characters in view
+    pub fn characters(&self) -> &[CharacterMapInfo] {
+        &self.characters
+    }
+
+    /// returns the NPCs in view
+    pub fn npcs(&self) -> &[NpcMapInfo] {
+        &self.npcs
+    }
+
+    /// returns the items in view
+    pub fn items(&self) -> &[ItemMapInfo] {
+        &self.items
+    }
+
+    /// returns the character with the specified player id
+    pub fn character(&self, player_id: i32) -> Option<&CharacterMapInfo> {
+        self.characters.iter().find(|c| c.player_id == player_id)
+    }
+
+    /// returns the NPC with the specified map index
+    pub fn npc(&self, index: i32) -> Option<&NpcMapInfo> {
+        self.npcs.iter().find(|n| n.index == index)
+    }
+
+    /// returns the item with the specified unique id
+    pub fn item(&self, uid: i32) -> Option<&ItemMapInfo> {
+        self.items.iter().find(|i| i.uid == uid)
+    }
+
+    /// replaces everything in view with the entities from a WARP_AGREE packet
+    pub fn apply_warp(&mut self, packet: &WarpAgreeServerPacket) {
+        self.clear();
+        self.apply_nearby(&packet.nearby);
+    }
+
+    /// replaces everything in view with the entities from a REFRESH_REPLY packet
+    pub fn apply_refresh(&mut self, packet: &RefreshReplyServerPacket) {
+        self.clear();
+        self.apply_nearby(&packet.nearby);
+    }
+
+    /// adds the entities from a PLAYERS_AGREE packet to the view
+    pub fn apply_players_agree(&mut self, packet: &PlayersAgreeServerPacket) {
+        self.apply_nearby(&packet.nearby);
+    }
+
+    /// adds nearby entities to the view, replacing any that are already in it
+    pub fn apply_nearby(&mut self, nearby: &NearbyInfo) {
+        for character in &nearby.characters {
+            upsert(&mut self.characters, character, |c| c.player_id);
+        }
+
+        for npc in &nearby.npcs {
+            upsert(&mut self.npcs, npc, |n| n.index);
+        }
+
+        for item in &nearby.items {
+            upsert(&mut self.items, item, |i| i.uid);
+        }
+    }
+
+    /// removes the character that left view in an AVATAR_REMOVE packet and returns it
+    pub fn apply_avatar_remove(
+        &mut self,
+        packet: &AvatarRemoveServerPacket,
+    ) -> Option<CharacterMapInfo> {
+        self.remove_character(packet.player_id)
+    }
+
+    /// removes the item taken off the ground in an ITEM_REMOVE packet and returns it
+    pub fn apply_item_remove(&mut self, packet: &ItemRemoveServerPacket) -> Option<ItemMapInfo> {
+        self.remove_item(packet.item_index)
+    }
+
+    /// removes the NPC killed in an NPC_SPEC packet and returns it, see
+    /// [apply_npc_killed](WorldView::apply_npc_killed)
+    pub fn apply_npc_spec(&mut self, packet: &NpcSpecServerPacket) -> Option<NpcMapInfo> {
+        self.apply_npc_killed(&packet.npc_killed_data)
+    }
+
+    /// removes a killed NPC and returns it, adding the item it dropped if there is one
+    ///
+    /// for the packets that report kills with [NpcKilledData], like NPC_SPEC and NPC_ACCEPT
+    pub fn apply_npc_killed(&mut self, killed: &NpcKilledData) -> Option<NpcMapInfo> {
+        if killed.drop_id != 0 {
+            let drop = ItemMapInfo {
+                uid: killed.drop_index,
+                id: killed.drop_id,
+                coords: killed.drop_coords,
+                amount: killed.drop_amount,
+            };
+            upsert(&mut self.items, &drop, |i| i.uid);
+        }
+
+        self.remove_npc(killed.npc_index)
+    }
+
+    /// removes a character from the view and returns it
+    pub fn remove_character(&mut self, player_id: i32) -> Option<CharacterMapInfo> {
+        remove(&mut self.characters, |c| c.player_id == player_id)
+    }
+
+    /// removes an NPC from the view and returns it
+    pub fn remove_npc(&mut self, index: i32) -> Option<NpcMapInfo> {
+        remove(&mut self.npcs, |n| n.index == index)
+    }
+
+    /// removes an item from the view and returns it
+    pub fn remove_item(&mut self, uid: i32) -> Option<ItemMapInfo> {
+        remove(&mut self.items, |i| i.uid == uid)
+    }
+
+    /// removes everything from the view
+    pub fn clear(&mut self) {
+        self.characters.clear();
+        self.npcs.clear();
+        self.items.clear();
+    }
+}
+
+fn upsert<T: Clone>(entities: &mut Vec<T>, entity: &T, key: impl Fn(&T) -> i32) {
+    match entities.iter_mut().find(|e| key(e) == key(entity)) {
+        Some(existing) => *existing = entity.clone(),
+        None => entities.push(entity.clone()),
+    }
+}
+
+fn remove<T>(entities: &mut Vec<T>, matches: impl Fn(&T) -> bool) -> Option<T> {
+    let index = entities.iter().position(matches)?;
+    Some(entities.remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WorldView;
+    use crate::protocol::{
+        net::{
+            server::{
+                AvatarRemoveServerPacket, ItemRemoveServerPacket, NpcKilledData,
+                NpcSpecServerPacket, PlayersAgreeServerPacket, RefreshReplyServerPacket,
+                WarpAgreeServerPacket,
+            },
+            CharacterMapInfo, ItemMapInfo, NearbyInfo, NpcMapInfo,
+        },
+        Coords,
+    };
+
+    fn character(player_id: i32, name: &str) -> CharacterMapInfo {
+        CharacterMapInfo {
+            name: name.to_string(),
+            player_id,
+            ..Default::default()
+        }
+    }
+
+    fn nearby() -> NearbyInfo {
+        NearbyInfo {
+            characters: vec![character(1, "alice"), character(2, "bob")],
+            npcs: vec![NpcMapInfo {
+                index: 1,
+                id: 170,
+                ..Default::default()
+            }],
+            items: vec![ItemMapInfo {
+                uid: 5,
+                id: 1,
+                coords: Coords { x: 3, y: 4 },
+                amount: 100,
+            }],
+        }
+    }
+
+    #[test]
+    fn refresh_replaces_view() {
+        let mut view = WorldView::new();
+        view.apply_refresh(&RefreshReplyServerPacket { nearby: nearby() });
+        view.apply_refresh(&RefreshReplyServerPacket {
+            nearby: NearbyInfo {
+                characters: vec![character(3, "carol")],
+                ..Default::default()
+            },
+        });
+
+        assert_eq!(view.characters(), [character(3, "carol")]);
+        assert!(view.npcs().is_empty());
+        assert!(view.items().is_empty());
+    }
+
+    #[test]
+    fn warp_replaces_view() {
+        let mut view = WorldView::new();
+        view.apply_refresh(&RefreshReplyServerPacket { nearby: nearby() });
+        view.apply_warp(&WarpAgreeServerPacket {
+            nearby: NearbyInfo::default(),
+            ..Default::default()
+        });
+
+        assert_eq!(view, WorldView::new());
+    }
+
+    #[test]
+    fn players_agree_merges() {
+        let mut view = WorldView::new();
+        view.apply_refresh(&RefreshReplyServerPacket { nearby: nearby() });
+        view.apply_players_agree(&PlayersAgreeServerPacket {
+            nearby: NearbyInfo {
+                characters: vec![character(2, "robert"), character(4, "dave")],
+                ..Default::default()
+            },
+        });
+
+        let names: Vec<_> = view.characters().iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["alice", "robert", "dave"]);
+        assert_eq!(view.npcs().len(), 1);
+        assert_eq!(view.item(5).map(|i| i.amount), Some(100));
+    }
+
+    #[test]
+    fn remove() {
+        let mut view = WorldView::new();
+        view.apply_nearby(&nearby());
+
+        assert_eq!(view.remove_character(1), Some(character(1, "alice")));
+        assert_eq!(view.remove_character(1), None);
+        assert_eq!(view.remove_npc(1).map(|n| n.id), Some(170));
+        assert_eq!(view.remove_item(5).map(|i| i.id), Some(1));
+
+        assert_eq!(view.characters(), [character(2, "bob")]);
+        assert!(view.npc(1).is_none());
+        assert!(view.item(5).is_none());
+    }
+
+    #[test]
+    fn remove_packets() {
+        let mut view = WorldView::new();
+        view.apply_nearby(&nearby());
+
+        let removed = view.apply_avatar_remove(&AvatarRemoveServerPacket {
+            player_id: 2,
+            ..Default::default()
+        });
+        assert_eq!(removed, Some(character(2, "bob")));
+
+        let removed = view.apply_item_remove(&ItemRemoveServerPacket { item_index: 5 });
+        assert_eq!(removed.map(|i| i.id), Some(1));
+        assert_eq!(
+            view.apply_item_remove(&ItemRemoveServerPacket { item_index: 5 }),
+            None
+        );
+
+        assert_eq!(view.characters(), [character(1, "alice")]);
+        assert!(view.items().is_empty());
+    }
+
+    #[test]
+    fn killed_npcs_leave_their_drop() {
+        let mut view = WorldView::new();
+        view.apply_nearby(&nearby());
+
+        let killed = view.apply_npc_spec(&NpcSpecServerPacket {
+            npc_killed_data: NpcKilledData {
+                npc_index: 1,
+                drop_index: 6,
+                drop_id: 2,
+                drop_coords: Coords { x: 7, y: 8 },
+                drop_amount: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert_eq!(killed.map(|n| n.id), Some(170));
+        assert!(view.npcs().is_empty());
+        assert_eq!(
+            view.item(6),
+            Some(&ItemMapInfo {
+                uid: 6,
+                id: 2,
+                coords: Coords { x: 7, y: 8 },
+                amount: 3,
+            })
+        );
+
+        // no drop
+        view.apply_nearby(&nearby());
+        view.apply_npc_killed(&NpcKilledData {
+            npc_index: 1,
+            ..Default::default()
+        });
+        assert!(view.npcs().is_empty());
+        assert_eq!(view.items().len(), 2);
+    }
+}
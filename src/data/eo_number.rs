@@ -0,0 +1,95 @@
+use super::{EoReader, EoWriter, EoWriterError, CHAR_SIZE, INT_SIZE, SHORT_SIZE, THREE_SIZE};
+
+/// An EO integer type, used to pick the width of a number in generic code
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::{EoChar, EoNumber, EoReader, EoShort, EoWriter, EoWriterError};
+///
+/// fn add_ids<N: EoNumber>(writer: &mut EoWriter, ids: &[u16]) -> Result<(), EoWriterError> {
+///     for id in ids {
+///         writer.add::<N>(*id)?;
+///     }
+///     Ok(())
+/// }
+///
+/// let mut writer = EoWriter::new();
+/// add_ids::<EoChar>(&mut writer, &[1, 2]).unwrap();
+/// add_ids::<EoShort>(&mut writer, &[300]).unwrap();
+///
+/// let reader = EoReader::new(writer.to_byte_array());
+/// assert_eq!(reader.get::<EoChar>(), 1);
+/// assert_eq!(reader.get::<EoChar>(), 2);
+/// assert_eq!(reader.get::<EoShort>(), 300);
+/// ```
+pub trait EoNumber {
+    /// number of bytes the number takes up in the data stream
+    const SIZE: usize;
+
+    /// adds a number of this type to the data stream
+    fn write(writer: &mut EoWriter, number: i32) -> Result<(), EoWriterError>;
+
+    /// returns a number of this type from the data stream
+    fn read(reader: &EoReader) -> i32;
+}
+
+/// A 1-byte EO integer
+pub enum EoChar {}
+
+/// A 2-byte EO integer
+pub enum EoShort {}
+
+/// A 3-byte EO integer
+pub enum EoThree {}
+
+/// A 4-byte EO integer
+pub enum EoInt {}
+
+impl EoNumber for EoChar {
+    const SIZE: usize = CHAR_SIZE;
+
+    fn write(writer: &mut EoWriter, number: i32) -> Result<(), EoWriterError> {
+        writer.add_char(number)
+    }
+
+    fn read(reader: &EoReader) -> i32 {
+        reader.get_char()
+    }
+}
+
+impl EoNumber for EoShort {
+    const SIZE: usize = SHORT_SIZE;
+
+    fn write(writer: &mut EoWriter, number: i32) -> Result<(), EoWriterError> {
+        writer.add_short(number)
+    }
+
+    fn read(reader: &EoReader) -> i32 {
+        reader.get_short()
+    }
+}
+
+impl EoNumber for EoThree {
+    const SIZE: usize = THREE_SIZE;
+
+    fn write(writer: &mut EoWriter, number: i32) -> Result<(), EoWriterError> {
+        writer.add_three(number)
+    }
+
+    fn read(reader: &EoReader) -> i32 {
+        reader.get_three()
+    }
+}
+
+impl EoNumber for EoInt {
+    const SIZE: usize = INT_SIZE;
+
+    fn write(writer: &mut EoWriter, number: i32) -> Result<(), EoWriterError> {
+        writer.add_int(number)
+    }
+
+    fn read(reader: &EoReader) -> i32 {
+        reader.get_int()
+    }
+}
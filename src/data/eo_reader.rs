@@ -1,25 +1,296 @@
-use std::{cell::Cell, cmp};
+use std::{
+    cell::Cell,
+    cmp, fmt, io,
+    ops::{Bound, RangeBounds},
+    str,
+};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use encoding_rs::WINDOWS_1252;
 use thiserror::Error;
 
-use super::{decode_number, decode_string};
+use super::{
+    decode_number, decode_number_checked, decode_string, DecodeError, EoNumber, EoSerialize,
+    StrictnessProfile,
+};
+
+// bytes either side of the reader position included in error contexts
+const SNIPPET_RADIUS: usize = 8;
 
 #[derive(Error, Debug)]
 pub enum EoReaderError {
-    #[error("Chunked reading mode is disabled")]
-    ChunkedReadingDisabled,
-    #[error("{0}")]
-    Other(String),
+    #[error("Chunked reading mode is disabled ({0})")]
+    ChunkedReadingDisabled(ReaderContext),
+    #[error("Fixed encoded string of length {0} is not terminated by a break and padding ({1})")]
+    InvalidStringPadding(usize, ReaderContext),
+    #[error("String of length {0} is longer than the limit of {1} ({2})")]
+    StringTooLong(usize, usize, ReaderContext),
+    #[error("Array of length {0} is longer than the limit of {1} ({2})")]
+    ArrayTooLong(usize, usize, ReaderContext),
+    #[error("Expected {0} more bytes but only {1} remain ({2})")]
+    UnexpectedEof(usize, usize, ReaderContext),
+    #[error("{0} ({1})")]
+    InvalidNumber(DecodeError, ReaderContext),
+    #[error("Expected {0} more bytes but only {1} remain in the chunk ({2})")]
+    ChunkOverrun(usize, usize, ReaderContext),
+    #[error("Unrecognized {0} value {1} ({2})")]
+    UnrecognizedEnum(&'static str, i32, ReaderContext),
+}
+
+impl EoReaderError {
+    /// returns where in the data stream the error happened
+    pub fn context(&self) -> &ReaderContext {
+        match self {
+            Self::ChunkedReadingDisabled(context) => context,
+            Self::InvalidStringPadding(_, context) => context,
+            Self::StringTooLong(_, _, context) => context,
+            Self::ArrayTooLong(_, _, context) => context,
+            Self::UnexpectedEof(_, _, context) => context,
+            Self::InvalidNumber(_, context) => context,
+            Self::ChunkOverrun(_, _, context) => context,
+            Self::UnrecognizedEnum(_, _, context) => context,
+        }
+    }
+
+    fn context_mut(&mut self) -> &mut ReaderContext {
+        match self {
+            Self::ChunkedReadingDisabled(context) => context,
+            Self::InvalidStringPadding(_, context) => context,
+            Self::StringTooLong(_, _, context) => context,
+            Self::ArrayTooLong(_, _, context) => context,
+            Self::UnexpectedEof(_, _, context) => context,
+            Self::InvalidNumber(_, context) => context,
+            Self::ChunkOverrun(_, _, context) => context,
+            Self::UnrecognizedEnum(_, _, context) => context,
+        }
+    }
+
+    /// sets the name of the packet or struct being read
+    ///
+    /// called by each enclosing struct so the outermost name is kept
+    pub fn with_packet(mut self, packet: &str) -> Self {
+        self.context_mut().packet = Some(packet.to_owned());
+        self
+    }
+
+    /// prepends a field name to the path of the field being read
+    pub fn with_field(mut self, field: &str) -> Self {
+        let context = self.context_mut();
+        context.field = Some(match context.field.take() {
+            Some(path) => format!("{}.{}", field, path),
+            None => field.to_owned(),
+        });
+        self
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+/// Returned by [EoReader::next_chunk_or_end] when the reader couldn't move to another chunk
+pub enum ChunkedReadingError {
+    #[error("Chunked reading mode is disabled ({0})")]
+    Disabled(ReaderContext),
+    #[error("No more chunks in the input data ({0})")]
+    NoMoreChunks(ReaderContext),
+    #[error("Chunk is missing its break, skipped the last {0} bytes ({1})")]
+    MalformedChunk(usize, ReaderContext),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Where in a data stream an [EoReaderError] happened
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::ReaderContext;
+///
+/// let context = ReaderContext::new()
+///     .packet("TalkReplyServerPacket")
+///     .field("message")
+///     .offset(12)
+///     .chunk_index(2);
+///
+/// assert_eq!(
+///     context.to_string(),
+///     "packet TalkReplyServerPacket, field message, offset 12, chunk 2"
+/// );
+///
+/// let context = context.snippet(10, &[0x48, 0x69, 0xFF, 0x6F]);
+/// assert_eq!(
+///     context.to_string(),
+///     "packet TalkReplyServerPacket, field message, offset 12, chunk 2, bytes 48 69 [FF] 6F"
+/// );
+/// ```
+pub struct ReaderContext {
+    /// name of the outermost packet or struct being read
+    pub packet: Option<String>,
+    /// dotted path to the field being read
+    pub field: Option<String>,
+    /// position of the reader in the data stream
+    pub offset: usize,
+    /// number of chunks the reader has moved past
+    pub chunk_index: usize,
+    /// position of the first byte of `snippet` in the data stream
+    pub snippet_start: usize,
+    /// up to 8 bytes of input data either side of `offset`
+    pub snippet: Vec<u8>,
+}
+
+impl ReaderContext {
+    /// creates a new empty [ReaderContext]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the name of the packet or struct being read
+    pub fn packet(mut self, packet: &str) -> Self {
+        self.packet = Some(packet.to_owned());
+        self
+    }
+
+    /// sets the path to the field being read
+    pub fn field(mut self, field: &str) -> Self {
+        self.field = Some(field.to_owned());
+        self
+    }
+
+    /// sets the position of the reader in the data stream
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// sets the number of chunks the reader has moved past
+    pub fn chunk_index(mut self, chunk_index: usize) -> Self {
+        self.chunk_index = chunk_index;
+        self
+    }
+
+    /// sets the input data around the offset and where it starts in the data stream
+    pub fn snippet(mut self, snippet_start: usize, snippet: &[u8]) -> Self {
+        self.snippet_start = snippet_start;
+        self.snippet = snippet.to_vec();
+        self
+    }
 }
 
-impl From<String> for EoReaderError {
-    fn from(s: String) -> Self {
-        Self::Other(s)
+impl fmt::Display for ReaderContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(packet) = &self.packet {
+            write!(f, "packet {}, ", packet)?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, "field {}, ", field)?;
+        }
+        write!(f, "offset {}, chunk {}", self.offset, self.chunk_index)?;
+        if self.snippet.is_empty() {
+            return Ok(());
+        }
+
+        // the byte at the offset is bracketed, or an empty pair marks the end of the data
+        write!(f, ", bytes")?;
+        for (i, byte) in self.snippet.iter().enumerate() {
+            if self.snippet_start + i == self.offset {
+                write!(f, " [{:02X}]", byte)?;
+            } else {
+                write!(f, " {:02X}", byte)?;
+            }
+        }
+        if self.snippet_start + self.snippet.len() == self.offset {
+            write!(f, " []")?;
+        }
+        Ok(())
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Limits on the data an [EoReader] will accept
+///
+/// Generated deserializers check these before reading strings and arrays so a forged length
+/// field can't make them build huge values. There are no limits by default.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::{EoReader, EoReaderError, ReaderLimits};
+///
+/// let reader = EoReader::from(vec![72, 101, 108, 108, 111]);
+/// reader.set_limits(ReaderLimits::new().max_string_length(4));
+///
+/// assert!(matches!(
+///     reader.check_string_length(reader.remaining()),
+///     Err(EoReaderError::StringTooLong(5, 4, _))
+/// ));
+/// ```
+pub struct ReaderLimits {
+    /// longest string, in bytes, that can be read
+    pub max_string_length: usize,
+    /// most elements an array can hold
+    pub max_array_length: usize,
+}
+
+impl ReaderLimits {
+    /// creates a new [ReaderLimits] with no limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the longest string, in bytes, that can be read
+    pub fn max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = max_string_length;
+        self
+    }
+
+    /// sets the most elements an array can hold
+    pub fn max_array_length(mut self, max_array_length: usize) -> Self {
+        self.max_array_length = max_array_length;
+        self
+    }
+}
+
+impl Default for ReaderLimits {
+    fn default() -> Self {
+        Self {
+            max_string_length: usize::MAX,
+            max_array_length: usize::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The character encoding an [EoReader] decodes strings with
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::{EoReader, StringEncoding};
+///
+/// let data = "héllo".as_bytes().to_vec();
+///
+/// let reader = EoReader::from(data.clone());
+/// assert_eq!(reader.get_string(), "hÃ©llo");
+///
+/// let reader = EoReader::from(data);
+/// reader.set_string_encoding(StringEncoding::Utf8);
+/// assert_eq!(reader.get_string(), "héllo");
+/// ```
+pub enum StringEncoding {
+    /// WINDOWS-1252, used by the official client. every byte is a valid character
+    #[default]
+    Windows1252,
+    /// UTF-8, for custom clients. invalid sequences are replaced with `U+FFFD` and counted,
+    /// see [EoReader::invalid_string_sequences]
+    Utf8,
+}
+
+// an error found by one of the strict modes while reading, reported by EoReader::check_errors
+#[derive(Debug, Clone, Copy)]
+enum StrictError {
+    Number(DecodeError),
+    Eof(usize, usize),
+    ChunkOverrun(usize, usize),
+    UnrecognizedEnum(&'static str, i32),
+}
+
 #[derive(Debug)]
 /// A reader for reading data from an EO data stream
 ///
@@ -70,7 +341,18 @@ pub struct EoReader {
     position: Cell<usize>,
     chunked_reading_mode: Cell<bool>,
     chunk_start: Cell<usize>,
+    chunk_index: Cell<usize>,
     next_break: Cell<Option<usize>>,
+    limits: Cell<ReaderLimits>,
+    string_encoding: Cell<StringEncoding>,
+    invalid_string_sequences: Cell<usize>,
+    invalid_numbers: Cell<usize>,
+    strict_number_mode: Cell<bool>,
+    strict_eof_mode: Cell<bool>,
+    strict_chunk_mode: Cell<bool>,
+    strict_enum_mode: Cell<bool>,
+    // the first error found by a strict mode and where it started
+    strict_error: Cell<Option<(StrictError, usize)>>,
 }
 
 impl EoReader {
@@ -81,10 +363,93 @@ impl EoReader {
             position: Cell::new(0),
             chunked_reading_mode: Cell::new(false),
             chunk_start: Cell::new(0),
+            chunk_index: Cell::new(0),
             next_break: Cell::new(None),
+            limits: Cell::new(ReaderLimits::default()),
+            string_encoding: Cell::new(StringEncoding::default()),
+            invalid_string_sequences: Cell::new(0),
+            invalid_numbers: Cell::new(0),
+            strict_number_mode: Cell::new(false),
+            strict_eof_mode: Cell::new(false),
+            strict_chunk_mode: Cell::new(false),
+            strict_enum_mode: Cell::new(false),
+            strict_error: Cell::new(None),
         }
     }
 
+    /// creates a new [EoReader] that reads directly from `owner` without copying it
+    ///
+    /// `owner` is kept alive until the reader and every [Bytes] taken from it are dropped. a
+    /// memory mapped file (e.g. `memmap2::Mmap`) can be passed to parse large map and pub
+    /// files without reading them into memory first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoReader, EoSerialize};
+    /// use eolib::protocol::r#pub::Eif;
+    ///
+    /// let file: Box<[u8]> = Eif::default().to_bytes().unwrap().to_vec().into();
+    /// let reader = EoReader::from_owner(file);
+    ///
+    /// assert_eq!(Eif::deserialize(&reader).unwrap(), Eif::default());
+    /// ```
+    pub fn from_owner<T>(owner: T) -> Self
+    where
+        T: AsRef<[u8]> + Send + 'static,
+    {
+        Self::new(Bytes::from_owner(owner))
+    }
+
+    /// creates a new [EoReader] over a memory mapped `file` without reading it into memory
+    ///
+    /// # Safety
+    ///
+    /// the file must not be modified or truncated by this or any other process while the
+    /// reader, or any [Bytes] taken from it, is alive. see `memmap2::Mmap::map`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// use eolib::{
+    ///     data::{EoReader, EoSerialize},
+    ///     protocol::map::Emf,
+    /// };
+    ///
+    /// let file = File::open("maps/00001.emf").unwrap();
+    /// let reader = unsafe { EoReader::from_mmap(&file) }.unwrap();
+    /// let emf = Emf::deserialize(&reader).unwrap();
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub unsafe fn from_mmap(file: &std::fs::File) -> io::Result<Self> {
+        let mmap = memmap2::Mmap::map(file)?;
+        Ok(Self::from_owner(mmap))
+    }
+
+    /// creates a new [EoReader] with a copy of the specified data
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoReader;
+    ///
+    /// let buf = vec![1, 43, 11, 254];
+    /// let reader = EoReader::from_slice(&buf[1..]);
+    ///
+    /// assert_eq!(reader.get_char(), 42);
+    /// assert_eq!(reader.get_short(), 10);
+    /// ```
+    pub fn from_slice(data: &[u8]) -> Self {
+        Self::new(Bytes::copy_from_slice(data))
+    }
+
+    /// creates a new [EoReader] over static data without copying it
+    pub fn from_static(data: &'static [u8]) -> Self {
+        Self::new(Bytes::from_static(data))
+    }
+
     /// returns the number of bytes remaining in the input data or chunk if chunked reading is
     /// enabled
     pub fn remaining(&self) -> usize {
@@ -113,6 +478,15 @@ impl EoReader {
     /// * the reader will treat `0xFF` bytes as the end of the current chunk
     /// * [next_chunk](EoReader::next_chunk) can be called to move to the next chunk
     pub fn set_chunked_reading_mode(&self, enabled: bool) {
+        #[cfg(feature = "tracing")]
+        if self.chunked_reading_mode.get() != enabled {
+            tracing::trace!(
+                enabled,
+                position = self.position.get(),
+                "chunked reading mode changed"
+            );
+        }
+
         self.chunked_reading_mode.set(enabled);
         let next_break = self.next_break.get();
         if next_break.is_none() {
@@ -120,13 +494,355 @@ impl EoReader {
         }
     }
 
+    /// returns the limits on strings and arrays read by the reader
+    pub fn get_limits(&self) -> ReaderLimits {
+        self.limits.get()
+    }
+
+    /// sets the limits on strings and arrays read by the reader
+    ///
+    /// readers created by [slice](EoReader::slice) and [chunks](EoReader::chunks) inherit them
+    pub fn set_limits(&self, limits: ReaderLimits) {
+        self.limits.set(limits);
+    }
+
+    /// applies the limits, string encoding and strict modes of `profile`
+    ///
+    /// readers created by [slice](EoReader::slice) and [chunks](EoReader::chunks) inherit them
+    pub fn set_strictness_profile(&self, profile: StrictnessProfile) {
+        self.set_limits(profile.limits);
+        self.set_string_encoding(profile.string_encoding);
+        self.set_strict_number_mode(profile.strict_number_mode);
+        self.set_strict_eof_mode(profile.strict_eof_mode);
+        self.set_strict_chunk_mode(profile.strict_chunk_mode);
+        self.set_strict_enum_mode(profile.strict_enum_mode);
+    }
+
+    /// returns the current strict number mode for the reader
+    pub fn get_strict_number_mode(&self) -> bool {
+        self.strict_number_mode.get()
+    }
+
+    /// sets the strict number mode for the reader
+    ///
+    /// in strict number mode numbers are decoded with
+    /// [decode_number_checked](super::decode_number_checked). a number with a byte
+    /// [encode_number](super::encode_number) never writes, like a `0`, is read as `0` and
+    /// [check_errors](EoReader::check_errors) returns an error. generated types call it at
+    /// the end of `deserialize`, so they fail to read instead. readers created by
+    /// [slice](EoReader::slice) and [chunks](EoReader::chunks) inherit it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{DecodeError, EoReader, EoReaderError};
+    ///
+    /// let reader = EoReader::from(vec![43, 254, 43, 0]);
+    /// reader.set_strict_number_mode(true);
+    ///
+    /// assert_eq!(reader.get_short(), 42);
+    /// assert!(reader.check_errors().is_ok());
+    /// assert_eq!(reader.get_short(), 0);
+    /// assert!(matches!(
+    ///     reader.check_errors(),
+    ///     Err(EoReaderError::InvalidNumber(DecodeError::InvalidByte(0, 1), _))
+    /// ));
+    /// ```
+    pub fn set_strict_number_mode(&self, enabled: bool) {
+        self.strict_number_mode.set(enabled);
+    }
+
+    /// returns the current strict EOF mode for the reader
+    pub fn get_strict_eof_mode(&self) -> bool {
+        self.strict_eof_mode.get()
+    }
+
+    /// sets the strict EOF mode for the reader
+    ///
+    /// reading past the end of the data returns default values. in strict EOF mode
+    /// [check_errors](EoReader::check_errors) also returns
+    /// [UnexpectedEof](EoReaderError::UnexpectedEof) for the first read that did, so a
+    /// truncated packet fails to deserialize instead of being filled with zeroes. readers
+    /// created by [slice](EoReader::slice) and [chunks](EoReader::chunks) inherit it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoReader, EoReaderError};
+    ///
+    /// let reader = EoReader::from(vec![11]);
+    /// reader.set_strict_eof_mode(true);
+    ///
+    /// assert_eq!(reader.get_short(), 10);
+    /// assert!(matches!(
+    ///     reader.check_errors(),
+    ///     Err(EoReaderError::UnexpectedEof(2, 1, _))
+    /// ));
+    /// ```
+    pub fn set_strict_eof_mode(&self, enabled: bool) {
+        self.strict_eof_mode.set(enabled);
+    }
+
+    /// returns the current strict chunk mode for the reader
+    pub fn get_strict_chunk_mode(&self) -> bool {
+        self.strict_chunk_mode.get()
+    }
+
+    /// sets the strict chunk mode for the reader
+    ///
+    /// in chunked reading mode a read is cut short at the next break. in strict chunk mode
+    /// [check_errors](EoReader::check_errors) also returns
+    /// [ChunkOverrun](EoReaderError::ChunkOverrun) for the first read that was, so a field
+    /// that runs into the next chunk fails to deserialize. readers created by
+    /// [slice](EoReader::slice) and [chunks](EoReader::chunks) inherit it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoReader, EoReaderError};
+    ///
+    /// let reader = EoReader::from(vec![11, 255, 11, 254]);
+    /// reader.set_chunked_reading_mode(true);
+    /// reader.set_strict_chunk_mode(true);
+    ///
+    /// assert_eq!(reader.get_short(), 10);
+    /// assert!(matches!(
+    ///     reader.check_errors(),
+    ///     Err(EoReaderError::ChunkOverrun(2, 1, _))
+    /// ));
+    /// ```
+    pub fn set_strict_chunk_mode(&self, enabled: bool) {
+        self.strict_chunk_mode.set(enabled);
+    }
+
+    /// returns the current strict enum mode for the reader
+    pub fn get_strict_enum_mode(&self) -> bool {
+        self.strict_enum_mode.get()
+    }
+
+    /// sets the strict enum mode for the reader
+    ///
+    /// generated types read enum values the protocol doesn't define as `Unrecognized`. in
+    /// strict enum mode they return [UnrecognizedEnum](EoReaderError::UnrecognizedEnum)
+    /// instead. readers created by [slice](EoReader::slice) and [chunks](EoReader::chunks)
+    /// inherit it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::{
+    ///     data::{EoReader, EoReaderError, EoSerialize},
+    ///     protocol::net::client::WalkPlayerClientPacket,
+    /// };
+    ///
+    /// let reader = EoReader::from(vec![100, 1, 1, 1, 1, 1]);
+    /// reader.set_strict_enum_mode(true);
+    ///
+    /// assert!(matches!(
+    ///     WalkPlayerClientPacket::deserialize(&reader),
+    ///     Err(EoReaderError::UnrecognizedEnum("Direction", 99, _))
+    /// ));
+    /// ```
+    pub fn set_strict_enum_mode(&self, enabled: bool) {
+        self.strict_enum_mode.set(enabled);
+    }
+
+    /// returns the first error found by a strict mode since the last call, if there was one
+    ///
+    /// see [set_strict_number_mode](EoReader::set_strict_number_mode),
+    /// [set_strict_eof_mode](EoReader::set_strict_eof_mode),
+    /// [set_strict_chunk_mode](EoReader::set_strict_chunk_mode) and
+    /// [set_strict_enum_mode](EoReader::set_strict_enum_mode)
+    pub fn check_errors(&self) -> Result<(), EoReaderError> {
+        let (error, position) = match self.strict_error.take() {
+            Some(error) => error,
+            None => return Ok(()),
+        };
+
+        let context = self.context_at(position);
+        Err(match error {
+            StrictError::Number(e) => EoReaderError::InvalidNumber(e, context),
+            StrictError::Eof(length, remaining) => {
+                EoReaderError::UnexpectedEof(length, remaining, context)
+            }
+            StrictError::ChunkOverrun(length, remaining) => {
+                EoReaderError::ChunkOverrun(length, remaining, context)
+            }
+            StrictError::UnrecognizedEnum(name, value) => {
+                EoReaderError::UnrecognizedEnum(name, value, context)
+            }
+        })
+    }
+
+    // called by generated types after reading an enum value the protocol doesn't define
+    pub(crate) fn unrecognized_enum(&self, position: usize, name: &'static str, value: i32) {
+        if self.strict_enum_mode.get() {
+            self.record_strict_error(StrictError::UnrecognizedEnum(name, value), position);
+        }
+    }
+
+    // keeps the first error so it's the one reported
+    fn record_strict_error(&self, error: StrictError, position: usize) {
+        let first = self.strict_error.take().unwrap_or((error, position));
+        self.strict_error.set(Some(first));
+    }
+
+    /// returns the encoding strings are decoded with
+    pub fn get_string_encoding(&self) -> StringEncoding {
+        self.string_encoding.get()
+    }
+
+    /// sets the encoding strings are decoded with
+    ///
+    /// readers created by [slice](EoReader::slice) and [chunks](EoReader::chunks) inherit it
+    pub fn set_string_encoding(&self, encoding: StringEncoding) {
+        self.string_encoding.set(encoding);
+    }
+
+    /// returns the number of invalid sequences replaced while decoding strings so far
+    ///
+    /// always `0` with [StringEncoding::Windows1252]. readers created by
+    /// [slice](EoReader::slice) and [chunks](EoReader::chunks) keep their own count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoReader, StringEncoding};
+    ///
+    /// let reader = EoReader::from(vec![b'h', 0xC3, b'i', 0xE9, 0x80]);
+    /// reader.set_string_encoding(StringEncoding::Utf8);
+    ///
+    /// assert_eq!(reader.get_string(), "h\u{FFFD}i\u{FFFD}");
+    /// assert_eq!(reader.invalid_string_sequences(), 2);
+    /// ```
+    pub fn invalid_string_sequences(&self) -> usize {
+        self.invalid_string_sequences.get()
+    }
+
+    /// returns the number of numbers read so far that contained a `0` byte
+    ///
+    /// [encode_number](super::encode_number) never writes a `0`, so it usually means the data
+    /// is corrupt. the number is still read with the `0` treated like `254`, see
+    /// [set_strict_number_mode](EoReader::set_strict_number_mode) to reject it instead. readers
+    /// created by [slice](EoReader::slice) and [chunks](EoReader::chunks) keep their own count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoReader;
+    ///
+    /// let reader = EoReader::from(vec![43, 254, 43, 0]);
+    ///
+    /// assert_eq!(reader.get_short(), 42);
+    /// assert_eq!(reader.invalid_numbers(), 0);
+    /// assert_eq!(reader.get_short(), 42);
+    /// assert_eq!(reader.invalid_numbers(), 1);
+    /// ```
+    pub fn invalid_numbers(&self) -> usize {
+        self.invalid_numbers.get()
+    }
+
+    /// returns an error if a string of `length` bytes is longer than the string limit
+    pub fn check_string_length(&self, length: usize) -> Result<(), EoReaderError> {
+        let max = self.limits.get().max_string_length;
+        if length > max {
+            return Err(EoReaderError::StringTooLong(length, max, self.context()));
+        }
+        Ok(())
+    }
+
+    /// returns an error if an array of `length` elements is longer than the array limit
+    pub fn check_array_length(&self, length: usize) -> Result<(), EoReaderError> {
+        let max = self.limits.get().max_array_length;
+        if length > max {
+            return Err(EoReaderError::ArrayTooLong(length, max, self.context()));
+        }
+        Ok(())
+    }
+
+    /// returns an error if fewer than `length` bytes remain
+    ///
+    /// reading past the end of the data returns default values, this lets a length field be
+    /// checked against the data before it's trusted
+    pub fn check_remaining(&self, length: usize) -> Result<(), EoReaderError> {
+        let remaining = self.remaining();
+        if length > remaining {
+            return Err(EoReaderError::UnexpectedEof(
+                length,
+                remaining,
+                self.context(),
+            ));
+        }
+        Ok(())
+    }
+
     /// moves the reader position to the start of the next chunk in the input data
     pub fn next_chunk(&self) -> Result<(), EoReaderError> {
         let chunked_reading_mode = self.chunked_reading_mode.get();
         if !chunked_reading_mode {
-            return Err(EoReaderError::ChunkedReadingDisabled);
+            return Err(EoReaderError::ChunkedReadingDisabled(self.context()));
         }
 
+        self.advance_chunk();
+        Ok(())
+    }
+
+    /// moves the reader position to the start of the next chunk, or to the end of the input
+    /// data if there are no more breaks
+    ///
+    /// [next_chunk](EoReader::next_chunk) moves to the end without saying so, which makes
+    /// a missing break look like a run of default values. this returns
+    /// [NoMoreChunks](ChunkedReadingError::NoMoreChunks) if the data was fully read, or
+    /// [MalformedChunk](ChunkedReadingError::MalformedChunk) with the number of bytes skipped
+    /// if unread data was left without a break, so damaged packets can be detected and
+    /// dropped instead of misread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{ChunkedReadingError, EoReader};
+    ///
+    /// let reader = EoReader::from(vec![43, 255, 44, 45]);
+    /// reader.set_chunked_reading_mode(true);
+    ///
+    /// assert_eq!(reader.get_char(), 42);
+    /// assert!(reader.next_chunk_or_end().is_ok());
+    ///
+    /// // the second chunk should have ended with a break after one char
+    /// assert_eq!(reader.get_char(), 43);
+    /// assert!(matches!(
+    ///     reader.next_chunk_or_end(),
+    ///     Err(ChunkedReadingError::MalformedChunk(1, _))
+    /// ));
+    /// assert!(matches!(
+    ///     reader.next_chunk_or_end(),
+    ///     Err(ChunkedReadingError::NoMoreChunks(_))
+    /// ));
+    /// ```
+    pub fn next_chunk_or_end(&self) -> Result<(), ChunkedReadingError> {
+        if !self.chunked_reading_mode.get() {
+            return Err(ChunkedReadingError::Disabled(self.context()));
+        }
+
+        let len = self.data.len();
+        let has_break = matches!(self.next_break.get(), Some(next_break) if next_break < len);
+        if has_break {
+            self.advance_chunk();
+            return Ok(());
+        }
+
+        let context = self.context();
+        let skipped = len.saturating_sub(self.position.get());
+        self.advance_chunk();
+
+        if skipped == 0 {
+            Err(ChunkedReadingError::NoMoreChunks(context))
+        } else {
+            Err(ChunkedReadingError::MalformedChunk(skipped, context))
+        }
+    }
+
+    fn advance_chunk(&self) {
         let next_break = match self.next_break.get() {
             Some(next_break) => next_break,
             None => self.position.get(),
@@ -140,9 +856,118 @@ impl EoReader {
         }
 
         self.position.set(position);
+        self.chunk_index.set(self.chunk_index.get() + 1);
         self.next_break.set(Some(self.find_next_break_index()));
 
-        Ok(())
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            chunk = self.chunk_index.get(),
+            position,
+            remaining = self.remaining(),
+            "next chunk"
+        );
+    }
+
+    /// returns the current position and chunk index of the reader, along with the input data
+    /// around the position
+    pub fn context(&self) -> ReaderContext {
+        self.context_at(self.position.get())
+    }
+
+    fn context_at(&self, position: usize) -> ReaderContext {
+        let start = position.saturating_sub(SNIPPET_RADIUS);
+        let end = cmp::min(position + SNIPPET_RADIUS, self.data.len());
+        ReaderContext::new()
+            .offset(position)
+            .chunk_index(self.chunk_index.get())
+            .snippet(start, &self.data[start..end])
+    }
+
+    /// returns an iterator over the remaining `0xFF` delimited chunks in the input data
+    ///
+    /// each chunk is returned as its own [EoReader] over the chunk's bytes (without the break).
+    /// the reader position is moved past each chunk as it is returned, so after iterating every
+    /// chunk the reader is at the end of the input data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use eolib::data::EoReader;
+    ///
+    /// let data = Bytes::from_static(&[72, 105, 255, 43, 11, 254, 255, 2]);
+    /// let reader = EoReader::new(data);
+    ///
+    /// let chunks: Vec<EoReader> = reader.chunks().collect();
+    /// assert_eq!(chunks.len(), 3);
+    /// assert_eq!(chunks[0].get_string(), "Hi");
+    /// assert_eq!(chunks[1].get_char(), 42);
+    /// assert_eq!(chunks[1].get_short(), 10);
+    /// assert_eq!(chunks[2].get_char(), 1);
+    /// assert_eq!(reader.remaining(), 0);
+    /// ```
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks { reader: self }
+    }
+
+    /// returns the current read position in the input data
+    pub fn position(&self) -> usize {
+        self.position.get()
+    }
+
+    /// returns all of the input data, including anything already read
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// returns a new [EoReader] over a range of the input data
+    ///
+    /// the range is clamped to the input data and the position of this reader is not changed.
+    /// useful for handing a bounded reader to another deserializer so it can't read past the
+    /// end of a field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoReader;
+    ///
+    /// let reader = EoReader::from(vec![1, 6, 11, 3]);
+    /// assert_eq!(reader.get_byte(), 1);
+    ///
+    /// let coords = reader.slice(reader.position()..reader.position() + 2);
+    /// assert_eq!(coords.get_char(), 5);
+    /// assert_eq!(coords.get_short(), 10);
+    /// assert_eq!(coords.remaining(), 0);
+    ///
+    /// assert_eq!(reader.slice(3..100).as_bytes()[..], [3]);
+    /// ```
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> EoReader {
+        let len = self.data.len();
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.saturating_add(1),
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => len,
+        };
+        let end = cmp::min(end, len);
+        let start = cmp::min(start, end);
+        let reader = EoReader::new(self.data.slice(start..end));
+        self.copy_settings_to(&reader);
+        reader
+    }
+
+    // settings inherited by readers created by slice and chunks
+    fn copy_settings_to(&self, reader: &EoReader) {
+        reader.set_limits(self.get_limits());
+        reader.set_string_encoding(self.get_string_encoding());
+        reader.set_strict_number_mode(self.get_strict_number_mode());
+        reader.set_strict_eof_mode(self.get_strict_eof_mode());
+        reader.set_strict_chunk_mode(self.get_strict_chunk_mode());
+        reader.set_strict_enum_mode(self.get_strict_enum_mode());
     }
 
     fn find_next_break_index(&self) -> usize {
@@ -158,8 +983,8 @@ impl EoReader {
     /// increases the read position by 1
     pub fn get_byte(&self) -> u8 {
         match self.read_bytes(1) {
-            Some(buf) => buf[0],
-            None => 0,
+            Some([byte]) => *byte,
+            _ => 0,
         }
     }
 
@@ -173,44 +998,98 @@ impl EoReader {
         }
     }
 
+    /// returns the rest of the data stream, or of the chunk in chunked reading mode, without
+    /// copying it
+    ///
+    /// increases the read position to the end of the data or chunk
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoReader;
+    ///
+    /// let reader = EoReader::from(vec![1, 2, 3, 4]);
+    /// reader.skip(1);
+    ///
+    /// assert_eq!(reader.get_remaining_bytes()[..], [2, 3, 4]);
+    /// assert_eq!(reader.remaining(), 0);
+    /// ```
+    pub fn get_remaining_bytes(&self) -> Bytes {
+        let position = self.position.get();
+        let end = position + self.remaining();
+        self.position.set(end);
+        self.data.slice(position..end)
+    }
+
+    /// moves the read position forward by `length` bytes without reading them
+    ///
+    /// stops at the end of the data, or of the chunk in chunked reading mode
+    pub fn skip(&self, length: usize) {
+        self.read_bytes(length);
+    }
+
+    /// returns two raw [u8]s from the data stream as a little endian [u16]
+    ///
+    /// increases the read position by 2. returns `0` if there are fewer than 2 bytes left,
+    /// see [EoWriter::add_raw_short_le](super::EoWriter::add_raw_short_le)
+    pub fn get_raw_short_le(&self) -> u16 {
+        self.read_raw().map(u16::from_le_bytes).unwrap_or(0)
+    }
+
+    /// returns two raw [u8]s from the data stream as a big endian [u16]
+    ///
+    /// increases the read position by 2
+    pub fn get_raw_short_be(&self) -> u16 {
+        self.read_raw().map(u16::from_be_bytes).unwrap_or(0)
+    }
+
+    /// returns four raw [u8]s from the data stream as a little endian [u32]
+    ///
+    /// increases the read position by 4
+    pub fn get_raw_int_le(&self) -> u32 {
+        self.read_raw().map(u32::from_le_bytes).unwrap_or(0)
+    }
+
+    /// returns four raw [u8]s from the data stream as a big endian [u32]
+    ///
+    /// increases the read position by 4
+    pub fn get_raw_int_be(&self) -> u32 {
+        self.read_raw().map(u32::from_be_bytes).unwrap_or(0)
+    }
+
     /// returns a single [u8] from the data stream decoded into an [i32]
     ///
     /// increases the read position by 1
     pub fn get_char(&self) -> i32 {
-        match self.read_bytes(1) {
-            Some(buf) => decode_number(buf),
-            None => 0,
-        }
+        self.read_number(1)
     }
 
     /// returns two [u8]s from the data stream decoded into an [i32]
     ///
     /// increases the read position by 2
     pub fn get_short(&self) -> i32 {
-        match self.read_bytes(2) {
-            Some(buf) => decode_number(buf),
-            None => 0,
-        }
+        self.read_number(2)
     }
 
     /// returns three [u8]s from the data stream decoded into an [i32]
     ///
     /// increases the read position by 3
     pub fn get_three(&self) -> i32 {
-        match self.read_bytes(3) {
-            Some(buf) => decode_number(buf),
-            None => 0,
-        }
+        self.read_number(3)
     }
 
     /// returns four [u8]s from the data stream decoded into an [i32]
     ///
     /// increases the read position by 4
     pub fn get_int(&self) -> i32 {
-        match self.read_bytes(4) {
-            Some(buf) => decode_number(buf),
-            None => 0,
-        }
+        self.read_number(4)
+    }
+
+    /// returns an encoded number of the type `N` from the data stream
+    ///
+    /// see [EoNumber]
+    pub fn get<N: EoNumber>(&self) -> i32 {
+        N::read(self)
     }
 
     /// returns a [String] from the data stream
@@ -219,6 +1098,39 @@ impl EoReader {
         self.get_fixed_string(remaining)
     }
 
+    /// returns a [String] from the data stream up to the next `0xFF` break and moves past the
+    /// break
+    ///
+    /// works with or without chunked reading mode. if there is no break the rest of the data is
+    /// returned
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoReader, EoWriter};
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_string_with_break("Hello").unwrap();
+    /// writer.add_char(1).unwrap();
+    ///
+    /// let reader = EoReader::new(writer.to_byte_array());
+    /// assert_eq!(reader.get_string_until_break(), "Hello");
+    /// assert_eq!(reader.get_char(), 1);
+    /// ```
+    pub fn get_string_until_break(&self) -> String {
+        if self.chunked_reading_mode.get() {
+            let string = self.get_string();
+            self.next_chunk().expect("chunked reading mode is enabled");
+            return string;
+        }
+
+        let position = self.position.get();
+        let next_break = self.find_next_break_index();
+        let string = self.get_fixed_string(next_break.saturating_sub(position));
+        self.position.set(cmp::min(next_break + 1, self.data.len()));
+        string
+    }
+
     /// returns a [String] from the data stream with a fixed length
     ///
     /// if `length` is `0` then an empty [String] is returned
@@ -233,8 +1145,7 @@ impl EoReader {
             None => return String::new(),
         };
 
-        let (cow, _, _) = WINDOWS_1252.decode(buf);
-        cow.to_string()
+        self.decode_characters(buf)
     }
 
     /// returns an encoded [String] from the data stream
@@ -243,6 +1154,9 @@ impl EoReader {
     }
 
     /// returns an encoded [String] from the data stream with a fixed length
+    ///
+    /// the string ends at the first `0xFF` break, or fills the whole field if there is none, see
+    /// [EoWriter::add_fixed_encoded_string](super::EoWriter::add_fixed_encoded_string)
     pub fn get_fixed_encoded_string(&self, length: usize) -> String {
         if length == 0 {
             return String::new();
@@ -256,20 +1170,590 @@ impl EoReader {
         decode_string(&mut buf);
         let position_of_break = match buf.iter().position(|b| *b == 0xff) {
             Some(position_of_break) => position_of_break,
-            None => length - 1,
+            None => buf.len(),
         };
-        let (cow, _, _) = WINDOWS_1252.decode(&buf[..position_of_break]);
-        cow.to_string()
+        self.decode_characters(&buf[..position_of_break])
     }
 
-    fn read_bytes(&self, length: usize) -> Option<&[u8]> {
+    /// returns an encoded [String] from the data stream with a fixed length, verifying that it
+    /// either fills the field or is terminated by a `0xFF` break followed only by `0xFF` padding
+    ///
+    /// this is the layout written by
+    /// [EoWriter::add_fixed_encoded_string](super::EoWriter::add_fixed_encoded_string)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoReader, EoReaderError, EoWriter};
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_fixed_encoded_string("Void", 8).unwrap();
+    ///
+    /// let reader = EoReader::new(writer.to_byte_array());
+    /// assert_eq!(reader.try_get_fixed_encoded_string(8).unwrap(), "Void");
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_encoded_string("Void").unwrap();
+    ///
+    /// let reader = EoReader::new(writer.to_byte_array());
+    /// assert!(matches!(
+    ///     reader.try_get_fixed_encoded_string(5),
+    ///     Err(EoReaderError::InvalidStringPadding(5, _))
+    /// ));
+    /// ```
+    pub fn try_get_fixed_encoded_string(&self, length: usize) -> Result<String, EoReaderError> {
+        let context = self.context();
+        let mut buf = match self.read_bytes(length) {
+            Some(buf) => buf.to_vec(),
+            None => Vec::new(),
+        };
+
+        decode_string(&mut buf);
+        let position_of_break = match buf.iter().position(|b| *b == 0xff) {
+            Some(position_of_break) if buf[position_of_break..].iter().all(|b| *b == 0xff) => {
+                position_of_break
+            }
+            None if buf.len() == length => length,
+            _ => return Err(EoReaderError::InvalidStringPadding(length, context)),
+        };
+        Ok(self.decode_characters(&buf[..position_of_break]))
+    }
+
+    /// returns `count` instances of `T` deserialized one after another
+    ///
+    /// returns an error if `count` is longer than the array limit or an element fails to
+    /// deserialize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::{data::EoReader, protocol::Coords};
+    ///
+    /// let reader = EoReader::from(vec![2, 3, 4, 5]);
+    /// let coords: Vec<Coords> = reader.get_array(2).unwrap();
+    /// assert_eq!(coords, [Coords { x: 1, y: 2 }, Coords { x: 3, y: 4 }]);
+    /// ```
+    pub fn get_array<T: EoSerialize>(&self, count: usize) -> Result<Vec<T>, EoReaderError> {
+        self.check_array_length(count)?;
+        let mut array = Vec::with_capacity(cmp::min(count, self.remaining()));
+        for _ in 0..count {
+            array.push(T::deserialize(self)?);
+        }
+        Ok(array)
+    }
+
+    /// returns instances of `T` from each of the remaining chunks, moving past the break after
+    /// every element
+    ///
+    /// stops at the end of the data or at an empty chunk. chunked reading mode must be enabled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::{data::EoReader, protocol::Coords};
+    ///
+    /// let reader = EoReader::from(vec![2, 3, 255, 4, 5, 255]);
+    /// reader.set_chunked_reading_mode(true);
+    ///
+    /// let coords: Vec<Coords> = reader.get_delimited_array().unwrap();
+    /// assert_eq!(coords, [Coords { x: 1, y: 2 }, Coords { x: 3, y: 4 }]);
+    /// assert_eq!(reader.remaining(), 0);
+    /// ```
+    pub fn get_delimited_array<T: EoSerialize>(&self) -> Result<Vec<T>, EoReaderError> {
+        if !self.chunked_reading_mode.get() {
+            return Err(EoReaderError::ChunkedReadingDisabled(self.context()));
+        }
+
+        let mut array = Vec::new();
+        while self.remaining() > 0 {
+            self.check_array_length(array.len() + 1)?;
+            array.push(T::deserialize(self)?);
+            self.next_chunk()?;
+        }
+        Ok(array)
+    }
+
+    fn decode_characters(&self, buf: &[u8]) -> String {
+        match self.string_encoding.get() {
+            StringEncoding::Windows1252 => {
+                let (cow, _, _) = WINDOWS_1252.decode(buf);
+                cow.to_string()
+            }
+            StringEncoding::Utf8 => {
+                let mut string = String::with_capacity(buf.len());
+                let mut rest = buf;
+                loop {
+                    match str::from_utf8(rest) {
+                        Ok(valid) => {
+                            string.push_str(valid);
+                            return string;
+                        }
+                        Err(e) => {
+                            let (valid, invalid) = rest.split_at(e.valid_up_to());
+                            string.push_str(str::from_utf8(valid).unwrap());
+                            string.push(char::REPLACEMENT_CHARACTER);
+                            self.invalid_string_sequences
+                                .set(self.invalid_string_sequences.get() + 1);
+                            rest = &invalid[e.error_len().unwrap_or(invalid.len())..];
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn read_number(&self, size: usize) -> i32 {
         let position = self.position.get();
-        let length = cmp::min(length, self.remaining());
-        let buf = match self.data.get(position..position + length) {
+        let buf = match self.read_bytes(size) {
             Some(buf) => buf,
-            None => return None,
+            None => return 0,
         };
+
+        if buf.contains(&0) {
+            self.invalid_numbers.set(self.invalid_numbers.get() + 1);
+        }
+
+        // reading past the end is left to check_remaining
+        if !self.strict_number_mode.get() || buf.is_empty() {
+            return decode_number(buf);
+        }
+
+        match decode_number_checked(buf) {
+            Ok(number) => number,
+            Err(e) => {
+                self.record_strict_error(StrictError::Number(e), position);
+                0
+            }
+        }
+    }
+
+    // records a read cut short by the end of the chunk or data, if the strict mode for it is on
+    fn overrun(&self, length: usize, remaining: usize) {
+        let position = self.position.get();
+        let at_break = self.chunked_reading_mode.get()
+            && matches!(self.next_break.get(), Some(next_break) if next_break < self.data.len());
+        if at_break && self.strict_chunk_mode.get() {
+            self.record_strict_error(StrictError::ChunkOverrun(length, remaining), position);
+        } else if !at_break && self.strict_eof_mode.get() {
+            self.record_strict_error(StrictError::Eof(length, remaining), position);
+        }
+    }
+
+    fn read_raw<const N: usize>(&self) -> Option<[u8; N]> {
+        self.read_bytes(N)?.try_into().ok()
+    }
+
+    fn read_bytes(&self, length: usize) -> Option<&[u8]> {
+        let position = self.position.get();
+        let remaining = self.remaining();
+        if length > remaining {
+            self.overrun(length, remaining);
+        }
+
+        let length = cmp::min(length, remaining);
+        let buf = self.data.get(position..position + length)?;
         self.position.set(position + length);
         Some(buf)
     }
 }
+
+impl From<Bytes> for EoReader {
+    fn from(data: Bytes) -> Self {
+        Self::new(data)
+    }
+}
+
+impl From<BytesMut> for EoReader {
+    fn from(data: BytesMut) -> Self {
+        Self::new(data.freeze())
+    }
+}
+
+impl From<Vec<u8>> for EoReader {
+    fn from(data: Vec<u8>) -> Self {
+        Self::new(Bytes::from(data))
+    }
+}
+
+impl From<&[u8]> for EoReader {
+    /// copies the data into a new buffer
+    fn from(data: &[u8]) -> Self {
+        Self::from_slice(data)
+    }
+}
+
+impl io::Read for EoReader {
+    /// reads raw bytes from the current position, stopping at the end of the chunk in chunked
+    /// reading mode
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+impl io::Read for &EoReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.read_bytes(buf.len()).unwrap_or_default();
+        buf[..data.len()].copy_from_slice(data);
+        Ok(data.len())
+    }
+}
+
+#[derive(Debug)]
+/// An iterator over the `0xFF` delimited chunks of an [EoReader]
+///
+/// Created by [EoReader::chunks].
+pub struct Chunks<'a> {
+    reader: &'a EoReader,
+}
+
+impl Iterator for Chunks<'_> {
+    type Item = EoReader;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let reader = self.reader;
+        let position = reader.position.get();
+        if position >= reader.data.len() {
+            return None;
+        }
+
+        let next_break = reader.find_next_break_index();
+        let chunk = EoReader::new(reader.data.slice(position..next_break));
+        reader.copy_settings_to(&chunk);
+
+        reader.chunk_start.set(next_break);
+        reader.chunk_index.set(reader.chunk_index.get() + 1);
+        reader
+            .position
+            .set(cmp::min(next_break + 1, reader.data.len()));
+        reader.next_break.set(Some(reader.find_next_break_index()));
+
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Bytes, BytesMut};
+
+    use super::{
+        ChunkedReadingError, EoReader, EoReaderError, ReaderContext, ReaderLimits, StringEncoding,
+    };
+    use crate::{
+        data::{DecodeError, EoSerialize, EoWriter},
+        protocol::{map::MapTileSpecRow, net::OnlinePlayer, Coords},
+    };
+
+    #[test]
+    fn error_context() {
+        let reader = EoReader::from(vec![1, 255, 2, 255, 0x69, 0x36]);
+        reader.set_chunked_reading_mode(true);
+        reader.next_chunk().unwrap();
+        reader.next_chunk().unwrap();
+
+        let error = reader
+            .try_get_fixed_encoded_string(3)
+            .unwrap_err()
+            .with_field("name")
+            .with_field("items")
+            .with_packet("ShopOpenServerPacket");
+
+        assert_eq!(
+            *error.context(),
+            ReaderContext::new()
+                .packet("ShopOpenServerPacket")
+                .field("items.name")
+                .offset(4)
+                .chunk_index(2)
+                .snippet(0, &[1, 255, 2, 255, 0x69, 0x36])
+        );
+    }
+
+    #[test]
+    fn error_snippet() {
+        let data: Vec<u8> = (0..20).collect();
+        let reader = EoReader::from(data);
+        reader.get_bytes(10);
+        assert_eq!(
+            reader.context().to_string(),
+            "offset 10, chunk 0, bytes 02 03 04 05 06 07 08 09 [0A] 0B 0C 0D 0E 0F 10 11"
+        );
+
+        reader.get_bytes(10);
+        assert_eq!(
+            reader.context().to_string(),
+            "offset 20, chunk 0, bytes 0C 0D 0E 0F 10 11 12 13 []"
+        );
+    }
+
+    #[test]
+    fn slice_out_of_range() {
+        let reader = EoReader::from(vec![1, 2, 3]);
+        assert!(reader.slice(5..10).as_bytes().is_empty());
+        assert_eq!(reader.slice(..=1).as_bytes()[..], [1, 2]);
+        assert_eq!(reader.slice(1..).as_bytes()[..], [2, 3]);
+    }
+
+    #[test]
+    fn from_slice() {
+        let data = [1, 43, 11, 254];
+        let reader = EoReader::from(&data[..]);
+        assert_eq!(reader.get_byte(), 1);
+        assert_eq!(reader.get_char(), 42);
+        assert_eq!(reader.get_short(), 10);
+
+        let reader = EoReader::from_static(&[1, 43]);
+        assert_eq!(reader.get_byte(), 1);
+        assert_eq!(reader.get_char(), 42);
+    }
+
+    #[test]
+    fn from_vec() {
+        let reader: EoReader = vec![43, 255, 2].into();
+        assert_eq!(reader.get_char(), 42);
+        assert_eq!(reader.remaining(), 2);
+    }
+
+    #[test]
+    fn from_bytes_mut() {
+        let reader = EoReader::from(BytesMut::from(&[11, 254][..]));
+        assert_eq!(reader.get_short(), 10);
+    }
+
+    #[test]
+    fn short_fixed_encoded_string() {
+        let reader = EoReader::new(Bytes::from_static(&[0x69, 0x36, 0x5E, 0x49]));
+        assert_eq!(reader.get_fixed_encoded_string(24), "Void");
+        assert_eq!(reader.get_fixed_encoded_string(24), "");
+    }
+
+    #[test]
+    fn fixed_encoded_string_with_data_after_break() {
+        let reader = EoReader::new(Bytes::from_static(&[0x69, 0xFF, 0x5E, 0x49]));
+        assert!(reader.try_get_fixed_encoded_string(4).is_err());
+    }
+
+    #[test]
+    fn chunks_empty() {
+        let reader = EoReader::new(Bytes::new());
+        assert_eq!(reader.chunks().count(), 0);
+    }
+
+    #[test]
+    fn chunks_trailing_break() {
+        let reader = EoReader::new(Bytes::from_static(&[1, 255, 2, 255]));
+        let chunks: Vec<Vec<u8>> = reader
+            .chunks()
+            .map(|chunk| chunk.get_bytes(chunk.remaining()))
+            .collect();
+        assert_eq!(chunks, [vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn chunks_empty_chunk() {
+        let reader = EoReader::new(Bytes::from_static(&[1, 255, 255, 2]));
+        let chunks: Vec<Vec<u8>> = reader
+            .chunks()
+            .map(|chunk| chunk.get_bytes(chunk.remaining()))
+            .collect();
+        assert_eq!(chunks, [vec![1], vec![], vec![2]]);
+    }
+
+    #[test]
+    fn chunks_from_current_position() {
+        let reader = EoReader::new(Bytes::from_static(&[1, 2, 255, 3]));
+        reader.get_byte();
+        let mut chunks = reader.chunks();
+        assert_eq!(chunks.next().unwrap().get_bytes(2), [2]);
+        assert_eq!(reader.get_byte(), 3);
+        assert!(chunks.next().is_none());
+    }
+
+    #[test]
+    fn chunks_in_chunked_reading_mode() {
+        let reader = EoReader::new(Bytes::from_static(&[1, 255, 2, 3, 255, 4]));
+        reader.set_chunked_reading_mode(true);
+        reader.chunks().next();
+        assert_eq!(reader.remaining(), 2);
+        assert_eq!(reader.get_byte(), 2);
+        reader.next_chunk().unwrap();
+        assert_eq!(reader.get_byte(), 4);
+    }
+
+    #[test]
+    fn limits_are_inherited() {
+        let reader = EoReader::from(vec![1, 255, 2, 3]);
+        let limits = ReaderLimits::new().max_array_length(1);
+        reader.set_limits(limits);
+
+        assert_eq!(reader.slice(..).get_limits(), limits);
+        assert!(reader.chunks().all(|chunk| chunk.get_limits() == limits));
+    }
+
+    #[test]
+    fn skip_and_remaining_bytes_stop_at_chunk() {
+        let reader = EoReader::from(vec![1, 2, 3, 255, 4, 5]);
+        reader.set_chunked_reading_mode(true);
+        reader.skip(10);
+        assert_eq!(reader.position(), 3);
+        assert!(reader.get_remaining_bytes().is_empty());
+
+        reader.next_chunk().unwrap();
+        reader.skip(1);
+        assert_eq!(reader.get_remaining_bytes()[..], [5]);
+        assert_eq!(reader.get_byte(), 0);
+    }
+
+    #[test]
+    fn next_chunk_or_end() {
+        let reader = EoReader::from(vec![1, 255, 2, 255]);
+        assert!(matches!(
+            reader.next_chunk_or_end(),
+            Err(ChunkedReadingError::Disabled(_))
+        ));
+
+        reader.set_chunked_reading_mode(true);
+        reader.next_chunk_or_end().unwrap();
+        assert_eq!(reader.get_byte(), 2);
+        reader.next_chunk_or_end().unwrap();
+        assert_eq!(reader.position(), 4);
+
+        match reader.next_chunk_or_end() {
+            Err(ChunkedReadingError::NoMoreChunks(context)) => assert_eq!(context.offset, 4),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn utf8_strings() {
+        let mut data = vec![0xF0, 0x9F, 0x90];
+        data.extend_from_slice("✓".as_bytes());
+        data.push(255);
+        data.extend_from_slice("ok".as_bytes());
+
+        let reader = EoReader::from(data);
+        reader.set_string_encoding(StringEncoding::Utf8);
+        assert_eq!(reader.get_string_until_break(), "\u{FFFD}✓");
+
+        let chunk = reader.chunks().next().unwrap();
+        assert_eq!(chunk.get_string_encoding(), StringEncoding::Utf8);
+        assert_eq!(chunk.get_string(), "ok");
+
+        assert_eq!(reader.invalid_string_sequences(), 1);
+    }
+
+    #[test]
+    fn counts_zero_bytes_in_numbers() {
+        let reader = EoReader::from(vec![0, 5, 1, 0, 0, 0]);
+        let coords = Coords::deserialize(&reader).unwrap();
+        assert_eq!(coords, Coords { x: 0, y: 4 });
+        assert_eq!(reader.invalid_numbers(), 1);
+
+        assert_eq!(reader.get_int(), 0);
+        assert_eq!(reader.invalid_numbers(), 2);
+    }
+
+    #[test]
+    fn strict_number_mode_fails_generated_types() {
+        let reader = EoReader::from(vec![2, 0, 3, 4]);
+        reader.set_strict_number_mode(true);
+        match Coords::deserialize(&reader) {
+            Err(EoReaderError::InvalidNumber(DecodeError::InvalidByte(0, 0), context)) => {
+                assert_eq!(context.packet.as_deref(), Some("Coords"));
+                assert_eq!(context.offset, 1);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        // only the first error is kept, and the reader can carry on
+        assert_eq!(
+            Coords::deserialize(&reader.slice(2..)).unwrap(),
+            Coords { x: 2, y: 3 }
+        );
+        assert!(reader.check_errors().is_ok());
+    }
+
+    #[test]
+    fn generated_code_checks_limits() {
+        let mut writer = EoWriter::new();
+        OnlinePlayer {
+            name: "vulture".to_string(),
+            title: "the brave".to_string(),
+            ..Default::default()
+        }
+        .serialize(&mut writer)
+        .unwrap();
+
+        let reader = EoReader::new(writer.to_byte_array());
+        reader.set_limits(ReaderLimits::new().max_string_length(8));
+
+        let error = OnlinePlayer::deserialize(&reader).unwrap_err();
+        assert!(matches!(error, EoReaderError::StringTooLong(9, 8, _)));
+        assert_eq!(error.context().field.as_deref(), Some("title"));
+    }
+
+    #[test]
+    fn generated_code_checks_counts_against_remaining() {
+        // a row claiming 5 tiles with only one tile of data
+        let reader = EoReader::from(vec![2, 6, 2, 2]);
+        let error = MapTileSpecRow::deserialize(&reader).unwrap_err();
+        assert!(matches!(error, EoReaderError::UnexpectedEof(10, 2, _)));
+        assert_eq!(error.context().field.as_deref(), Some("tiles"));
+    }
+
+    #[test]
+    fn string_until_break_in_chunked_reading_mode() {
+        let reader = EoReader::from(vec![b'H', b'i', 255, 2, 255, b'!']);
+        reader.set_chunked_reading_mode(true);
+        assert_eq!(reader.get_string_until_break(), "Hi");
+        assert_eq!(reader.get_char(), 1);
+        reader.next_chunk().unwrap();
+        assert_eq!(reader.get_string_until_break(), "!");
+        assert_eq!(reader.remaining(), 0);
+    }
+
+    #[test]
+    fn io_read() {
+        use std::io::Read;
+
+        let reader = EoReader::from(vec![1, 2, 255, 3]);
+        reader.set_chunked_reading_mode(true);
+
+        let mut buf = Vec::new();
+        (&reader).read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2]);
+
+        reader.next_chunk().unwrap();
+        reader.set_chunked_reading_mode(false);
+
+        let mut reader = reader;
+        let mut buf = [0; 4];
+        assert_eq!(reader.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 3);
+    }
+
+    #[test]
+    fn array_helpers() {
+        let reader = EoReader::from(vec![2, 3, 4, 5]);
+        reader.set_limits(ReaderLimits::new().max_array_length(1));
+        assert!(matches!(
+            reader.get_array::<Coords>(2),
+            Err(EoReaderError::ArrayTooLong(2, 1, _))
+        ));
+
+        let reader = EoReader::from(vec![2, 3, 255, 4, 5, 255, 255, 6, 7]);
+        assert!(matches!(
+            reader.get_delimited_array::<Coords>(),
+            Err(EoReaderError::ChunkedReadingDisabled(_))
+        ));
+
+        // an empty chunk ends the array
+        reader.set_chunked_reading_mode(true);
+        let coords: Vec<Coords> = reader.get_delimited_array().unwrap();
+        assert_eq!(coords.len(), 2);
+        reader.next_chunk().unwrap();
+        assert_eq!(
+            reader.get_array::<Coords>(1).unwrap(),
+            [Coords { x: 5, y: 6 }]
+        );
+    }
+}
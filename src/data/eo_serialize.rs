@@ -1,4 +1,5 @@
 use super::{eo_reader::EoReaderError, eo_writer::EoWriterError, EoReader, EoWriter};
+use bytes::Bytes;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -15,7 +16,106 @@ impl From<EoWriterError> for EoSerializeError {
     }
 }
 
+#[derive(Error, Debug)]
+pub enum ForeignBytesError {
+    #[error("{0}")]
+    ReadError(EoReaderError),
+    #[error("{0} bytes were left over after deserializing")]
+    TrailingBytes(usize),
+}
+
+impl From<EoReaderError> for ForeignBytesError {
+    fn from(e: EoReaderError) -> Self {
+        Self::ReadError(e)
+    }
+}
+
+/// deserializes a value from bytes serialized by another protocol crate, such as `eo_protocol`
+///
+/// Both crates write the same wire format, so a project moving over one handler at a time can
+/// pass values across as bytes instead of converting them field by field. Going the other way,
+/// [to_bytes](EoSerialize::to_bytes) gives bytes the other crate can deserialize.
+///
+/// Unlike [from_bytes](EoSerialize::from_bytes), every byte has to be used. Bytes left over
+/// usually mean the two crates disagree about the type.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     data::{from_foreign_bytes, ForeignBytesError},
+///     protocol::Coords,
+/// };
+///
+/// // as written by the other crate
+/// let bytes = [6, 11];
+/// let coords: Coords = from_foreign_bytes(&bytes).unwrap();
+/// assert_eq!(coords, Coords { x: 5, y: 10 });
+///
+/// assert!(matches!(
+///     from_foreign_bytes::<Coords>(&[6, 11, 1]),
+///     Err(ForeignBytesError::TrailingBytes(1))
+/// ));
+/// ```
+pub fn from_foreign_bytes<T: EoSerialize>(bytes: &[u8]) -> Result<T, ForeignBytesError> {
+    let reader = EoReader::from(bytes);
+    let value = T::deserialize(&reader)?;
+    match bytes.len() - reader.position() {
+        0 => Ok(value),
+        left_over => Err(ForeignBytesError::TrailingBytes(left_over)),
+    }
+}
+
 pub trait EoSerialize: Sized {
     fn deserialize(reader: &EoReader) -> Result<Self, EoReaderError>;
     fn serialize(&self, writer: &mut EoWriter) -> Result<(), EoSerializeError>;
+
+    /// deserializes an instance from a byte buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use eolib::{data::EoSerialize, protocol::Coords};
+    ///
+    /// let coords = Coords::from_bytes(&Bytes::from_static(&[6, 11])).unwrap();
+    /// assert_eq!(coords, Coords { x: 5, y: 10 });
+    /// ```
+    fn from_bytes(bytes: &Bytes) -> Result<Self, EoReaderError> {
+        let reader = EoReader::new(bytes.clone());
+        Self::deserialize(&reader)
+    }
+
+    /// serializes the instance into a byte buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::{data::EoSerialize, protocol::Coords};
+    ///
+    /// let bytes = Coords { x: 5, y: 10 }.to_bytes().unwrap();
+    /// assert_eq!(&bytes[..], [6, 11]);
+    /// ```
+    fn to_bytes(&self) -> Result<Bytes, EoSerializeError> {
+        let mut writer = EoWriter::new();
+        self.serialize(&mut writer)?;
+        Ok(writer.to_byte_array())
+    }
+
+    /// returns the number of bytes [serialize](EoSerialize::serialize) writes
+    ///
+    /// useful for sizing [EoWriter::with_capacity] and length fields. generated types compute
+    /// it from their fields, the default implementation serializes the instance and returns
+    /// `0` if that fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::{data::EoSerialize, protocol::Coords};
+    ///
+    /// assert_eq!(Coords { x: 5, y: 10 }.encoded_size(), 2);
+    /// ```
+    fn encoded_size(&self) -> usize {
+        self.to_bytes().map(|bytes| bytes.len()).unwrap_or(0)
+    }
 }
@@ -1,8 +1,10 @@
+use std::{borrow::Cow, cmp, io};
+
 use bytes::{BufMut, Bytes, BytesMut};
 use encoding_rs::WINDOWS_1252;
 use thiserror::Error;
 
-use super::{encode_number, encode_string, CHAR_MAX, INT_MAX, SHORT_MAX, THREE_MAX};
+use super::{encode_string, EoNumber, StrictnessProfile, CHAR_MAX, INT_MAX, SHORT_MAX, THREE_MAX};
 
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum EoWriterError {
@@ -14,6 +16,10 @@ pub enum EoWriterError {
     InvalidThreeValue(i32),
     #[error("Invalid int value {0} must be between 0 and {}", INT_MAX)]
     InvalidIntValue(i64),
+    #[error("Invalid string length {0} must be {1}")]
+    InvalidStringLength(usize, usize),
+    #[error("String contains a 0xFF break byte at index {0}")]
+    UnsanitizedString(usize),
     #[error("{0}")]
     Other(String),
 }
@@ -24,6 +30,57 @@ impl From<String> for EoWriterError {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What an [EoWriter] does with `0xFF` bytes in strings while string sanitization is on
+///
+/// `0xFF` is the break byte in chunked sections, so one inside a string would end the chunk
+/// early. the check is done on the WINDOWS-1252 encoded string, where `ÿ` is the only
+/// character that becomes `0xFF`.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::{EoWriter, EoWriterError, StringSanitizationPolicy};
+///
+/// let mut writer = EoWriter::new();
+/// writer.set_string_sanitization_mode(true);
+///
+/// writer.add_string("ÿ").unwrap();
+/// writer.set_string_sanitization_policy(StringSanitizationPolicy::ReplaceWith(b'?'));
+/// writer.add_string("ÿ").unwrap();
+///
+/// writer.set_string_sanitization_policy(StringSanitizationPolicy::Error);
+/// assert_eq!(
+///     writer.add_string("Heÿ"),
+///     Err(EoWriterError::UnsanitizedString(2))
+/// );
+///
+/// assert_eq!(&writer.to_byte_array()[..], b"y?");
+/// ```
+pub enum StringSanitizationPolicy {
+    /// replaces each `0xFF` byte with another byte, `y` by default like the official client
+    ReplaceWith(u8),
+    /// fails with [UnsanitizedString](EoWriterError::UnsanitizedString)
+    Error,
+    /// writes `0xFF` bytes as they are
+    Allow,
+}
+
+impl Default for StringSanitizationPolicy {
+    fn default() -> Self {
+        Self::ReplaceWith(b'y')
+    }
+}
+
+// maps a negative int above i32::MAX the same way as encode_number
+fn unwrap_negative_int(int: i32) -> i64 {
+    if int < 0 {
+        (int as i64).abs() + i32::MAX as i64
+    } else {
+        int as i64
+    }
+}
+
 #[derive(Debug, Default)]
 /// A writer for writing data to an EO data stream
 ///
@@ -48,6 +105,8 @@ impl From<String> for EoWriterError {
 pub struct EoWriter {
     data: BytesMut,
     string_sanitization_mode: bool,
+    string_sanitization_policy: StringSanitizationPolicy,
+    strict_int_mode: bool,
 }
 
 impl EoWriter {
@@ -74,14 +133,48 @@ impl EoWriter {
         self.data.put_slice(bytes);
     }
 
+    /// adds a [u16] to the data stream as two raw little endian bytes
+    ///
+    /// raw numbers aren't EO encoded, so they can be any value in their type's range. the
+    /// protocol only uses them inside some file payloads
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoWriter;
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_raw_short_le(0x1234);
+    /// writer.add_raw_short_be(0x1234);
+    ///
+    /// assert_eq!(&writer.to_byte_array()[..], [0x34, 0x12, 0x12, 0x34]);
+    /// ```
+    pub fn add_raw_short_le(&mut self, short: u16) {
+        self.data.put_u16_le(short);
+    }
+
+    /// adds a [u16] to the data stream as two raw big endian bytes
+    pub fn add_raw_short_be(&mut self, short: u16) {
+        self.data.put_u16(short);
+    }
+
+    /// adds a [u32] to the data stream as four raw little endian bytes
+    pub fn add_raw_int_le(&mut self, int: u32) {
+        self.data.put_u32_le(int);
+    }
+
+    /// adds a [u32] to the data stream as four raw big endian bytes
+    pub fn add_raw_int_be(&mut self, int: u32) {
+        self.data.put_u32(int);
+    }
+
     /// adds a char to the data stream
     pub fn add_char(&mut self, char: i32) -> Result<(), EoWriterError> {
         if !(0..=CHAR_MAX).contains(&char) {
             return Err(EoWriterError::InvalidCharValue(char));
         }
 
-        let encoded = encode_number(char)?;
-        self.data.put_slice(&encoded[0..1]);
+        self.put_number(char as i64, 1);
         Ok(())
     }
 
@@ -91,8 +184,7 @@ impl EoWriter {
             return Err(EoWriterError::InvalidShortValue(short));
         }
 
-        let encoded = encode_number(short)?;
-        self.data.put_slice(&encoded[0..2]);
+        self.put_number(short as i64, 2);
         Ok(())
     }
 
@@ -102,43 +194,292 @@ impl EoWriter {
             return Err(EoWriterError::InvalidThreeValue(three));
         }
 
-        let encoded = encode_number(three)?;
-        self.data.put_slice(&encoded[0..3]);
+        self.put_number(three as i64, 3);
         Ok(())
     }
 
     /// adds an int to the data stream
+    ///
+    /// negative values are written the same way as [encode_number](super::encode_number),
+    /// as `-int + i32::MAX`, which [get_int](super::EoReader::get_int) reads back as a
+    /// different number. values from `-1949668433` to [i32::MAX] can be written, or only
+    /// `0` to [i32::MAX] in strict int mode, see [set_strict_int_mode](EoWriter::set_strict_int_mode)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoReader, EoWriter, EoWriterError};
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_int(i32::MAX).unwrap();
+    /// writer.add_int(-1).unwrap();
+    /// assert_eq!(
+    ///     writer.add_int(-1949668434),
+    ///     Err(EoWriterError::InvalidIntValue(4097152081))
+    /// );
+    ///
+    /// writer.set_strict_int_mode(true);
+    /// assert_eq!(writer.add_int(-1), Err(EoWriterError::InvalidIntValue(-1)));
+    ///
+    /// let reader = EoReader::new(writer.to_byte_array());
+    /// assert_eq!(reader.get_int(), i32::MAX);
+    /// assert_eq!(reader.get_int(), i32::MIN);
+    /// ```
     pub fn add_int(&mut self, int: i32) -> Result<(), EoWriterError> {
-        let encoded = encode_number(int)?;
-        self.data.put_slice(&encoded[0..4]);
+        if self.strict_int_mode && int < 0 {
+            return Err(EoWriterError::InvalidIntValue(int as i64));
+        }
+
+        let number = unwrap_negative_int(int);
+        if number >= INT_MAX {
+            return Err(EoWriterError::InvalidIntValue(number));
+        }
+
+        self.put_number(number, 4);
         Ok(())
     }
 
-    fn sanitize_string(&self, string: &str) -> String {
-        if self.string_sanitization_mode {
-            string
-                .chars()
-                .map(|c| if c as i32 == 0xff { 0x79 as char } else { c })
-                .collect()
-        } else {
-            string.to_owned()
+    /// adds an int to the data stream without checking it, even in strict int mode
+    ///
+    /// negative values are written like [add_int](EoWriter::add_int) and values that can't be
+    /// encoded are wrapped around to `value % INT_MAX`
+    pub fn add_int_unchecked(&mut self, int: i32) {
+        self.put_number(unwrap_negative_int(int), 4);
+    }
+
+    // Writes the first `size` bytes of encode_number(number) straight into the buffer. Each
+    // byte is one base CHAR_MAX digit plus one, or 254 once the digits run out
+    fn put_number(&mut self, number: i64, size: usize) {
+        let mut place = 1;
+        for i in 0..size {
+            if i == 0 || number >= place {
+                self.data
+                    .put_u8((number / place % CHAR_MAX as i64) as u8 + 1);
+            } else {
+                self.data.put_u8(254);
+            }
+            place *= CHAR_MAX as i64;
         }
     }
 
+    /// adds an encoded number of the type `N` to the data stream
+    ///
+    /// see [EoNumber]
+    pub fn add<N: EoNumber>(&mut self, number: impl Into<i32>) -> Result<(), EoWriterError> {
+        N::write(self, number.into())
+    }
+
+    /// writes a section with `write` on a sub-writer, adding it only if `write` succeeds
+    ///
+    /// the sub-writer has the same string sanitization and strict int settings. a section
+    /// that fails part way leaves the data as it was, so optional and trailing sections can be
+    /// written without cleaning up after an error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoWriter, EoWriterError};
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_char(1).unwrap();
+    ///
+    /// let result = writer.nested(|writer| {
+    ///     writer.add_char(2)?;
+    ///     writer.add_char(300)
+    /// });
+    /// assert_eq!(result, Err(EoWriterError::InvalidCharValue(300)));
+    ///
+    /// writer.nested(|writer| writer.add_char(3)).unwrap();
+    /// assert_eq!(&writer.to_byte_array()[..], [2, 4]);
+    /// ```
+    pub fn nested<T, E>(
+        &mut self,
+        write: impl FnOnce(&mut EoWriter) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut writer = self.sub_writer();
+        let result = write(&mut writer)?;
+        self.data.extend_from_slice(&writer.data);
+        Ok(result)
+    }
+
+    /// like [nested](EoWriter::nested) but adds the length of the section as an `N` before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::{EoChar, EoReader, EoWriter, EoWriterError};
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer
+    ///     .nested_with_length::<EoChar, _, EoWriterError>(|writer| {
+    ///         writer.add_string("Hello")
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let reader = EoReader::new(writer.to_byte_array());
+    /// let length = reader.get_char() as usize;
+    /// assert_eq!(reader.get_fixed_string(length), "Hello");
+    /// ```
+    pub fn nested_with_length<N: EoNumber, T, E: From<EoWriterError>>(
+        &mut self,
+        write: impl FnOnce(&mut EoWriter) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut writer = self.sub_writer();
+        let result = write(&mut writer)?;
+        let length = i32::try_from(writer.data.len()).unwrap_or(i32::MAX);
+        self.add::<N>(length)?;
+        self.data.extend_from_slice(&writer.data);
+        Ok(result)
+    }
+
+    fn sub_writer(&self) -> EoWriter {
+        EoWriter {
+            data: BytesMut::new(),
+            string_sanitization_mode: self.string_sanitization_mode,
+            string_sanitization_policy: self.string_sanitization_policy,
+            strict_int_mode: self.strict_int_mode,
+        }
+    }
+
+    // encodes a string as WINDOWS-1252, applying the sanitization policy if the mode is on
+    fn encode_windows_1252<'a>(&self, string: &'a str) -> Result<Cow<'a, [u8]>, EoWriterError> {
+        let (mut bytes, _, _) = WINDOWS_1252.encode(string);
+        if !self.string_sanitization_mode {
+            return Ok(bytes);
+        }
+
+        match self.string_sanitization_policy {
+            StringSanitizationPolicy::ReplaceWith(replacement) => {
+                if bytes.contains(&0xff) {
+                    for byte in bytes.to_mut().iter_mut().filter(|b| **b == 0xff) {
+                        *byte = replacement;
+                    }
+                }
+            }
+            StringSanitizationPolicy::Error => {
+                if let Some(index) = bytes.iter().position(|b| *b == 0xff) {
+                    return Err(EoWriterError::UnsanitizedString(index));
+                }
+            }
+            StringSanitizationPolicy::Allow => {}
+        }
+
+        Ok(bytes)
+    }
+
     /// adds a string to the data stream
-    pub fn add_string(&mut self, string: &str) {
-        let string = self.sanitize_string(string);
-        let (string, _, _) = WINDOWS_1252.encode(&string);
+    pub fn add_string(&mut self, string: &str) -> Result<(), EoWriterError> {
+        let string = self.encode_windows_1252(string)?;
         self.data.put_slice(&string);
+        Ok(())
+    }
+
+    /// adds a string followed by a `0xFF` break to the data stream
+    ///
+    /// the string is always sanitized with the current policy, whatever the string
+    /// sanitization mode, so it can't end the chunk early unless the policy is
+    /// [Allow](StringSanitizationPolicy::Allow). read it back with
+    /// [EoReader::get_string_until_break](super::EoReader::get_string_until_break)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoWriter;
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_string_with_break("Hi").unwrap();
+    /// writer.add_string_with_break("ÿ").unwrap();
+    ///
+    /// assert_eq!(&writer.to_byte_array()[..], [b'H', b'i', 0xFF, b'y', 0xFF]);
+    /// ```
+    pub fn add_string_with_break(&mut self, string: &str) -> Result<(), EoWriterError> {
+        let string_sanitization_mode = self.string_sanitization_mode;
+        self.string_sanitization_mode = true;
+        let result = self.add_string(string);
+        self.string_sanitization_mode = string_sanitization_mode;
+        result?;
+        self.add_byte(0xff);
+        Ok(())
+    }
+
+    /// adds a fixed length string to the data stream
+    ///
+    /// strings longer than `length` are truncated. shorter strings are padded with `0xFF` bytes
+    /// when `padded` is set, otherwise [InvalidStringLength](EoWriterError::InvalidStringLength)
+    /// is returned
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoWriter;
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_fixed_string("Void", 6, true).unwrap();
+    /// writer.add_fixed_string("Aeven", 2, false).unwrap();
+    ///
+    /// assert_eq!(&writer.to_byte_array()[..], b"Void\xff\xffAe");
+    /// ```
+    pub fn add_fixed_string(
+        &mut self,
+        string: &str,
+        length: usize,
+        padded: bool,
+    ) -> Result<(), EoWriterError> {
+        let string = self.encode_windows_1252(string)?;
+        if string.len() < length && !padded {
+            return Err(EoWriterError::InvalidStringLength(string.len(), length));
+        }
+
+        let end = cmp::min(string.len(), length);
+        self.data.put_slice(&string[..end]);
+        self.data.put_bytes(0xff, length - end);
+        Ok(())
     }
 
     /// encodes a string and adds it to the data stream
-    pub fn add_encoded_string(&mut self, string: &str) {
-        let string = self.sanitize_string(string);
-        let (mut string, _, _) = WINDOWS_1252.encode(&string);
+    pub fn add_encoded_string(&mut self, string: &str) -> Result<(), EoWriterError> {
+        let mut string = self.encode_windows_1252(string)?;
         let string = string.to_mut();
         encode_string(&mut *string);
         self.data.put_slice(string);
+        Ok(())
+    }
+
+    /// encodes a fixed length string and adds it to the data stream
+    ///
+    /// a string shorter than `length` is followed by a `0xFF` break and padded with `0xFF`
+    /// bytes up to `length` before being encoded. a string that fills the field is written
+    /// without a break, and longer strings are truncated. this is the layout expected by
+    /// [EoReader::get_fixed_encoded_string](super::EoReader::get_fixed_encoded_string).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoWriter;
+    ///
+    /// let mut writer = EoWriter::new();
+    /// writer.add_fixed_encoded_string("Void", 6).unwrap();
+    ///
+    /// assert_eq!(&writer.to_byte_array()[..], [0xFF, 0xFF, 0x69, 0x36, 0x5E, 0x49]);
+    /// ```
+    pub fn add_fixed_encoded_string(
+        &mut self,
+        string: &str,
+        length: usize,
+    ) -> Result<(), EoWriterError> {
+        if length == 0 {
+            return Ok(());
+        }
+
+        let string = self.encode_windows_1252(string)?;
+        let end = cmp::min(string.len(), length);
+
+        let mut buf = Vec::with_capacity(length);
+        buf.extend_from_slice(&string[..end]);
+        buf.resize(length, 0xff);
+        encode_string(&mut buf);
+        self.data.put_slice(&buf);
+        Ok(())
     }
 
     /// gets the string sanitization mode
@@ -147,19 +488,124 @@ impl EoWriter {
     }
 
     /// sets the string sanitization mode
+    ///
+    /// generated code turns it on inside chunked sections, see [StringSanitizationPolicy]
     pub fn set_string_sanitization_mode(&mut self, mode: bool) {
         self.string_sanitization_mode = mode;
     }
 
+    /// gets the string sanitization policy
+    pub fn get_string_sanitization_policy(&self) -> StringSanitizationPolicy {
+        self.string_sanitization_policy
+    }
+
+    /// sets what is done with `0xFF` bytes in strings while string sanitization is on
+    pub fn set_string_sanitization_policy(&mut self, policy: StringSanitizationPolicy) {
+        self.string_sanitization_policy = policy;
+    }
+
+    /// applies the string sanitization policy and strict int mode of `profile`
+    pub fn set_strictness_profile(&mut self, profile: StrictnessProfile) {
+        self.string_sanitization_policy = profile.string_sanitization_policy;
+        self.strict_int_mode = profile.strict_int_mode;
+    }
+
+    /// gets the strict int mode
+    pub fn get_strict_int_mode(&self) -> bool {
+        self.strict_int_mode
+    }
+
+    /// sets the strict int mode
+    ///
+    /// while it's on [add_int](EoWriter::add_int) rejects negative values instead of writing
+    /// a number that reads back differently
+    pub fn set_strict_int_mode(&mut self, mode: bool) {
+        self.strict_int_mode = mode;
+    }
+
+    /// returns the data written so far
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
     /// freezes the data and returns a [Bytes] object that can be freely cloned
     pub fn to_byte_array(self) -> Bytes {
         self.data.freeze()
     }
+
+    /// returns the written data as a [Bytes] object and empties the writer so it can be reused
+    ///
+    /// the writer keeps its unused capacity, and gets the rest of its allocation back once every
+    /// [Bytes] taken from it has been dropped
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoWriter;
+    ///
+    /// let mut writer = EoWriter::with_capacity(64);
+    /// writer.add_string("first").unwrap();
+    /// assert_eq!(&writer.take()[..], b"first");
+    ///
+    /// writer.add_string("second").unwrap();
+    /// assert_eq!(&writer.take()[..], b"second");
+    /// ```
+    pub fn take(&mut self) -> Bytes {
+        self.data.split().freeze()
+    }
+
+    /// removes all written data, keeping the allocated capacity
+    ///
+    /// the string sanitization mode and policy are left unchanged
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// writes the data written so far to `writer` and empties the writer
+    ///
+    /// calling this as data is added lets large files be produced without holding all of
+    /// them in memory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::data::EoWriter;
+    ///
+    /// let mut file = Vec::new();
+    /// let mut writer = EoWriter::new();
+    ///
+    /// for id in 1..=3 {
+    ///     writer.add_short(id).unwrap();
+    ///     writer.write_to(&mut file).unwrap();
+    /// }
+    ///
+    /// assert_eq!(file, [2, 254, 3, 254, 4, 254]);
+    /// ```
+    pub fn write_to(&mut self, writer: &mut impl io::Write) -> io::Result<()> {
+        writer.write_all(&self.data)?;
+        self.data.clear();
+        Ok(())
+    }
+}
+
+impl io::Write for EoWriter {
+    /// adds raw bytes to the data stream
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.add_bytes(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::data::{eo_writer::EoWriterError, CHAR_MAX, SHORT_MAX, THREE_MAX};
+    use crate::data::{
+        encode_number, eo_writer::EoWriterError, EoChar, EoReader, EoShort,
+        StringSanitizationPolicy, CHAR_MAX, SHORT_MAX, THREE_MAX,
+    };
 
     use super::EoWriter;
 
@@ -169,6 +615,28 @@ mod tests {
         assert_eq!(writer.data.capacity(), 10);
     }
 
+    #[test]
+    fn clear() {
+        let mut writer = EoWriter::with_capacity(10);
+        writer.add_string("hello").unwrap();
+        writer.clear();
+        assert!(writer.data.is_empty());
+        assert_eq!(writer.data.capacity(), 10);
+    }
+
+    #[test]
+    fn take_reuses_allocation() {
+        let mut writer = EoWriter::with_capacity(10);
+        writer.add_string("hello").unwrap();
+        let data = writer.take();
+        assert_eq!(&data[..], b"hello");
+        assert!(writer.data.is_empty());
+
+        drop(data);
+        writer.data.reserve(10);
+        assert_eq!(writer.data.capacity(), 10);
+    }
+
     #[test]
     fn add_byte() {
         let mut writer = EoWriter::with_capacity(1);
@@ -204,6 +672,41 @@ mod tests {
         assert_eq!(&writer.data[..], [2, 0xfe, 0xfe, 0xfe]);
     }
 
+    #[test]
+    fn numbers_match_encode_number() {
+        let values = [
+            0,
+            1,
+            CHAR_MAX - 1,
+            CHAR_MAX,
+            SHORT_MAX - 1,
+            SHORT_MAX,
+            THREE_MAX - 1,
+            THREE_MAX,
+            i32::MAX,
+            -1,
+        ];
+
+        for value in values {
+            let encoded = encode_number(value).unwrap();
+            let mut writer = EoWriter::new();
+            if (0..=CHAR_MAX).contains(&value) {
+                writer.add_char(value).unwrap();
+                assert_eq!(&writer.data.split()[..], &encoded[..1], "char {}", value);
+            }
+            if (0..=SHORT_MAX).contains(&value) {
+                writer.add_short(value).unwrap();
+                assert_eq!(&writer.data.split()[..], &encoded[..2], "short {}", value);
+            }
+            if (0..=THREE_MAX).contains(&value) {
+                writer.add_three(value).unwrap();
+                assert_eq!(&writer.data.split()[..], &encoded[..3], "three {}", value);
+            }
+            writer.add_int(value).unwrap();
+            assert_eq!(&writer.data.split()[..], encoded, "int {}", value);
+        }
+    }
+
     #[test]
     fn add_negative_char() {
         let mut writer = EoWriter::with_capacity(1);
@@ -225,6 +728,47 @@ mod tests {
         assert_eq!(result, EoWriterError::InvalidThreeValue(-1));
     }
 
+    #[test]
+    fn int_range() {
+        let mut writer = EoWriter::new();
+        writer.add_int(-1949668433).unwrap();
+        assert_eq!(&writer.data.split()[..], [253, 253, 253, 253]);
+        assert_eq!(
+            writer.add_int(i32::MIN),
+            Err(EoWriterError::InvalidIntValue(4294967295))
+        );
+        assert_eq!(
+            encode_number(i32::MIN),
+            Err(EoWriterError::InvalidIntValue(4294967295))
+        );
+
+        writer.set_strict_int_mode(true);
+        writer.add_int(0).unwrap();
+        writer.add_int(i32::MAX).unwrap();
+        assert_eq!(
+            writer.add_int(-1949668433),
+            Err(EoWriterError::InvalidIntValue(-1949668433))
+        );
+
+        let reader = EoReader::new(writer.to_byte_array());
+        assert_eq!(reader.get_int(), 0);
+        assert_eq!(reader.get_int(), i32::MAX);
+    }
+
+    #[test]
+    fn add_int_unchecked() {
+        let mut writer = EoWriter::new();
+        writer.set_strict_int_mode(true);
+        writer.add_int_unchecked(-1);
+        writer.add_int_unchecked(-1949668434);
+        assert_eq!(&writer.data[..], [168, 181, 154, 133, 1, 1, 1, 1]);
+
+        // wrapped to 0
+        let reader = EoReader::new(writer.to_byte_array());
+        assert_eq!(reader.get_int(), i32::MIN);
+        assert_eq!(reader.get_int(), 0);
+    }
+
     #[test]
     fn add_negative_int() {
         let mut writer = EoWriter::with_capacity(4);
@@ -260,15 +804,184 @@ mod tests {
         assert_eq!(result, EoWriterError::InvalidIntValue(i32::MAX as i64 * 2));
     }
 
+    #[test]
+    fn add_fixed_string() {
+        let mut writer = EoWriter::new();
+        writer.add_fixed_string("foo", 3, false).unwrap();
+        assert_eq!(&writer.data[..], b"foo");
+    }
+
+    #[test]
+    fn add_padded_fixed_string() {
+        let mut writer = EoWriter::new();
+        writer.add_fixed_string("foo", 5, true).unwrap();
+        assert_eq!(&writer.data[..], [b'f', b'o', b'o', 0xff, 0xff]);
+    }
+
+    #[test]
+    fn add_truncated_fixed_string() {
+        let mut writer = EoWriter::new();
+        writer.add_fixed_string("foobar", 3, true).unwrap();
+        writer.add_fixed_string("bazqux", 3, false).unwrap();
+        assert_eq!(&writer.data[..], b"foobaz");
+    }
+
+    #[test]
+    fn add_short_fixed_string() {
+        let mut writer = EoWriter::new();
+        let result = writer.add_fixed_string("foo", 5, false).unwrap_err();
+        assert_eq!(result, EoWriterError::InvalidStringLength(3, 5));
+        assert!(writer.data.is_empty());
+    }
+
+    #[test]
+    fn add_fixed_encoded_string() {
+        let mut writer = EoWriter::new();
+        writer.add_fixed_encoded_string("Void", 5).unwrap();
+        assert_eq!(&writer.data[..], [0xff, 0x3b, 0x64, 0x30, 0x77]);
+    }
+
+    #[test]
+    fn add_truncated_fixed_encoded_string() {
+        let mut writer = EoWriter::new();
+        writer.add_fixed_encoded_string("Voids!", 5).unwrap();
+        assert_eq!(&writer.data[..], [0x5a, 0x3b, 0x64, 0x30, 0x77]);
+    }
+
+    #[test]
+    fn fixed_encoded_string_round_trip() {
+        for name in [
+            "",
+            "a",
+            "Aeven",
+            "A map name of 23 chars!",
+            "A map name of 24 chars!!",
+        ] {
+            let mut writer = EoWriter::new();
+            writer.add_fixed_encoded_string(name, 24).unwrap();
+            let reader = EoReader::new(writer.to_byte_array());
+            assert_eq!(reader.try_get_fixed_encoded_string(24).unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn raw_numbers_round_trip() {
+        let mut writer = EoWriter::new();
+        writer.add_raw_short_le(0xfffe);
+        writer.add_raw_short_be(0x0102);
+        writer.add_raw_int_le(0xdeadbeef);
+        writer.add_raw_int_be(0x01020304);
+        assert_eq!(
+            &writer.data[..],
+            [0xfe, 0xff, 0x01, 0x02, 0xef, 0xbe, 0xad, 0xde, 0x01, 0x02, 0x03, 0x04]
+        );
+
+        let reader = EoReader::new(writer.to_byte_array());
+        assert_eq!(reader.get_raw_short_le(), 0xfffe);
+        assert_eq!(reader.get_raw_short_be(), 0x0102);
+        assert_eq!(reader.get_raw_int_le(), 0xdeadbeef);
+        assert_eq!(reader.get_raw_int_be(), 0x01020304);
+        assert_eq!(reader.remaining(), 0);
+    }
+
     #[test]
     fn string_sanitization_mode() {
         let mut writer = EoWriter::new();
-        writer.add_string("ÿ");
+        writer.add_string("ÿ").unwrap();
         assert_eq!(&writer.to_byte_array()[..], &[0xff]);
 
         let mut writer = EoWriter::new();
         writer.set_string_sanitization_mode(true);
-        writer.add_string("ÿ");
+        writer.add_string("ÿ").unwrap();
         assert_eq!(&writer.to_byte_array()[..], &[0x79]);
     }
+
+    #[test]
+    fn string_sanitization_policy() {
+        let mut writer = EoWriter::new();
+        writer.set_string_sanitization_mode(true);
+        writer.set_string_sanitization_policy(StringSanitizationPolicy::ReplaceWith(b'?'));
+        writer.add_string("ÿÿ").unwrap();
+        writer.add_fixed_string("ÿ", 2, true).unwrap();
+        assert_eq!(&writer.take()[..], [b'?', b'?', b'?', 0xff]);
+
+        writer.set_string_sanitization_policy(StringSanitizationPolicy::Allow);
+        writer.add_string("ÿ").unwrap();
+        assert_eq!(&writer.take()[..], [0xff]);
+
+        writer.set_string_sanitization_policy(StringSanitizationPolicy::Error);
+        assert_eq!(
+            writer.add_fixed_encoded_string("aÿ", 4),
+            Err(EoWriterError::UnsanitizedString(1))
+        );
+        assert_eq!(
+            writer.add_string_with_break("ÿ"),
+            Err(EoWriterError::UnsanitizedString(0))
+        );
+        assert!(writer.data.is_empty());
+        assert!(writer.get_string_sanitization_mode());
+
+        // the policy only applies while the mode is on
+        writer.set_string_sanitization_mode(false);
+        writer.add_string("ÿ").unwrap();
+        assert_eq!(&writer.take()[..], [0xff]);
+    }
+
+    #[test]
+    fn io_write() {
+        use std::io::Write;
+
+        let mut writer = EoWriter::new();
+        write!(writer, "{}!", 42).unwrap();
+        assert_eq!(&writer.to_byte_array()[..], b"42!");
+    }
+
+    #[test]
+    fn nested() {
+        let mut writer = EoWriter::new();
+        writer.set_strict_int_mode(true);
+        writer.add_byte(1);
+
+        // the sub-writer keeps the settings and nothing is added when it fails
+        assert_eq!(
+            writer.nested(|writer| {
+                writer.add_byte(2);
+                writer.add_int(-1)
+            }),
+            Err(EoWriterError::InvalidIntValue(-1))
+        );
+        assert_eq!(&writer.data[..], [1]);
+
+        assert_eq!(
+            writer.nested(|writer| {
+                writer.add_byte(3);
+                Ok::<_, EoWriterError>(4)
+            }),
+            Ok(4)
+        );
+        assert_eq!(&writer.data[..], [1, 3]);
+    }
+
+    #[test]
+    fn nested_with_length() {
+        let mut writer = EoWriter::new();
+        writer
+            .nested_with_length::<EoShort, _, EoWriterError>(|writer| {
+                writer.add_bytes(&[0; 300]);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(writer.data.len(), 302);
+        assert_eq!(&writer.data[..2], &encode_number(300).unwrap()[..2]);
+
+        let mut writer = EoWriter::new();
+        assert_eq!(
+            writer.nested_with_length::<EoChar, _, EoWriterError>(|writer| {
+                writer.add_bytes(&[0; 300]);
+                Ok(())
+            }),
+            Err(EoWriterError::InvalidCharValue(300))
+        );
+        assert!(writer.data.is_empty());
+    }
 }
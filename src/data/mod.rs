@@ -1,3 +1,6 @@
+use encoding_rs::WINDOWS_1252;
+use thiserror::Error;
+
 /// The maximum value of an EO char (1-byte encoded integer type)
 pub const CHAR_MAX: i32 = 253;
 
@@ -10,6 +13,18 @@ pub const THREE_MAX: i32 = CHAR_MAX * CHAR_MAX * CHAR_MAX;
 /// The maximum value of an EO int (4-byte encoded integer type)
 pub const INT_MAX: i64 = CHAR_MAX as i64 * CHAR_MAX as i64 * CHAR_MAX as i64 * CHAR_MAX as i64;
 
+/// The number of bytes [EoWriter::add_char] writes
+pub const CHAR_SIZE: usize = 1;
+
+/// The number of bytes [EoWriter::add_short] writes
+pub const SHORT_SIZE: usize = 2;
+
+/// The number of bytes [EoWriter::add_three] writes
+pub const THREE_SIZE: usize = 3;
+
+/// The number of bytes [EoWriter::add_int] writes
+pub const INT_SIZE: usize = 4;
+
 /// Returns an encoded byte array from `number`
 ///
 /// EO uses a maximum of four bytes to represent a number
@@ -112,7 +127,7 @@ pub fn encode_number(number: i32) -> Result<[u8; 4], EoWriterError> {
 
     // Unwrap negative i32 to positive i64
     let mut number = if number < 0 {
-        number.abs() as i64 + i32::MAX as i64
+        (number as i64).abs() + i32::MAX as i64
     } else {
         number as i64
     };
@@ -168,12 +183,14 @@ pub fn encode_number(number: i32) -> Result<[u8; 4], EoWriterError> {
 /// ```
 ///
 /// * bytes with `254` are swapped to `1`
-/// `[43, 1, 1, 1]`
+///   `[43, 1, 1, 1]`
 /// * bytes are decremented by 1
-/// `[42, 0, 0, 0]`
+///   `[42, 0, 0, 0]`
 /// * bytes are multiplied by MAX's and summed
-/// `(0 * THREE_MAX) + (0 * SHORT_MAX) + (0 * CHAR_MAX) + 42 == 42`
+///   `(0 * THREE_MAX) + (0 * SHORT_MAX) + (0 * CHAR_MAX) + 42 == 42`
 ///
+/// `0` bytes are never written by [encode_number] and are read like `254`, see
+/// [decode_number_checked] to reject them
 pub fn decode_number(bytes: &[u8]) -> i32 {
     let mut data: [u8; 4] = [254, 254, 254, 254];
     for i in 0..4 {
@@ -192,6 +209,83 @@ pub fn decode_number(bytes: &[u8]) -> i32 {
         .wrapping_add(data[0] as i32)
 }
 
+/// Returns a decoded number from an EO byte array without wrapping
+///
+/// Like [decode_number] but returns an [i64] so values from 4-byte numbers larger than
+/// [i32::MAX] (up to `INT_MAX - 1`) are kept intact.
+///
+/// # Examples
+/// ```
+/// use eolib::data::decode_number_i64;
+///
+/// assert_eq!(decode_number_i64(&[43, 254, 254, 254]), 42);
+/// assert_eq!(decode_number_i64(&[253, 253, 253, 253]), 4_097_152_080);
+/// ```
+pub fn decode_number_i64(bytes: &[u8]) -> i64 {
+    let mut result = 0;
+    let mut multiplier = 1;
+    for i in 0..4 {
+        let byte = match bytes.get(i) {
+            Some(0) | Some(254) | None => 1,
+            Some(byte) => *byte,
+        };
+        result += (byte - 1) as i64 * multiplier;
+        multiplier *= CHAR_MAX as i64;
+    }
+    result
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("Invalid number length {0} must be between 1 and 4")]
+    InvalidLength(usize),
+    #[error("Invalid byte {0} at index {1}")]
+    InvalidByte(u8, usize),
+    #[error("Number {0} is too large for an i32")]
+    Overflow(i64),
+}
+
+/// Returns a decoded number from an EO byte array, rejecting bytes [encode_number] never
+/// writes
+///
+/// Each byte must be between `1` and `253`, or `254` for unused high bytes. A `0` (corrupt
+/// data), a `255` (a break), or a `254` followed by a used byte is an
+/// [InvalidByte](DecodeError::InvalidByte) error rather than being silently read as zero.
+/// Numbers larger than [i32::MAX] are an [Overflow](DecodeError::Overflow) error, see
+/// [decode_number_i64] to read them.
+///
+/// # Examples
+/// ```
+/// use eolib::data::{decode_number_checked, DecodeError};
+///
+/// assert_eq!(decode_number_checked(&[43, 254]), Ok(42));
+/// assert_eq!(decode_number_checked(&[43, 0]), Err(DecodeError::InvalidByte(0, 1)));
+/// assert_eq!(decode_number_checked(&[254, 2]), Err(DecodeError::InvalidByte(254, 0)));
+/// assert_eq!(
+///     decode_number_checked(&[253, 253, 253, 253]),
+///     Err(DecodeError::Overflow(4_097_152_080))
+/// );
+/// ```
+pub fn decode_number_checked(bytes: &[u8]) -> Result<i32, DecodeError> {
+    if bytes.is_empty() || bytes.len() > 4 {
+        return Err(DecodeError::InvalidLength(bytes.len()));
+    }
+
+    for (index, byte) in bytes.iter().enumerate() {
+        let filler_before_used_byte = *byte == 254 && bytes[index + 1..].iter().any(|b| *b != 254);
+        if *byte == 0 || *byte == 255 || filler_before_used_byte {
+            return Err(DecodeError::InvalidByte(*byte, index));
+        }
+    }
+
+    let number = decode_number_i64(bytes);
+    if number > i32::MAX as i64 {
+        return Err(DecodeError::Overflow(number));
+    }
+
+    Ok(number as i32)
+}
+
 /// Decodes a string in place
 ///
 /// This is used for map names and sign text in map files
@@ -251,9 +345,210 @@ pub fn encode_string(buf: &mut [u8]) {
     buf.reverse();
 }
 
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum StringEncodingError {
+    #[error("Invalid byte {0} at index {1} would be read back as a break")]
+    InvalidByte(u8, usize),
+}
+
+// every byte survives the cipher itself, since it only swaps bytes within 34..=125 and
+// passes the rest through, but encoded strings are read up to the first `0xFF` break
+fn check_encoded_string_bytes(buf: &[u8]) -> Result<(), StringEncodingError> {
+    match buf.iter().position(|b| *b == 0xff) {
+        Some(index) => Err(StringEncodingError::InvalidByte(buf[index], index)),
+        None => Ok(()),
+    }
+}
+
+/// Encodes a string in place, rejecting `0xFF` bytes that would end the string early when
+/// it's read back
+///
+/// Only `0xFF` is rejected. Bytes outside of `34..=125` aren't transformed by the cipher, so
+/// they decode back to themselves, and rejecting them would also reject spaces and `!`.
+///
+/// `buf` is left untouched if an error is returned.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::{try_encode_string, StringEncodingError};
+///
+/// let mut buf = b"Void".to_vec();
+/// try_encode_string(&mut buf).unwrap();
+/// assert_eq!(buf, [0x69, 0x36, 0x5E, 0x49]);
+///
+/// let mut buf = b"Hi th\xFFere".to_vec();
+/// let result = try_encode_string(&mut buf);
+/// assert_eq!(result, Err(StringEncodingError::InvalidByte(0xFF, 5)));
+/// assert_eq!(buf, b"Hi th\xFFere");
+/// ```
+pub fn try_encode_string(buf: &mut [u8]) -> Result<(), StringEncodingError> {
+    check_encoded_string_bytes(buf)?;
+    encode_string(buf);
+    Ok(())
+}
+
+/// Decodes a string in place, rejecting `0xFF` break and padding bytes
+///
+/// Like [try_encode_string], bytes outside of `34..=125` are allowed, since they're passed
+/// through unchanged.
+///
+/// `buf` is left untouched if an error is returned.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::{try_decode_string, StringEncodingError};
+///
+/// let mut buf = [0x69, 0x36, 0x5E, 0x49];
+/// try_decode_string(&mut buf).unwrap();
+/// assert_eq!(&buf, b"Void");
+///
+/// let mut buf = [0x69, 0x36, 0x5E, 0xFF];
+/// let result = try_decode_string(&mut buf);
+/// assert_eq!(result, Err(StringEncodingError::InvalidByte(0xFF, 3)));
+/// ```
+pub fn try_decode_string(buf: &mut [u8]) -> Result<(), StringEncodingError> {
+    check_encoded_string_bytes(buf)?;
+    decode_string(buf);
+    Ok(())
+}
+
+/// Returns the number of bytes [EoWriter::add_string] or [EoWriter::add_encoded_string]
+/// writes for `string`
+///
+/// Strings are written as WINDOWS-1252, so this is the number of characters unless some of
+/// them can't be represented and are written as HTML numeric character references. Fixed
+/// strings are always their length.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::string_size;
+///
+/// assert_eq!(string_size("Aeven"), 5);
+/// assert_eq!(string_size("naïve"), 5);
+/// assert_eq!(string_size("日"), "&#26085;".len());
+/// ```
+pub fn string_size(string: &str) -> usize {
+    if string.is_ascii() {
+        return string.len();
+    }
+
+    WINDOWS_1252.encode(string).0.len()
+}
+
+/// Returns true if `string` survives being written as an encoded string and read back
+///
+/// Checks the whole path used for map names and sign text: conversion to WINDOWS-1252,
+/// [encode_string], [decode_string], and the trimming at the first `0xFF` byte done when
+/// reading fixed length encoded strings.
+///
+/// Characters that can't be represented in WINDOWS-1252 or that map to `0xFF` (`ÿ`) are lost
+/// along the way.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::encoded_string_round_trips;
+///
+/// assert!(encoded_string_round_trips("Welcome to Aeven!"));
+/// assert!(!encoded_string_round_trips("naïve ÿ"));
+/// assert!(!encoded_string_round_trips("日本"));
+/// ```
+pub fn encoded_string_round_trips(string: &str) -> bool {
+    let (bytes, _, had_errors) = WINDOWS_1252.encode(string);
+    if had_errors {
+        return false;
+    }
+
+    let mut buf = bytes.into_owned();
+    encode_string(&mut buf);
+    decode_string(&mut buf);
+
+    let end = buf.iter().position(|b| *b == 0xff).unwrap_or(buf.len());
+    let (decoded, _, _) = WINDOWS_1252.decode(&buf[..end]);
+    decoded == string
+}
+
 mod eo_reader;
-pub use eo_reader::{EoReader, EoReaderError};
+pub use eo_reader::{
+    ChunkedReadingError, Chunks, EoReader, EoReaderError, ReaderContext, ReaderLimits,
+    StringEncoding,
+};
 mod eo_writer;
-pub use eo_writer::{EoWriter, EoWriterError};
+pub use eo_writer::{EoWriter, EoWriterError, StringSanitizationPolicy};
+mod eo_number;
+pub use eo_number::{EoChar, EoInt, EoNumber, EoShort, EoThree};
 mod eo_serialize;
-pub use eo_serialize::{EoSerialize, EoSerializeError};
+pub use eo_serialize::{from_foreign_bytes, EoSerialize, EoSerializeError, ForeignBytesError};
+mod strictness_profile;
+pub use strictness_profile::StrictnessProfile;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        string_size, try_decode_string, try_encode_string, EoWriter, StringEncodingError,
+        CHAR_SIZE, INT_SIZE, SHORT_SIZE, THREE_SIZE,
+    };
+
+    #[test]
+    fn primitive_sizes_match_writer() {
+        let mut writer = EoWriter::new();
+        writer.add_char(1).unwrap();
+        assert_eq!(writer.as_bytes().len(), CHAR_SIZE);
+        writer.add_short(1).unwrap();
+        assert_eq!(writer.as_bytes().len(), CHAR_SIZE + SHORT_SIZE);
+        writer.add_three(1).unwrap();
+        assert_eq!(writer.as_bytes().len(), CHAR_SIZE + SHORT_SIZE + THREE_SIZE);
+        writer.add_int(1).unwrap();
+        assert_eq!(
+            writer.as_bytes().len(),
+            CHAR_SIZE + SHORT_SIZE + THREE_SIZE + INT_SIZE
+        );
+
+        for string in ["Aeven", "naïve", "日本"] {
+            let mut writer = EoWriter::new();
+            writer.add_string(string).unwrap();
+            assert_eq!(writer.as_bytes().len(), string_size(string));
+
+            let mut writer = EoWriter::new();
+            writer.add_encoded_string(string).unwrap();
+            assert_eq!(writer.as_bytes().len(), string_size(string));
+        }
+    }
+
+    #[test]
+    fn sentences_round_trip() {
+        for sentence in [
+            "Welcome to Aeven!",
+            "Hello, world. How are you?",
+            "  leading and trailing spaces  ",
+            "Tabs\tand ~tildes~ {braces} |pipes|",
+        ] {
+            let mut buf = sentence.as_bytes().to_vec();
+            try_encode_string(&mut buf).unwrap();
+            try_decode_string(&mut buf).unwrap();
+            assert_eq!(buf, sentence.as_bytes());
+        }
+    }
+
+    #[test]
+    fn every_byte_but_break_round_trips() {
+        let bytes: Vec<u8> = (0..0xff).collect();
+        let mut buf = bytes.clone();
+        try_encode_string(&mut buf).unwrap();
+        try_decode_string(&mut buf).unwrap();
+        assert_eq!(buf, bytes);
+    }
+
+    #[test]
+    fn breaks_are_rejected() {
+        let mut buf = b"sign\xFFtext".to_vec();
+        assert_eq!(
+            try_encode_string(&mut buf),
+            Err(StringEncodingError::InvalidByte(0xFF, 4))
+        );
+        assert_eq!(buf, b"sign\xFFtext");
+    }
+}
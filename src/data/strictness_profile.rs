@@ -0,0 +1,315 @@
+use super::{ReaderLimits, StringEncoding, StringSanitizationPolicy, SHORT_MAX};
+
+// the largest packet, see packet::MAX_PACKET_SIZE. nothing in a packet can be longer
+const MAX_PACKET_SIZE: usize = SHORT_MAX as usize - 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The strictness settings of an [EoReader](super::EoReader), an [EoWriter](super::EoWriter)
+/// and a [Connection](crate::packet::Connection) or
+/// [ClientSession](crate::packet::ClientSession), applied together
+///
+/// Start from the preset for what the application is and change single settings with the
+/// builder methods:
+///
+/// - [server](StrictnessProfile::server) rejects anything the official client never sends:
+///   truncated packets, fields running into the next chunk, unknown enum values and numbers
+///   with bytes out of range. Sequences must match exactly. Strings written with `0xFF`
+///   bytes in chunked sections have them replaced, so player text can't break a packet
+/// - [client](StrictnessProfile::client) reads whatever a server sends as well as it can,
+///   since servers add their own enum values and fields, but stops forged lengths at the
+///   size of a packet
+/// - [proxy](StrictnessProfile::proxy) reads like a client and writes strings back exactly
+///   as they were read. Sequences may drift as far as the counter allows, leaving the check
+///   to the server behind it
+///
+/// [lenient](StrictnessProfile::lenient) is what a new reader, writer and connection use.
+/// [official](StrictnessProfile::official) and [strict](StrictnessProfile::strict) are the
+/// reading levels the personas are built from.
+///
+/// The [Codec](crate::packet::Codec) only frames and encrypts packets, so nothing in a
+/// profile applies to it.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::data::{
+///     EoReader, EoWriter, EoWriterError, StrictnessProfile, StringEncoding,
+/// };
+///
+/// let profile = StrictnessProfile::strict().string_encoding(StringEncoding::Utf8);
+///
+/// let mut writer = EoWriter::new();
+/// writer.set_strictness_profile(profile);
+/// assert_eq!(writer.add_int(-1), Err(EoWriterError::InvalidIntValue(-1)));
+/// writer.add_int(1).unwrap();
+///
+/// let reader = EoReader::new(writer.to_byte_array());
+/// reader.set_strictness_profile(profile);
+/// assert_eq!(reader.get_limits(), profile.limits);
+/// assert_eq!(reader.get_string_encoding(), StringEncoding::Utf8);
+/// assert_eq!(reader.get_int(), 1);
+/// ```
+pub struct StrictnessProfile {
+    /// limits on strings and arrays read, see [EoReader::set_limits](super::EoReader::set_limits)
+    pub limits: ReaderLimits,
+    /// encoding strings are read with, see [StringEncoding]
+    pub string_encoding: StringEncoding,
+    /// what's done with `0xFF` bytes in strings written in chunked sections, see
+    /// [StringSanitizationPolicy]
+    pub string_sanitization_policy: StringSanitizationPolicy,
+    /// whether negative ints are rejected, see
+    /// [EoWriter::set_strict_int_mode](super::EoWriter::set_strict_int_mode)
+    pub strict_int_mode: bool,
+    /// whether numbers with bytes out of range are rejected, see
+    /// [EoReader::set_strict_number_mode](super::EoReader::set_strict_number_mode)
+    pub strict_number_mode: bool,
+    /// whether reading past the end of the data is an error, see
+    /// [EoReader::set_strict_eof_mode](super::EoReader::set_strict_eof_mode)
+    pub strict_eof_mode: bool,
+    /// whether reading past the end of a chunk is an error, see
+    /// [EoReader::set_strict_chunk_mode](super::EoReader::set_strict_chunk_mode)
+    pub strict_chunk_mode: bool,
+    /// whether enum values the protocol doesn't define are an error, see
+    /// [EoReader::set_strict_enum_mode](super::EoReader::set_strict_enum_mode)
+    pub strict_enum_mode: bool,
+    /// how many packets a client's sequence may drift before a
+    /// [Connection](crate::packet::Connection) rejects it, see
+    /// [Sequencer::set_tolerance](crate::packet::Sequencer::set_tolerance)
+    pub sequence_tolerance: u8,
+}
+
+impl StrictnessProfile {
+    /// no limits, invalid data is read and written as well as it can be
+    pub fn lenient() -> Self {
+        Self {
+            limits: ReaderLimits::new(),
+            string_encoding: StringEncoding::Windows1252,
+            string_sanitization_policy: StringSanitizationPolicy::ReplaceWith(b'y'),
+            strict_int_mode: false,
+            strict_number_mode: false,
+            strict_eof_mode: false,
+            strict_chunk_mode: false,
+            strict_enum_mode: false,
+            sequence_tolerance: 0,
+        }
+    }
+
+    /// like [lenient](StrictnessProfile::lenient), with strings and arrays limited to the
+    /// size of a packet
+    pub fn official() -> Self {
+        Self {
+            limits: ReaderLimits::new()
+                .max_string_length(MAX_PACKET_SIZE)
+                .max_array_length(MAX_PACKET_SIZE),
+            ..Self::lenient()
+        }
+    }
+
+    /// like [official](StrictnessProfile::official), but `0xFF` bytes in strings, negative
+    /// ints, numbers with bytes out of range, reads past the end of the data or a chunk and
+    /// unknown enum values are errors
+    pub fn strict() -> Self {
+        Self {
+            string_sanitization_policy: StringSanitizationPolicy::Error,
+            strict_int_mode: true,
+            strict_number_mode: true,
+            strict_eof_mode: true,
+            strict_chunk_mode: true,
+            strict_enum_mode: true,
+            ..Self::official()
+        }
+    }
+
+    /// for servers: reads like [strict](StrictnessProfile::strict), but replaces `0xFF` bytes
+    /// in strings written in chunked sections
+    pub fn server() -> Self {
+        Self {
+            string_sanitization_policy: StringSanitizationPolicy::ReplaceWith(b'y'),
+            ..Self::strict()
+        }
+    }
+
+    /// for clients: reads like [official](StrictnessProfile::official) and rejects negative
+    /// ints written
+    pub fn client() -> Self {
+        Self {
+            strict_int_mode: true,
+            ..Self::official()
+        }
+    }
+
+    /// for proxies: reads like [official](StrictnessProfile::official), writes strings as
+    /// they are and accepts any sequence drift the counter can express
+    pub fn proxy() -> Self {
+        Self {
+            string_sanitization_policy: StringSanitizationPolicy::Allow,
+            sequence_tolerance: 4,
+            ..Self::official()
+        }
+    }
+
+    /// sets the limits on strings and arrays read
+    pub fn limits(mut self, limits: ReaderLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// sets the encoding strings are read with
+    pub fn string_encoding(mut self, string_encoding: StringEncoding) -> Self {
+        self.string_encoding = string_encoding;
+        self
+    }
+
+    /// sets what's done with `0xFF` bytes in strings written in chunked sections
+    pub fn string_sanitization_policy(mut self, policy: StringSanitizationPolicy) -> Self {
+        self.string_sanitization_policy = policy;
+        self
+    }
+
+    /// sets whether negative ints are rejected
+    pub fn strict_int_mode(mut self, strict_int_mode: bool) -> Self {
+        self.strict_int_mode = strict_int_mode;
+        self
+    }
+
+    /// sets whether numbers with bytes out of range are rejected
+    pub fn strict_number_mode(mut self, strict_number_mode: bool) -> Self {
+        self.strict_number_mode = strict_number_mode;
+        self
+    }
+
+    /// sets whether reading past the end of the data is an error
+    pub fn strict_eof_mode(mut self, strict_eof_mode: bool) -> Self {
+        self.strict_eof_mode = strict_eof_mode;
+        self
+    }
+
+    /// sets whether reading past the end of a chunk is an error
+    pub fn strict_chunk_mode(mut self, strict_chunk_mode: bool) -> Self {
+        self.strict_chunk_mode = strict_chunk_mode;
+        self
+    }
+
+    /// sets whether enum values the protocol doesn't define are an error
+    pub fn strict_enum_mode(mut self, strict_enum_mode: bool) -> Self {
+        self.strict_enum_mode = strict_enum_mode;
+        self
+    }
+
+    /// sets how many packets a client's sequence may drift before it's rejected
+    pub fn sequence_tolerance(mut self, sequence_tolerance: u8) -> Self {
+        self.sequence_tolerance = sequence_tolerance;
+        self
+    }
+}
+
+impl Default for StrictnessProfile {
+    fn default() -> Self {
+        Self::lenient()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StrictnessProfile;
+    use crate::{
+        data::{
+            EoReader, EoReaderError, EoSerialize, EoWriter, EoWriterError, StringSanitizationPolicy,
+        },
+        packet::{Connection, SequenceCheck},
+        protocol::net::client::WalkPlayerClientPacket,
+    };
+
+    #[test]
+    fn lenient_is_the_default() {
+        let profile = StrictnessProfile::lenient();
+        let reader = EoReader::from(vec![]);
+        let writer = EoWriter::new();
+        assert_eq!(reader.get_limits(), profile.limits);
+        assert_eq!(reader.get_string_encoding(), profile.string_encoding);
+        assert_eq!(reader.get_strict_number_mode(), profile.strict_number_mode);
+        assert_eq!(reader.get_strict_eof_mode(), profile.strict_eof_mode);
+        assert_eq!(reader.get_strict_chunk_mode(), profile.strict_chunk_mode);
+        assert_eq!(reader.get_strict_enum_mode(), profile.strict_enum_mode);
+        assert_eq!(
+            writer.get_string_sanitization_policy(),
+            profile.string_sanitization_policy
+        );
+        assert_eq!(writer.get_strict_int_mode(), profile.strict_int_mode);
+    }
+
+    #[test]
+    fn forged_lengths_are_rejected() {
+        let reader = EoReader::from(vec![]);
+        reader.set_strictness_profile(StrictnessProfile::official());
+        assert!(reader.check_array_length(64008).is_ok());
+        assert!(matches!(
+            reader.check_array_length(64009),
+            Err(EoReaderError::ArrayTooLong(64009, 64008, _))
+        ));
+    }
+
+    #[test]
+    fn strict_writer() {
+        let mut writer = EoWriter::new();
+        writer.set_strictness_profile(StrictnessProfile::strict());
+        writer.set_string_sanitization_mode(true);
+        assert_eq!(
+            writer.add_string("ÿ"),
+            Err(EoWriterError::UnsanitizedString(0))
+        );
+        assert_eq!(writer.add_int(-1), Err(EoWriterError::InvalidIntValue(-1)));
+
+        writer.set_strictness_profile(
+            StrictnessProfile::strict()
+                .string_sanitization_policy(StringSanitizationPolicy::Allow)
+                .strict_int_mode(false),
+        );
+        writer.add_string("ÿ").unwrap();
+        writer.add_int(-1).unwrap();
+    }
+
+    #[test]
+    fn server_rejects_malformed_packets() {
+        let read = |data: Vec<u8>, profile: StrictnessProfile| {
+            let reader = EoReader::from(data);
+            reader.set_strictness_profile(profile);
+            WalkPlayerClientPacket::deserialize(&reader)
+        };
+
+        let truncated = vec![1, 1, 1, 1, 1];
+        assert!(read(truncated.clone(), StrictnessProfile::client()).is_ok());
+        assert!(matches!(
+            read(truncated, StrictnessProfile::server()),
+            Err(EoReaderError::UnexpectedEof(1, 0, _))
+        ));
+
+        let unknown_direction = vec![100, 1, 1, 1, 1, 1];
+        assert!(read(unknown_direction.clone(), StrictnessProfile::proxy()).is_ok());
+        assert!(matches!(
+            read(unknown_direction, StrictnessProfile::server()),
+            Err(EoReaderError::UnrecognizedEnum("Direction", 99, _))
+        ));
+
+        let reader = EoReader::from(vec![1, 255, 2]);
+        reader.set_strictness_profile(StrictnessProfile::server());
+        reader.set_chunked_reading_mode(true);
+        reader.get_short();
+        assert!(matches!(
+            reader.check_errors(),
+            Err(EoReaderError::ChunkOverrun(2, 1, _))
+        ));
+    }
+
+    #[test]
+    fn personas_apply_to_connections() {
+        let mut connection = Connection::new(1);
+        connection.set_strictness_profile(StrictnessProfile::proxy());
+        assert_eq!(connection.strictness_profile(), StrictnessProfile::proxy());
+        assert_eq!(connection.sequencer_mut().verify(5), SequenceCheck::Ok);
+
+        let mut connection = Connection::new(1);
+        connection.set_strictness_profile(StrictnessProfile::server());
+        assert_ne!(connection.sequencer_mut().verify(5), SequenceCheck::Ok);
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Write;
+
+const BYTES_PER_LINE: usize = 16;
+
+/// returns a hex dump of `bytes`, 16 per line with their offset and printable characters
+///
+/// # Examples
+///
+/// ```
+/// use eolib::debug::hexdump;
+///
+/// assert_eq!(
+///     hexdump(b"EIF\x01\x02\xfe\xfe\xff"),
+///     "00000000  45 49 46 01 02 FE FE FF                           |EIF.....|"
+/// );
+/// ```
+pub fn hexdump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    for (index, line) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        if index > 0 {
+            dump.push('\n');
+        }
+
+        write!(
+            dump,
+            "{:08X}  {}  |",
+            index * BYTES_PER_LINE,
+            hex_line(line)
+        )
+        .unwrap();
+        for byte in line {
+            dump.push(match byte {
+                0x20..=0x7e => *byte as char,
+                _ => '.',
+            });
+        }
+        dump.push('|');
+    }
+    dump
+}
+
+/// returns a line by line hex diff of two byte slices
+///
+/// lines that differ are shown from both sides, `-` for `left` and `+` for `right`, with the
+/// differing bytes marked underneath. bytes past the end of the shorter slice count as
+/// different. used by [assert_bytes_eq](crate::assert_bytes_eq)
+///
+/// # Examples
+///
+/// ```
+/// use eolib::debug::hex_diff;
+///
+/// assert_eq!(
+///     hex_diff(&[1, 2, 3], &[1, 4, 3, 5]),
+///     "- left (3 bytes)
+/// + right (4 bytes)
+/// - 00000000  01 02 03
+/// + 00000000  01 04 03 05
+///                ^^    ^^"
+/// );
+/// ```
+pub fn hex_diff(left: &[u8], right: &[u8]) -> String {
+    let mut diff = format!(
+        "- left ({} bytes)\n+ right ({} bytes)",
+        left.len(),
+        right.len()
+    );
+
+    let length = left.len().max(right.len());
+    for offset in (0..length).step_by(BYTES_PER_LINE) {
+        let left_line = line_at(left, offset);
+        let right_line = line_at(right, offset);
+        if left_line == right_line {
+            write!(
+                diff,
+                "\n  {:08X}  {}",
+                offset,
+                hex_line(left_line).trim_end()
+            )
+            .unwrap();
+            continue;
+        }
+
+        write!(
+            diff,
+            "\n- {:08X}  {}",
+            offset,
+            hex_line(left_line).trim_end()
+        )
+        .unwrap();
+        write!(
+            diff,
+            "\n+ {:08X}  {}",
+            offset,
+            hex_line(right_line).trim_end()
+        )
+        .unwrap();
+
+        let mut markers = String::new();
+        for index in 0..BYTES_PER_LINE {
+            if index == BYTES_PER_LINE / 2 {
+                markers.push(' ');
+            }
+            if left_line.get(index) == right_line.get(index) {
+                markers.push_str("   ");
+            } else {
+                markers.push_str("^^ ");
+            }
+        }
+        write!(diff, "\n{}{}", " ".repeat(12), markers.trim_end()).unwrap();
+    }
+    diff
+}
+
+fn line_at(bytes: &[u8], offset: usize) -> &[u8] {
+    let start = offset.min(bytes.len());
+    let end = (offset + BYTES_PER_LINE).min(bytes.len());
+    &bytes[start..end]
+}
+
+// the bytes of a line as hex, padded to the width of a full line
+fn hex_line(line: &[u8]) -> String {
+    let mut hex = String::with_capacity(BYTES_PER_LINE * 3 + 1);
+    for index in 0..BYTES_PER_LINE {
+        if index == BYTES_PER_LINE / 2 {
+            hex.push(' ');
+        }
+        match line.get(index) {
+            Some(byte) => write!(hex, "{:02X} ", byte).unwrap(),
+            None => hex.push_str("   "),
+        }
+    }
+    hex.pop();
+    hex
+}
+
+/// Asserts that two byte sequences are equal, panicking with a
+/// [hex_diff](crate::debug::hex_diff) if they aren't
+///
+/// Both sides can be anything that implements `AsRef<[u8]>`, like `Vec<u8>`, `Bytes` or an
+/// array.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{assert_bytes_eq, data::EoWriter};
+///
+/// let mut writer = EoWriter::new();
+/// writer.add_short(300).unwrap();
+///
+/// assert_bytes_eq!(writer.to_byte_array(), [48, 2]);
+/// ```
+#[macro_export]
+macro_rules! assert_bytes_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let left: &[u8] = ::std::convert::AsRef::as_ref(left);
+                let right: &[u8] = ::std::convert::AsRef::as_ref(right);
+                if left != right {
+                    ::std::panic!(
+                        "assertion failed: `(left == right)`\n{}",
+                        $crate::debug::hex_diff(left, right)
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hex_diff, hexdump};
+
+    #[test]
+    fn hexdump_lines() {
+        let bytes: Vec<u8> = (0x30..0x50).chain([0x00, 0xff]).collect();
+        assert_eq!(
+            hexdump(&bytes),
+            "00000000  30 31 32 33 34 35 36 37  38 39 3A 3B 3C 3D 3E 3F  |0123456789:;<=>?|
+00000010  40 41 42 43 44 45 46 47  48 49 4A 4B 4C 4D 4E 4F  |@ABCDEFGHIJKLMNO|
+00000020  00 FF                                             |..|"
+        );
+        assert_eq!(hexdump(&[]), "");
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let left: Vec<u8> = (0..20).collect();
+        let mut right = left.clone();
+        right[18] = 0xff;
+        assert_eq!(
+            hex_diff(&left, &right),
+            "- left (20 bytes)
++ right (20 bytes)
+  00000000  00 01 02 03 04 05 06 07  08 09 0A 0B 0C 0D 0E 0F
+- 00000010  10 11 12 13
++ 00000010  10 11 FF 13
+                  ^^"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "- 00000000  01 02\n+ 00000000  01 03")]
+    fn assert_bytes_eq_panics() {
+        crate::assert_bytes_eq!(vec![1u8, 2], [1, 3]);
+    }
+}
@@ -0,0 +1,6 @@
+mod traffic_analysis;
+pub use traffic_analysis::{analyze, TrafficAnalysis};
+mod packet_dump;
+pub use packet_dump::{dump_packet, PacketSource};
+mod hexdump;
+pub use hexdump::{hex_diff, hexdump};
@@ -0,0 +1,153 @@
+use std::fmt::Write;
+
+use crate::{
+    data::EoReader,
+    protocol::net::{client, server, PacketAction, PacketFamily},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The side that sent a packet passed to [dump_packet]
+pub enum PacketSource {
+    /// a client packet, with a sequence number of `sequence_size` bytes after the header
+    ///
+    /// the sequence is 2 bytes when its value is [CHAR_MAX](crate::data::CHAR_MAX) or more
+    /// (see [Sequencer::sequence_is_short](crate::packet::Sequencer::sequence_is_short)),
+    /// 1 byte otherwise and missing (0) in the first INIT_INIT packet
+    Client { sequence_size: usize },
+    /// a server packet
+    Server,
+}
+
+/// returns a human readable dump of a decrypted packet (without its length prefix)
+///
+/// the first line has the family, action and sender, followed by the packet's fields and
+/// values. data the packet doesn't use, or all of it if the packet is unknown or can't be
+/// deserialized, is dumped as hex.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     debug::{dump_packet, PacketSource},
+///     packet::PacketBuilder,
+///     protocol::net::{PacketAction, PacketFamily},
+/// };
+///
+/// let packet = PacketBuilder::new(PacketFamily::Connection, PacketAction::Player)
+///     .add_short(100)
+///     .add_char(5)
+///     .add_byte(1)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(
+///     dump_packet(&packet[2..], PacketSource::Server),
+///     "Connection_Player from server
+/// ConnectionPlayerServerPacket {
+///     seq1: 100,
+///     seq2: 5,
+/// }
+/// trailing bytes: 01"
+/// );
+/// ```
+pub fn dump_packet(bytes: &[u8], source: PacketSource) -> String {
+    let mut dump = String::new();
+    if bytes.len() < 2 {
+        dump.push_str("incomplete header");
+        write_hex(&mut dump, "data", bytes);
+        return dump;
+    }
+
+    let action = PacketAction::from(bytes[0]);
+    let family = PacketFamily::from(bytes[1]);
+    let data = &bytes[2..];
+    let reader = EoReader::new(data.to_vec().into());
+
+    write!(dump, "{:?}_{:?}", family, action).unwrap();
+    match source {
+        PacketSource::Client { sequence_size } => {
+            dump.push_str(" from client");
+            match sequence_size {
+                0 => {}
+                1 => write!(dump, ", sequence {}", reader.get_char()).unwrap(),
+                _ => write!(dump, ", sequence {}", reader.get_short()).unwrap(),
+            }
+        }
+        PacketSource::Server => dump.push_str(" from server"),
+    }
+
+    let start = reader.position().min(data.len());
+    let description = match source {
+        PacketSource::Client { .. } => client::describe_packet(family, action, &reader),
+        PacketSource::Server => server::describe_packet(family, action, &reader),
+    };
+
+    match description {
+        Some(Ok(description)) => {
+            dump.push('\n');
+            dump.push_str(&description);
+            let end = reader.position().min(data.len());
+            write_hex(&mut dump, "trailing bytes", &data[end..]);
+        }
+        Some(Err(e)) => {
+            write!(dump, "\nfailed to deserialize: {}", e).unwrap();
+            write_hex(&mut dump, "data", &data[start..]);
+        }
+        None => {
+            dump.push_str("\nunknown packet");
+            write_hex(&mut dump, "data", &data[start..]);
+        }
+    }
+
+    dump
+}
+
+fn write_hex(dump: &mut String, label: &str, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    write!(dump, "\n{}:", label).unwrap();
+    for byte in bytes {
+        write!(dump, " {:02X}", byte).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dump_packet, PacketSource};
+    use crate::{
+        packet::PacketBuilder,
+        protocol::net::{PacketAction, PacketFamily},
+    };
+
+    #[test]
+    fn client_sequence() {
+        let packet = PacketBuilder::new(PacketFamily::Connection, PacketAction::Player)
+            .sequence(300)
+            .build()
+            .unwrap();
+
+        let dump = dump_packet(&packet[2..], PacketSource::Client { sequence_size: 2 });
+        assert_eq!(
+            dump,
+            "Connection_Player from client, sequence 300\nunknown packet"
+        );
+    }
+
+    #[test]
+    fn unknown_packet() {
+        assert_eq!(
+            dump_packet(&[0x13, 0x37, 1, 2], PacketSource::Server),
+            "Unrecognized(55)_Unrecognized(19) from server\nunknown packet\ndata: 01 02"
+        );
+    }
+
+    #[test]
+    fn incomplete_header() {
+        assert_eq!(
+            dump_packet(&[0x13], PacketSource::Server),
+            "incomplete header\ndata: 13"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    encrypt::decrypt_packet,
+    protocol::net::{PacketAction, PacketFamily},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Statistics about a captured packet buffer
+///
+/// Returned by [analyze].
+pub struct TrafficAnalysis {
+    /// number of occurrences of each byte value
+    pub histogram: [usize; 256],
+    /// shannon entropy of the data in bits per byte (`0.0..=8.0`)
+    pub entropy: f64,
+    /// fraction of bytes with the most significant bit set
+    ///
+    /// packet data is mostly small encoded numbers and ASCII text, encryption flips the most
+    /// significant bit of nearly every byte.
+    pub high_bit_ratio: f64,
+    /// true if the first two bytes are a known action and family as-is
+    pub plain_header: bool,
+    /// swap multiples (6 to 12) that produce a known action and family when used to decrypt
+    /// the data
+    ///
+    /// always empty when [plain_header](TrafficAnalysis::plain_header) is set
+    pub suspected_swap_multiples: Vec<u8>,
+}
+
+impl TrafficAnalysis {
+    /// returns true if the data looks like an encrypted packet
+    pub fn looks_encrypted(&self) -> bool {
+        !self.plain_header && !self.suspected_swap_multiples.is_empty()
+    }
+}
+
+/// Analyzes a single captured packet (without its length prefix)
+///
+/// Useful for figuring out whether a capture was taken before or after decryption.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{debug::analyze, encrypt::encrypt_packet};
+///
+/// let mut buf = [21, 18, 145, 72, 101, 108, 108, 111, 44, 32, 119, 111, 114, 108, 100, 33];
+///
+/// let analysis = analyze(&buf);
+/// assert!(analysis.plain_header);
+/// assert!(!analysis.looks_encrypted());
+///
+/// encrypt_packet(&mut buf, 6);
+///
+/// let analysis = analyze(&buf);
+/// assert!(analysis.looks_encrypted());
+/// assert!(analysis.suspected_swap_multiples.contains(&6));
+/// ```
+pub fn analyze(bytes: &[u8]) -> TrafficAnalysis {
+    let mut histogram = [0; 256];
+    for byte in bytes {
+        histogram[*byte as usize] += 1;
+    }
+
+    let length = bytes.len() as f64;
+    let entropy = histogram
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / length;
+            -probability * probability.log2()
+        })
+        .sum();
+
+    let high_bit_ratio = if bytes.is_empty() {
+        0.0
+    } else {
+        histogram[0x80..].iter().sum::<usize>() as f64 / length
+    };
+
+    let plain_header = is_known_header(bytes);
+
+    let suspected_swap_multiples = if plain_header {
+        Vec::new()
+    } else {
+        (6..=12)
+            .filter(|multiple| {
+                let mut buf = bytes.to_vec();
+                decrypt_packet(&mut buf, *multiple);
+                is_known_header(&buf)
+            })
+            .collect()
+    };
+
+    TrafficAnalysis {
+        histogram,
+        entropy,
+        high_bit_ratio,
+        plain_header,
+        suspected_swap_multiples,
+    }
+}
+
+fn is_known_header(bytes: &[u8]) -> bool {
+    if bytes.len() < 2 {
+        return false;
+    }
+
+    !matches!(PacketAction::from(bytes[0]), PacketAction::Unrecognized(_))
+        && !matches!(PacketFamily::from(bytes[1]), PacketFamily::Unrecognized(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::analyze;
+
+    #[test]
+    fn empty() {
+        let analysis = analyze(&[]);
+        assert_eq!(analysis.entropy, 0.0);
+        assert_eq!(analysis.high_bit_ratio, 0.0);
+        assert!(!analysis.plain_header);
+        assert!(!analysis.looks_encrypted());
+    }
+
+    #[test]
+    fn histogram_and_entropy() {
+        let analysis = analyze(&[1, 1, 2, 0x80]);
+        assert_eq!(analysis.histogram[1], 2);
+        assert_eq!(analysis.histogram[2], 1);
+        assert_eq!(analysis.histogram[0x80], 1);
+        assert_eq!(analysis.entropy, 1.5);
+        assert_eq!(analysis.high_bit_ratio, 0.25);
+    }
+
+    #[test]
+    fn unknown_header() {
+        let analysis = analyze(&[0x13, 0x37, 0x01]);
+        assert!(!analysis.plain_header);
+        assert!(!analysis.looks_encrypted());
+    }
+}
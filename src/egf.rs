@@ -0,0 +1,320 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+// PE resource type of bitmaps
+const RT_BITMAP: u32 = 2;
+
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+// index of the resource table in the optional header data directories
+const RESOURCE_DIRECTORY_INDEX: u32 = 2;
+
+// set on directory entries that point to another directory rather than data
+const SUBDIRECTORY_FLAG: u32 = 0x8000_0000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum EgfError {
+    #[error("Missing MZ signature")]
+    InvalidDosHeader,
+    #[error("Missing PE signature at offset {0}")]
+    InvalidPeHeader(usize),
+    #[error("Unknown optional header magic {0:#x}")]
+    InvalidOptionalHeader(u16),
+    #[error("Data ends before offset {0}")]
+    UnexpectedEof(usize),
+    #[error("Address {0:#x} is outside of every section")]
+    InvalidAddress(u32),
+    #[error("Offset {0} + {1} overflows")]
+    OffsetOverflow(usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A bitmap resource in an EGF file
+///
+/// The bytes in `range` are a device independent bitmap: a `BITMAPINFOHEADER`, palette and
+/// pixel data. Unlike a `.bmp` file there is no 14-byte `BITMAPFILEHEADER` in front of it.
+pub struct EgfBitmap {
+    /// resource id of the bitmap
+    pub id: u32,
+    /// position of the bitmap in the EGF file
+    pub range: Range<usize>,
+}
+
+/// returns every bitmap in an EGF file
+///
+/// EGF files are Windows PE (DLL) files holding their graphics as bitmap resources. only the
+/// headers and resource directory are read, the bitmaps themselves are not copied or checked.
+///
+/// bitmaps are returned in resource directory order (by id) and ones with string names
+/// instead of ids are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use eolib::egf::read_bitmap_index;
+///
+/// let data = std::fs::read("gfx/gfx004.egf").unwrap();
+/// for bitmap in read_bitmap_index(&data).unwrap() {
+///     println!("{}: {} bytes", bitmap.id, bitmap.range.len());
+/// }
+/// ```
+pub fn read_bitmap_index(data: &[u8]) -> Result<Vec<EgfBitmap>, EgfError> {
+    if data.get(0..2) != Some(b"MZ") {
+        return Err(EgfError::InvalidDosHeader);
+    }
+
+    let pe_offset = read_u32(data, 0x3c)? as usize;
+    if data.get(pe_offset..add(pe_offset, 4)?) != Some(b"PE\0\0") {
+        return Err(EgfError::InvalidPeHeader(pe_offset));
+    }
+
+    let section_count = read_u16(data, add(pe_offset, 6)?)? as usize;
+    let optional_header_size = read_u16(data, add(pe_offset, 20)?)? as usize;
+    let optional_header = add(pe_offset, 24)?;
+
+    let data_directories = match read_u16(data, optional_header)? {
+        PE32_MAGIC => add(optional_header, 96)?,
+        PE32_PLUS_MAGIC => add(optional_header, 112)?,
+        magic => return Err(EgfError::InvalidOptionalHeader(magic)),
+    };
+
+    let directory_count = read_u32(data, data_directories - 4)?;
+    if directory_count <= RESOURCE_DIRECTORY_INDEX {
+        return Ok(Vec::new());
+    }
+
+    let resource_rva = read_u32(
+        data,
+        add(data_directories, RESOURCE_DIRECTORY_INDEX as usize * 8)?,
+    )?;
+    if resource_rva == 0 {
+        return Ok(Vec::new());
+    }
+
+    let sections = Sections {
+        data,
+        start: add(optional_header, optional_header_size)?,
+        count: section_count,
+    };
+    let root = sections.offset_of(resource_rva)?;
+
+    let bitmaps = match read_directory(data, root, root)?
+        .into_iter()
+        .find(|(id, _)| *id == RT_BITMAP)
+    {
+        Some((_, bitmaps)) => bitmaps,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut index = Vec::new();
+    for (id, languages) in read_directory(data, root, bitmaps)? {
+        // the same bitmap can be stored for several languages, the first one is used
+        let entry = match read_directory(data, root, languages)?.first() {
+            Some((_, entry)) => *entry,
+            None => continue,
+        };
+
+        let start = sections.offset_of(read_u32(data, entry)?)?;
+        let end = add(start, read_u32(data, add(entry, 4)?)? as usize)?;
+        if end > data.len() {
+            return Err(EgfError::UnexpectedEof(end));
+        }
+
+        index.push(EgfBitmap {
+            id,
+            range: start..end,
+        });
+    }
+
+    Ok(index)
+}
+
+struct Sections<'a> {
+    data: &'a [u8],
+    start: usize,
+    count: usize,
+}
+
+impl Sections<'_> {
+    // converts a relative virtual address to a file offset
+    fn offset_of(&self, rva: u32) -> Result<usize, EgfError> {
+        for i in 0..self.count {
+            let header = add(self.start, i * 40)?;
+            let virtual_size = read_u32(self.data, add(header, 8)?)?;
+            let virtual_address = read_u32(self.data, add(header, 12)?)?;
+            let raw_size = read_u32(self.data, add(header, 16)?)?;
+            let raw_offset = read_u32(self.data, add(header, 20)?)?;
+
+            let size = virtual_size.max(raw_size);
+            if rva >= virtual_address && rva - virtual_address < size {
+                return add(raw_offset as usize, (rva - virtual_address) as usize);
+            }
+        }
+
+        Err(EgfError::InvalidAddress(rva))
+    }
+}
+
+// returns the id and file offset of each id entry in a resource directory
+fn read_directory(
+    data: &[u8],
+    root: usize,
+    directory: usize,
+) -> Result<Vec<(u32, usize)>, EgfError> {
+    let named_count = read_u16(data, add(directory, 12)?)? as usize;
+    let id_count = read_u16(data, add(directory, 14)?)? as usize;
+
+    // named entries always come first
+    let first_id_entry = add(directory, 16 + named_count * 8)?;
+    (0..id_count)
+        .map(|i| {
+            let entry = add(first_id_entry, i * 8)?;
+            let id = read_u32(data, entry)?;
+            let offset = read_u32(data, add(entry, 4)?)? & !SUBDIRECTORY_FLAG;
+            Ok((id, add(root, offset as usize)?))
+        })
+        .collect()
+}
+
+// offsets are read from the file, so adding to them can overflow with crafted data
+fn add(offset: usize, len: usize) -> Result<usize, EgfError> {
+    offset
+        .checked_add(len)
+        .ok_or(EgfError::OffsetOverflow(offset, len))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, EgfError> {
+    let end = add(offset, 2)?;
+    match data.get(offset..end) {
+        Some(bytes) => Ok(u16::from_le_bytes([bytes[0], bytes[1]])),
+        None => Err(EgfError::UnexpectedEof(end)),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, EgfError> {
+    let end = add(offset, 4)?;
+    match data.get(offset..end) {
+        Some(bytes) => Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(EgfError::UnexpectedEof(end)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_bitmap_index, read_u32, EgfBitmap, EgfError};
+
+    const RESOURCE_OFFSET: usize = 0x200;
+    const RESOURCE_RVA: u32 = 0x1000;
+
+    fn put_u16(buf: &mut [u8], offset: usize, value: u16) {
+        buf[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(buf: &mut [u8], offset: usize, value: u32) {
+        buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    // a PE32 file with one section holding a resource directory with two bitmaps (101 and
+    // 102) and one icon
+    fn egf() -> Vec<u8> {
+        let mut buf = vec![0; 0x400];
+        buf[0..2].copy_from_slice(b"MZ");
+        put_u32(&mut buf, 0x3c, 0x40);
+
+        buf[0x40..0x44].copy_from_slice(b"PE\0\0");
+        put_u16(&mut buf, 0x46, 1);
+        put_u16(&mut buf, 0x54, 96 + 3 * 8);
+
+        let optional_header = 0x58;
+        put_u16(&mut buf, optional_header, 0x10b);
+        put_u32(&mut buf, optional_header + 92, 3);
+        put_u32(&mut buf, optional_header + 96 + 16, RESOURCE_RVA);
+        put_u32(&mut buf, optional_header + 96 + 20, 0x200);
+
+        let section = optional_header + 96 + 3 * 8;
+        put_u32(&mut buf, section + 8, 0x200);
+        put_u32(&mut buf, section + 12, RESOURCE_RVA);
+        put_u32(&mut buf, section + 16, 0x200);
+        put_u32(&mut buf, section + 20, RESOURCE_OFFSET as u32);
+
+        let r = RESOURCE_OFFSET;
+
+        // types: icon (3) then bitmap (2) to check the type is matched by id
+        put_u16(&mut buf, r + 14, 2);
+        put_u32(&mut buf, r + 16, 3);
+        put_u32(&mut buf, r + 20, 0x8000_0000 | 0x28);
+        put_u32(&mut buf, r + 24, 2);
+        put_u32(&mut buf, r + 28, 0x8000_0000 | 0x40);
+
+        // bitmap names
+        put_u16(&mut buf, r + 0x40 + 14, 2);
+        put_u32(&mut buf, r + 0x40 + 16, 101);
+        put_u32(&mut buf, r + 0x40 + 20, 0x8000_0000 | 0x60);
+        put_u32(&mut buf, r + 0x40 + 24, 102);
+        put_u32(&mut buf, r + 0x40 + 28, 0x8000_0000 | 0x78);
+
+        // languages
+        for (directory, entry) in [(0x60, 0x90), (0x78, 0xa0)] {
+            put_u16(&mut buf, r + directory + 14, 1);
+            put_u32(&mut buf, r + directory + 16, 1033);
+            put_u32(&mut buf, r + directory + 20, entry);
+        }
+
+        // data entries
+        put_u32(&mut buf, r + 0x90, RESOURCE_RVA + 0x100);
+        put_u32(&mut buf, r + 0x94, 40);
+        put_u32(&mut buf, r + 0xa0, RESOURCE_RVA + 0x128);
+        put_u32(&mut buf, r + 0xa4, 60);
+
+        buf
+    }
+
+    #[test]
+    fn bitmaps() {
+        assert_eq!(
+            read_bitmap_index(&egf()),
+            Ok(vec![
+                EgfBitmap {
+                    id: 101,
+                    range: 0x300..0x328,
+                },
+                EgfBitmap {
+                    id: 102,
+                    range: 0x328..0x364,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn not_a_pe_file() {
+        assert_eq!(read_bitmap_index(b"EMF"), Err(EgfError::InvalidDosHeader));
+
+        let mut data = egf();
+        data[0x40] = b'X';
+        assert_eq!(
+            read_bitmap_index(&data),
+            Err(EgfError::InvalidPeHeader(0x40))
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let data = egf();
+        assert_eq!(
+            read_bitmap_index(&data[..0x350]),
+            Err(EgfError::UnexpectedEof(0x364))
+        );
+    }
+
+    #[test]
+    fn offsets_past_usize_fail() {
+        let data = egf();
+        assert_eq!(
+            read_u32(&data, usize::MAX - 1),
+            Err(EgfError::OffsetOverflow(usize::MAX - 1, 4))
+        );
+    }
+}
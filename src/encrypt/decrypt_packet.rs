@@ -1,4 +1,4 @@
-use super::{swap_multiples, valid_for_encryption};
+use super::{deinterleave, flip_msb, swap_multiples, valid_for_encryption};
 
 /// Decrypts a packet.
 ///
@@ -49,22 +49,16 @@ pub fn decrypt_packet(buf: &mut [u8], swap_multiple: u8) {
         return;
     }
 
-    let length = buf.len();
-    let mut tmp: Vec<u8> = vec![0; length];
-    let big_half = (length + 1) / 2;
-    let little_half = length / 2;
-    for i in 0..big_half {
-        tmp[i] = buf[i * 2];
-        if tmp[i] & 0x7f != 0 {
-            tmp[i] ^= 0x80;
-        }
-    }
-    for i in 0..little_half {
-        tmp[length - 1 - i] = buf[(i * 2) + 1];
-        if tmp[length - 1 - i] & 0x7f != 0 {
-            tmp[length - 1 - i] ^= 0x80;
-        }
-    }
-    swap_multiples(&mut tmp, swap_multiple);
-    buf.copy_from_slice(&tmp);
+    flip_msb(buf);
+    deinterleave(buf);
+
+    swap_multiples(buf, swap_multiple);
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        family = ?crate::protocol::net::PacketFamily::from(buf[1]),
+        action = ?crate::protocol::net::PacketAction::from(buf[0]),
+        size = buf.len(),
+        "decrypted packet"
+    );
 }
@@ -0,0 +1,30 @@
+use rayon::prelude::*;
+
+use super::encrypt_packet;
+
+/// Encrypts many buffers at once, spread across the rayon thread pool
+///
+/// Each buffer is encrypted with [encrypt_packet] exactly as if it were done one by one. This is
+/// meant for servers preparing a large number of map and pub file payloads at startup, where
+/// encrypting them in sequence noticeably slows down booting.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::encrypt::{encrypt_files_parallel, encrypt_packet};
+///
+/// let mut files = vec![vec![1, 2, 3, 4, 5], vec![6, 7, 8, 9, 10, 11]];
+///
+/// let mut expected = files.clone();
+/// for file in &mut expected {
+///     encrypt_packet(file, 8);
+/// }
+///
+/// encrypt_files_parallel(&mut files, 8);
+/// assert_eq!(files, expected);
+/// ```
+pub fn encrypt_files_parallel<T: AsMut<[u8]> + Send>(files: &mut [T], swap_multiple: u8) {
+    files
+        .par_iter_mut()
+        .for_each(|file| encrypt_packet(file.as_mut(), swap_multiple));
+}
@@ -1,4 +1,4 @@
-use super::{swap_multiples, valid_for_encryption};
+use super::{flip_msb, interleave, swap_multiples, valid_for_encryption};
 
 /// Encrypts a packet.
 ///
@@ -49,23 +49,15 @@ pub fn encrypt_packet(buf: &mut [u8], swap_multiple: u8) {
         return;
     }
 
-    swap_multiples(buf, swap_multiple);
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        family = ?crate::protocol::net::PacketFamily::from(buf[1]),
+        action = ?crate::protocol::net::PacketAction::from(buf[0]),
+        size = buf.len(),
+        "encrypting packet"
+    );
 
-    let length = buf.len();
-    let mut tmp: Vec<u8> = vec![0; length];
-    let big_half = (length + 1) / 2;
-    let little_half = length / 2;
-    for i in 0..big_half {
-        tmp[i * 2] = buf[i];
-        if tmp[i * 2] & 0x7f != 0 {
-            tmp[i * 2] ^= 0x80;
-        }
-    }
-    for i in 0..little_half {
-        tmp[(i * 2) + 1] = buf[length - 1 - i];
-        if tmp[(i * 2) + 1] & 0x7f != 0 {
-            tmp[(i * 2) + 1] ^= 0x80;
-        }
-    }
-    buf.copy_from_slice(&tmp);
+    swap_multiples(buf, swap_multiple);
+    flip_msb(buf);
+    interleave(buf);
 }
@@ -0,0 +1,58 @@
+use rand::Rng;
+
+use super::server_verification_hash;
+
+/// The largest challenge that [server_verification_hash] produces a valid hash for
+///
+/// Larger challenges may result in negative hash values, which cannot be represented properly
+/// in the EO protocol.
+pub const MAX_CHALLENGE: i32 = 11_092_003;
+
+/// returns a random challenge for the INIT_INIT client packet
+///
+/// the challenge is always within `1..=`[MAX_CHALLENGE]
+pub fn generate_init_challenge() -> i32 {
+    let mut rng = rand::thread_rng();
+    rng.gen_range(1..=MAX_CHALLENGE)
+}
+
+/// returns true if `hash` is the expected response to `challenge`
+///
+/// used by the client to verify the hash sent back by the server in the INIT_INIT server packet.
+/// challenges outside of `0..=`[MAX_CHALLENGE] never validate.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::encrypt::{generate_init_challenge, server_verification_hash, validate_init_challenge_hash};
+///
+/// let challenge = generate_init_challenge();
+/// let hash = server_verification_hash(challenge);
+///
+/// assert!(validate_init_challenge_hash(challenge, hash));
+/// assert!(!validate_init_challenge_hash(challenge, hash + 1));
+/// ```
+pub fn validate_init_challenge_hash(challenge: i32, hash: i32) -> bool {
+    (0..=MAX_CHALLENGE).contains(&challenge) && server_verification_hash(challenge) == hash
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::THREE_MAX;
+
+    use super::{server_verification_hash, validate_init_challenge_hash, MAX_CHALLENGE};
+
+    #[test]
+    fn max_challenge_hash_is_valid() {
+        let hash = server_verification_hash(MAX_CHALLENGE);
+        assert!((0..THREE_MAX).contains(&hash));
+    }
+
+    #[test]
+    fn oversized_challenge_is_invalid() {
+        let challenge = MAX_CHALLENGE + 1;
+        let hash = server_verification_hash(challenge);
+        assert!(!validate_init_challenge_hash(challenge, hash));
+        assert!(!validate_init_challenge_hash(-1, 0));
+    }
+}
@@ -1,14 +1,199 @@
+use std::cell::RefCell;
+
 mod server_verification_hash;
 pub use server_verification_hash::server_verification_hash;
+mod init_challenge;
+pub use init_challenge::{generate_init_challenge, validate_init_challenge_hash, MAX_CHALLENGE};
 mod swap_multiples;
 pub use swap_multiples::swap_multiples;
 mod generate_swap_multiple;
 pub use generate_swap_multiple::generate_swap_multiple;
+mod negotiate_swap_multiples;
+pub use negotiate_swap_multiples::{
+    negotiate_swap_multiples, parse_swap_multiples, SwapMultipleError, SwapMultiples,
+    SWAP_MULTIPLES,
+};
 mod encrypt_packet;
 pub use encrypt_packet::encrypt_packet;
+#[cfg(feature = "rayon")]
+mod encrypt_files_parallel;
+#[cfg(feature = "rayon")]
+pub use encrypt_files_parallel::encrypt_files_parallel;
 mod decrypt_packet;
 pub use decrypt_packet::decrypt_packet;
 
 pub(crate) fn valid_for_encryption(buf: &[u8]) -> bool {
     buf.len() > 2 && buf[0..=1] != [0xff, 0xff]
 }
+
+/// flips the most significant bit of every byte except `0x00` and `0x80`
+pub(crate) fn flip_msb(buf: &mut [u8]) {
+    // branchless so the loop can be auto-vectorized
+    for byte in buf.iter_mut() {
+        *byte ^= (((*byte & 0x7f) != 0) as u8) << 7;
+    }
+}
+
+thread_local! {
+    // reused between packets so interleaving doesn't allocate once it has grown to fit
+    static SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+// copies `buf` into the thread's scratch buffer and hands both to `f`
+fn with_copy(buf: &mut [u8], f: impl FnOnce(&mut [u8], &[u8])) {
+    SCRATCH.with(|scratch| {
+        let mut scratch = scratch.borrow_mut();
+        scratch.clear();
+        scratch.extend_from_slice(buf);
+        f(buf, &scratch);
+    });
+}
+
+/// weaves the front half of `buf` with the reversed back half, `abcde -> aebdc`
+pub(crate) fn interleave(buf: &mut [u8]) {
+    with_copy(buf, |buf, source| {
+        let length = buf.len();
+        let (front, back) = source.split_at(length.div_ceil(2));
+
+        for (pair, (first, last)) in buf
+            .chunks_exact_mut(2)
+            .zip(front.iter().zip(back.iter().rev()))
+        {
+            pair[0] = *first;
+            pair[1] = *last;
+        }
+
+        if length % 2 == 1 {
+            buf[length - 1] = front[front.len() - 1];
+        }
+    });
+}
+
+/// undoes [interleave], `aebdc -> abcde`
+pub(crate) fn deinterleave(buf: &mut [u8]) {
+    with_copy(buf, |buf, source| {
+        let length = buf.len();
+        let (front, back) = buf.split_at_mut(length.div_ceil(2));
+
+        for ((first, last), pair) in front
+            .iter_mut()
+            .zip(back.iter_mut().rev())
+            .zip(source.chunks_exact(2))
+        {
+            *first = pair[0];
+            *last = pair[1];
+        }
+
+        if length % 2 == 1 {
+            front[front.len() - 1] = source[length - 1];
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::{decrypt_packet, deinterleave, encrypt_packet, interleave};
+
+    const DECRYPTED: [u8; 21] = [
+        21, 18, 145, 72, 101, 108, 108, 111, 44, 32, 119, 111, 114, 108, 100, 33, 0, 128, 12, 24,
+        36,
+    ];
+
+    const ENCRYPTED: [u8; 21] = [
+        149, 140, 146, 152, 17, 164, 200, 128, 229, 0, 236, 161, 236, 228, 239, 236, 172, 242, 160,
+        239, 247,
+    ];
+
+    #[test]
+    fn encrypt_odd_length() {
+        let mut buf = DECRYPTED;
+        encrypt_packet(&mut buf, 12);
+        assert_eq!(buf, ENCRYPTED);
+    }
+
+    #[test]
+    fn decrypt_odd_length() {
+        let mut buf = ENCRYPTED;
+        decrypt_packet(&mut buf, 12);
+        assert_eq!(buf, DECRYPTED);
+    }
+
+    // the interleaving encrypt_packet originally did, with a fresh copy of every packet
+    fn interleave_with_copy(buf: &mut [u8]) {
+        let length = buf.len();
+        let source = buf.to_vec();
+        let (front, back) = source.split_at(length.div_ceil(2));
+
+        for (pair, (first, last)) in buf
+            .chunks_exact_mut(2)
+            .zip(front.iter().zip(back.iter().rev()))
+        {
+            pair[0] = *first;
+            pair[1] = *last;
+        }
+
+        if length % 2 == 1 {
+            buf[length - 1] = front[front.len() - 1];
+        }
+    }
+
+    #[test]
+    fn interleave_matches_copying_implementation() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1785);
+        for length in 0..600 {
+            let data: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+
+            let mut expected = data.clone();
+            interleave_with_copy(&mut expected);
+
+            let mut buf = data.clone();
+            interleave(&mut buf);
+            assert_eq!(buf, expected, "length {}", length);
+
+            deinterleave(&mut buf);
+            assert_eq!(buf, data, "length {}", length);
+        }
+
+        let mut buf = *b"abcdef";
+        interleave(&mut buf);
+        assert_eq!(&buf, b"afbecd");
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn traces_packets() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+        use tracing::{span, Event, Metadata, Subscriber};
+
+        struct CountEvents(Arc<AtomicUsize>);
+
+        impl Subscriber for CountEvents {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, _: &Event<'_>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let events = Arc::new(AtomicUsize::new(0));
+        tracing::subscriber::with_default(CountEvents(events.clone()), || {
+            let mut buf = DECRYPTED;
+            encrypt_packet(&mut buf, 12);
+            decrypt_packet(&mut buf, 12);
+        });
+        assert_eq!(events.load(Ordering::SeqCst), 2);
+    }
+}
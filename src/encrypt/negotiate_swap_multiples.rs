@@ -0,0 +1,130 @@
+use std::ops::RangeInclusive;
+
+use thiserror::Error;
+
+use crate::protocol::net::server::InitInitServerPacketReplyCodeDataOk;
+
+use super::generate_swap_multiple;
+
+/// The swap multiples that can be sent to the client
+pub const SWAP_MULTIPLES: RangeInclusive<u8> = 6..=12;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SwapMultipleError {
+    #[error("Invalid swap multiple {0} must be between 6 and 12")]
+    InvalidSwapMultiple(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The swap multiples for both directions of a connection
+///
+/// The server picks both and sends them in the INIT_INIT reply as plain bytes, server
+/// multiple first. Each side encrypts with its own multiple and decrypts with the other.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     encrypt::{negotiate_swap_multiples, parse_swap_multiples},
+///     packet::InitReplyBuilder,
+///     protocol::net::server::InitInitServerPacketReplyCodeData,
+/// };
+///
+/// // server
+/// let multiples = negotiate_swap_multiples();
+/// let reply = InitReplyBuilder::new(123, 1)
+///     .swap_multiples(multiples)
+///     .to_packet()
+///     .unwrap();
+///
+/// // client
+/// match reply.reply_code_data {
+///     Some(InitInitServerPacketReplyCodeData::OK(ok)) => {
+///         assert_eq!(parse_swap_multiples(&ok), Ok(multiples));
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+pub struct SwapMultiples {
+    /// the multiple the server encrypts packets with and the client decrypts them with
+    pub server: u8,
+    /// the multiple the client encrypts packets with and the server decrypts them with
+    pub client: u8,
+}
+
+impl SwapMultiples {
+    /// creates a new [SwapMultiples], checking both are in [SWAP_MULTIPLES]
+    pub fn new(server: u8, client: u8) -> Result<Self, SwapMultipleError> {
+        for multiple in [server, client] {
+            if !SWAP_MULTIPLES.contains(&multiple) {
+                return Err(SwapMultipleError::InvalidSwapMultiple(multiple));
+            }
+        }
+
+        Ok(Self { server, client })
+    }
+
+    /// returns the multiples as they are written in the INIT_INIT reply
+    pub fn to_bytes(self) -> [u8; 2] {
+        [self.server, self.client]
+    }
+}
+
+/// returns a random pair of swap multiples for a new connection
+///
+/// see [generate_swap_multiple]
+pub fn negotiate_swap_multiples() -> SwapMultiples {
+    SwapMultiples {
+        server: generate_swap_multiple(),
+        client: generate_swap_multiple(),
+    }
+}
+
+/// returns the swap multiples the server sent in its INIT_INIT reply
+pub fn parse_swap_multiples(
+    reply: &InitInitServerPacketReplyCodeDataOk,
+) -> Result<SwapMultiples, SwapMultipleError> {
+    SwapMultiples::new(
+        reply.server_encryption_multiple,
+        reply.client_encryption_multiple,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        negotiate_swap_multiples, parse_swap_multiples, SwapMultipleError, SwapMultiples,
+        SWAP_MULTIPLES,
+    };
+    use crate::protocol::net::server::InitInitServerPacketReplyCodeDataOk;
+
+    #[test]
+    fn negotiated_multiples_are_valid() {
+        for _ in 0..100 {
+            let multiples = negotiate_swap_multiples();
+            let [server, client] = multiples.to_bytes();
+            assert!(SWAP_MULTIPLES.contains(&server));
+            assert!(SWAP_MULTIPLES.contains(&client));
+        }
+    }
+
+    #[test]
+    fn parse_rejects_out_of_range() {
+        let reply = InitInitServerPacketReplyCodeDataOk {
+            server_encryption_multiple: 12,
+            client_encryption_multiple: 13,
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_swap_multiples(&reply),
+            Err(SwapMultipleError::InvalidSwapMultiple(13))
+        );
+        assert_eq!(
+            SwapMultiples::new(6, 12),
+            Ok(SwapMultiples {
+                server: 6,
+                client: 12
+            })
+        );
+    }
+}
@@ -17,6 +17,8 @@
 ///
 /// # Warning
 /// Oversized challenges may result in negative hash values, which cannot be represented properly in the EO protocol.
+///
+/// Challenges up to [MAX_CHALLENGE](super::MAX_CHALLENGE) are safe, see [generate_init_challenge](super::generate_init_challenge).
 pub fn server_verification_hash(mut challenge: i32) -> i32 {
     challenge += 1;
     110905
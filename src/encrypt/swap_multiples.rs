@@ -14,20 +14,57 @@
 /// assert_eq!(bytes, [10, 27, 21]);
 /// ```
 pub fn swap_multiples(bytes: &mut [u8], multiple: u8) {
+    if multiple == 0 {
+        return;
+    }
+
+    // Dividing every byte by a runtime value is slow, so look divisibility up instead
+    let mut divisible = [false; 256];
+    for value in (0..256).step_by(multiple as usize) {
+        divisible[value] = true;
+    }
+
+    // Only runs of two or more bytes need reversing, so the (rare) pair check is the only
+    // branch taken in the common case
     let length = bytes.len();
-    let mut sequence_length: usize = 0;
-    for i in 0..=length {
-        if i != length && bytes[i] % multiple == 0 {
-            sequence_length += 1;
+    let mut i = 0;
+    while i + 1 < length {
+        if divisible[bytes[i] as usize] & divisible[bytes[i + 1] as usize] {
+            let end = bytes[i + 2..]
+                .iter()
+                .position(|b| !divisible[*b as usize])
+                .map_or(length, |offset| i + 2 + offset);
+
+            bytes[i..end].reverse();
+            i = end + 1;
         } else {
-            if sequence_length > 1 {
-                let start = i - sequence_length;
-                let end = start + sequence_length - 1;
-                for i in 0..sequence_length / 2 {
-                    bytes.swap(start + i, end - i);
-                }
-            }
-            sequence_length = 0;
+            i += 1;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::swap_multiples;
+
+    #[test]
+    fn swaps_each_sequence() {
+        let mut bytes = [6, 12, 18, 1, 24, 30, 2, 36];
+        swap_multiples(&mut bytes, 6);
+        assert_eq!(bytes, [18, 12, 6, 1, 30, 24, 2, 36]);
+    }
+
+    #[test]
+    fn zero_is_a_multiple() {
+        let mut bytes = [0, 7, 5];
+        swap_multiples(&mut bytes, 7);
+        assert_eq!(bytes, [7, 0, 5]);
+    }
+
+    #[test]
+    fn zero_multiple_is_ignored() {
+        let mut bytes = [1, 2, 3];
+        swap_multiples(&mut bytes, 0);
+        assert_eq!(bytes, [1, 2, 3]);
+    }
+}
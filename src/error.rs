@@ -0,0 +1,208 @@
+use thiserror::Error;
+
+use crate::{
+    data::{
+        ChunkedReadingError, DecodeError, EoReaderError, EoSerializeError, EoWriterError,
+        ForeignBytesError, StringEncodingError,
+    },
+    egf::EgfError,
+    encrypt::SwapMultipleError,
+    game::{map_state::SnapshotError, spawn::SpawnError},
+    packet::{
+        ClientSessionError, ConnectionError, InitReplyError, PacketBuilderError, PacketLengthError,
+        PacketSizeError,
+    },
+    time::TimestampError,
+    validation::ValidationError,
+};
+
+#[derive(Error, Debug)]
+/// Any error returned by eolib
+///
+/// Every error type in the crate converts into this so it can be used with `?`.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     data::{EoReader, EoWriter},
+///     Error,
+/// };
+///
+/// fn echo_char(reader: &EoReader) -> Result<EoWriter, Error> {
+///     reader.set_chunked_reading_mode(true);
+///     reader.next_chunk()?;
+///
+///     let mut writer = EoWriter::new();
+///     writer.add_char(reader.get_char())?;
+///     Ok(writer)
+/// }
+///
+/// let reader = EoReader::from(vec![255, 43]);
+/// let writer = echo_char(&reader).unwrap();
+/// assert_eq!(&writer.to_byte_array()[..], [43]);
+/// ```
+pub enum Error {
+    #[error("{0}")]
+    ReadError(EoReaderError),
+    #[error("{0}")]
+    ChunkedReadingError(ChunkedReadingError),
+    #[error("{0}")]
+    WriteError(EoWriterError),
+    #[error("{0}")]
+    SerializeError(EoSerializeError),
+    #[error("{0}")]
+    StringEncodingError(StringEncodingError),
+    #[error("{0}")]
+    ForeignBytesError(ForeignBytesError),
+    #[error("{0}")]
+    DecodeError(DecodeError),
+    #[error("{0}")]
+    PacketLengthError(PacketLengthError),
+    #[error("{0}")]
+    PacketBuilderError(PacketBuilderError),
+    #[error("{0}")]
+    InitReplyError(InitReplyError),
+    #[error("{0}")]
+    ValidationError(ValidationError),
+    #[error("{0}")]
+    EgfError(EgfError),
+    #[error("{0}")]
+    SpawnError(SpawnError),
+    #[error("{0}")]
+    SnapshotError(SnapshotError),
+    #[error("{0}")]
+    SwapMultipleError(SwapMultipleError),
+    #[error("{0}")]
+    PacketSizeError(PacketSizeError),
+    #[error("{0}")]
+    ConnectionError(ConnectionError),
+    #[error("{0}")]
+    ClientSessionError(ClientSessionError),
+    #[error("{0}")]
+    TimestampError(TimestampError),
+    #[cfg(feature = "mmap")]
+    #[error("{0}")]
+    MmapError(crate::mmap::MmapError),
+}
+
+impl From<EoReaderError> for Error {
+    fn from(e: EoReaderError) -> Self {
+        Self::ReadError(e)
+    }
+}
+
+impl From<ChunkedReadingError> for Error {
+    fn from(e: ChunkedReadingError) -> Self {
+        Self::ChunkedReadingError(e)
+    }
+}
+
+impl From<EoWriterError> for Error {
+    fn from(e: EoWriterError) -> Self {
+        Self::WriteError(e)
+    }
+}
+
+impl From<EoSerializeError> for Error {
+    fn from(e: EoSerializeError) -> Self {
+        Self::SerializeError(e)
+    }
+}
+
+impl From<ForeignBytesError> for Error {
+    fn from(e: ForeignBytesError) -> Self {
+        Self::ForeignBytesError(e)
+    }
+}
+
+impl From<StringEncodingError> for Error {
+    fn from(e: StringEncodingError) -> Self {
+        Self::StringEncodingError(e)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        Self::DecodeError(e)
+    }
+}
+
+impl From<PacketLengthError> for Error {
+    fn from(e: PacketLengthError) -> Self {
+        Self::PacketLengthError(e)
+    }
+}
+
+impl From<PacketBuilderError> for Error {
+    fn from(e: PacketBuilderError) -> Self {
+        Self::PacketBuilderError(e)
+    }
+}
+
+impl From<InitReplyError> for Error {
+    fn from(e: InitReplyError) -> Self {
+        Self::InitReplyError(e)
+    }
+}
+
+impl From<TimestampError> for Error {
+    fn from(e: TimestampError) -> Self {
+        Self::TimestampError(e)
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        Self::ValidationError(e)
+    }
+}
+
+impl From<EgfError> for Error {
+    fn from(e: EgfError) -> Self {
+        Self::EgfError(e)
+    }
+}
+
+impl From<SpawnError> for Error {
+    fn from(e: SpawnError) -> Self {
+        Self::SpawnError(e)
+    }
+}
+
+impl From<SnapshotError> for Error {
+    fn from(e: SnapshotError) -> Self {
+        Self::SnapshotError(e)
+    }
+}
+
+impl From<SwapMultipleError> for Error {
+    fn from(e: SwapMultipleError) -> Self {
+        Self::SwapMultipleError(e)
+    }
+}
+
+impl From<PacketSizeError> for Error {
+    fn from(e: PacketSizeError) -> Self {
+        Self::PacketSizeError(e)
+    }
+}
+
+impl From<ConnectionError> for Error {
+    fn from(e: ConnectionError) -> Self {
+        Self::ConnectionError(e)
+    }
+}
+
+impl From<ClientSessionError> for Error {
+    fn from(e: ClientSessionError) -> Self {
+        Self::ClientSessionError(e)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl From<crate::mmap::MmapError> for Error {
+    fn from(e: crate::mmap::MmapError) -> Self {
+        Self::MmapError(e)
+    }
+}
@@ -0,0 +1,227 @@
+//! Pub files whose records have extra trailing fields
+//!
+//! Some forks add fields to the end of every EIF, ENF, ESF or ECF record. The generated
+//! [Eif](crate::protocol::r#pub::Eif) and friends read records back to back until the file
+//! ends, so they can't read those files: the extra bytes are taken for the start of the next
+//! record. [ExtendedPubFile] reads a tail of type `T` after each record instead, either a
+//! type describing the fork's fields or a [RecordTail] that keeps them as raw bytes.
+
+use crate::{
+    checksum::PubFile,
+    data::{EoReader, EoReaderError, EoSerialize, EoSerializeError, EoWriter},
+    protocol::r#pub::{EcfRecord, EifRecord, EnfRecord, EsfRecord},
+};
+
+/// A record of a pub file
+pub trait PubRecord: EoSerialize {
+    /// the three characters at the start of the file, e.g. `EIF`
+    const FILE_TYPE: &'static str;
+}
+
+impl PubRecord for EifRecord {
+    const FILE_TYPE: &'static str = "EIF";
+}
+
+impl PubRecord for EnfRecord {
+    const FILE_TYPE: &'static str = "ENF";
+}
+
+impl PubRecord for EsfRecord {
+    const FILE_TYPE: &'static str = "ESF";
+}
+
+impl PubRecord for EcfRecord {
+    const FILE_TYPE: &'static str = "ECF";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// `N` bytes after a record, kept as they are
+///
+/// for forks whose extra fields aren't known but whose records grew by a fixed size
+pub struct RecordTail<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for RecordTail<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> EoSerialize for RecordTail<N> {
+    fn serialize(&self, writer: &mut EoWriter) -> Result<(), EoSerializeError> {
+        writer.add_bytes(&self.0);
+        Ok(())
+    }
+
+    fn deserialize(reader: &EoReader) -> Result<Self, EoReaderError> {
+        reader.check_remaining(N)?;
+        let mut tail = [0; N];
+        tail.copy_from_slice(&reader.get_bytes(N));
+        Ok(Self(tail))
+    }
+
+    fn encoded_size(&self) -> usize {
+        N
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A record and the fields a fork added after it
+pub struct ExtendedRecord<R, T> {
+    pub record: R,
+    pub tail: T,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A pub file with a `T` after every record
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     data::EoSerialize,
+///     extended_pub::{ExtendedPubFile, ExtendedRecord, RecordTail},
+///     protocol::r#pub::{Eif, EifRecord},
+/// };
+///
+/// let file = ExtendedPubFile {
+///     total_records: 2,
+///     records: vec![
+///         ExtendedRecord {
+///             record: EifRecord {
+///                 name: "Gold".to_owned(),
+///                 ..Default::default()
+///             },
+///             tail: RecordTail([1, 2]),
+///         },
+///         ExtendedRecord {
+///             record: EifRecord {
+///                 name: "Sword".to_owned(),
+///                 ..Default::default()
+///             },
+///             tail: RecordTail([3, 4]),
+///         },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let bytes = file.to_bytes().unwrap();
+/// assert_eq!(
+///     ExtendedPubFile::<EifRecord, RecordTail<2>>::from_bytes(&bytes).unwrap(),
+///     file
+/// );
+///
+/// // the generated file misreads the tails
+/// assert_ne!(Eif::from_bytes(&bytes).unwrap().items.len(), 2);
+/// ```
+pub struct ExtendedPubFile<R, T> {
+    pub rid: [i32; 2],
+    pub total_records: i32,
+    pub version: i32,
+    pub records: Vec<ExtendedRecord<R, T>>,
+}
+
+impl<R: PubRecord, T: EoSerialize> EoSerialize for ExtendedPubFile<R, T> {
+    fn serialize(&self, writer: &mut EoWriter) -> Result<(), EoSerializeError> {
+        writer.add_fixed_string(R::FILE_TYPE, 3, false)?;
+        for rid in self.rid {
+            writer.add_short(rid)?;
+        }
+        writer.add_short(self.total_records)?;
+        writer.add_char(self.version)?;
+        for record in &self.records {
+            record.record.serialize(writer)?;
+            record.tail.serialize(writer)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(reader: &EoReader) -> Result<Self, EoReaderError> {
+        reader.get_fixed_string(3);
+        let rid = [reader.get_short(), reader.get_short()];
+        let total_records = reader.get_short();
+        let version = reader.get_char();
+
+        let mut records = Vec::new();
+        while reader.remaining() > 0 {
+            reader.check_array_length(records.len() + 1)?;
+            let record = R::deserialize(reader)?;
+            let tail = T::deserialize(reader)?;
+            records.push(ExtendedRecord { record, tail });
+        }
+        reader.check_errors()?;
+
+        Ok(Self {
+            rid,
+            total_records,
+            version,
+            records,
+        })
+    }
+
+    fn encoded_size(&self) -> usize {
+        // file type, rid, total records and version
+        let header = 3 + 4 + 2 + 1;
+        self.records.iter().fold(header, |size, record| {
+            size + record.record.encoded_size() + record.tail.encoded_size()
+        })
+    }
+}
+
+impl<R: PubRecord, T: EoSerialize> PubFile for ExtendedPubFile<R, T> {
+    fn rid(&self) -> [i32; 2] {
+        self.rid
+    }
+
+    fn set_rid(&mut self, rid: [i32; 2]) {
+        self.rid = rid;
+    }
+
+    fn total_records(&self) -> i32 {
+        self.total_records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExtendedPubFile, ExtendedRecord, RecordTail};
+    use crate::{
+        checksum::update_rid,
+        data::{EoReaderError, EoSerialize},
+        protocol::r#pub::{Eif, EifRecord, EsfRecord},
+    };
+
+    #[test]
+    fn empty_tails_match_the_generated_file() {
+        let eif = Eif {
+            total_items_count: 1,
+            version: 2,
+            items: vec![EifRecord {
+                name: "Gold".to_owned(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let bytes = eif.to_bytes().unwrap();
+
+        let file = ExtendedPubFile::<EifRecord, RecordTail<0>>::from_bytes(&bytes).unwrap();
+        assert_eq!(file.records[0].record, eif.items[0]);
+        assert_eq!(file.encoded_size(), bytes.len());
+        assert_eq!(file.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn truncated_tails_fail() {
+        let mut file = ExtendedPubFile::<EsfRecord, RecordTail<3>>::default();
+        file.records.push(ExtendedRecord::default());
+        update_rid(&mut file).unwrap();
+
+        let bytes = file.to_bytes().unwrap();
+        assert_eq!(&bytes[..3], b"ESF");
+        assert!(matches!(
+            ExtendedPubFile::<EsfRecord, RecordTail<3>>::from_bytes(
+                &bytes.slice(..bytes.len() - 1)
+            ),
+            Err(EoReaderError::UnexpectedEof(3, 2, _))
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+//! Experience and weight calculations
+//!
+//! None of these are part of the protocol. The client is sent the results, and each server
+//! picks its own formulas (EOSERV reads most of them from its formula config). The experience
+//! table, `round(level³ × 133.1)`, is the one EOSERV builds and the official client shows
+//! progress with. Stat and hit rate formulas differ too much between servers to have a
+//! default here.
+
+/// The highest level a character can reach
+pub const MAX_LEVEL: i32 = 250;
+
+/// The most a character can carry, weight is sent as an EO char
+pub const MAX_WEIGHT: i32 = 250;
+
+/// returns the total experience needed to reach a level
+///
+/// levels outside of `0..=MAX_LEVEL` are clamped
+///
+/// # Examples
+///
+/// ```
+/// use eolib::game::calculations::experience_for_level;
+///
+/// assert_eq!(experience_for_level(1), 133);
+/// assert_eq!(experience_for_level(2), 1065);
+/// assert_eq!(experience_for_level(250), 2_079_687_500);
+/// ```
+pub fn experience_for_level(level: i32) -> i32 {
+    let level = level.clamp(0, MAX_LEVEL) as f64;
+    (level.powi(3) * 133.1).round() as i32
+}
+
+/// returns the experience needed for every level from 0 to [MAX_LEVEL]
+///
+/// the table is indexed by level
+pub fn experience_table() -> Vec<i32> {
+    (0..=MAX_LEVEL).map(experience_for_level).collect()
+}
+
+/// returns the level reached with an amount of experience
+///
+/// # Examples
+///
+/// ```
+/// use eolib::game::calculations::level_for_experience;
+///
+/// assert_eq!(level_for_experience(0), 0);
+/// assert_eq!(level_for_experience(1064), 1);
+/// assert_eq!(level_for_experience(1065), 2);
+/// ```
+pub fn level_for_experience(experience: i32) -> i32 {
+    (1..=MAX_LEVEL)
+        .take_while(|level| experience_for_level(*level) <= experience)
+        .last()
+        .unwrap_or(0)
+}
+
+/// returns an approximate carrying capacity for a strength, capped at [MAX_WEIGHT]
+pub fn max_weight(str: i32) -> i32 {
+    (70 + str).min(MAX_WEIGHT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        experience_for_level, experience_table, level_for_experience, max_weight, MAX_LEVEL,
+        MAX_WEIGHT,
+    };
+
+    #[test]
+    fn experience_table_is_increasing() {
+        let table = experience_table();
+        assert_eq!(table.len(), MAX_LEVEL as usize + 1);
+        assert!(table.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn level_round_trip() {
+        for level in 0..=MAX_LEVEL {
+            assert_eq!(level_for_experience(experience_for_level(level)), level);
+        }
+        assert_eq!(level_for_experience(i32::MAX), MAX_LEVEL);
+        assert_eq!(
+            experience_for_level(MAX_LEVEL + 1),
+            experience_for_level(MAX_LEVEL)
+        );
+    }
+
+    #[test]
+    fn weight_is_capped() {
+        assert_eq!(max_weight(10), 80);
+        assert_eq!(max_weight(500), MAX_WEIGHT);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::protocol::{
+    net::{EquipmentMapInfo, EquipmentPaperdoll},
+    r#pub::Eif,
+};
+
+/// returns the doll graphic shown on the map for an equipped item
+///
+/// equipment is sent as item IDs in paperdoll packets but as doll graphics (the item's `spec1`)
+/// everywhere the character is drawn. returns 0 for an empty slot or an unknown item.
+pub fn doll_graphic(eif: &Eif, item_id: i32) -> i32 {
+    if item_id <= 0 {
+        return 0;
+    }
+
+    match eif.items.get(item_id as usize - 1) {
+        Some(record) => record.spec1,
+        None => 0,
+    }
+}
+
+impl EquipmentPaperdoll {
+    /// returns the equipment as it is drawn on the map
+    ///
+    /// the paperdoll holds item IDs while [EquipmentMapInfo] holds doll graphics, so they can't
+    /// be copied field by field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::protocol::{
+    ///     net::{EquipmentMapInfo, EquipmentPaperdoll},
+    ///     r#pub::{Eif, EifRecord},
+    /// };
+    ///
+    /// let eif = Eif {
+    ///     items: vec![
+    ///         EifRecord { spec1: 12, ..Default::default() },
+    ///         EifRecord { spec1: 4, ..Default::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let paperdoll = EquipmentPaperdoll { armor: 1, weapon: 2, ..Default::default() };
+    ///
+    /// assert_eq!(
+    ///     paperdoll.to_map_info(&eif),
+    ///     EquipmentMapInfo { armor: 12, weapon: 4, ..Default::default() }
+    /// );
+    /// ```
+    pub fn to_map_info(&self, eif: &Eif) -> EquipmentMapInfo {
+        EquipmentMapInfo {
+            boots: doll_graphic(eif, self.boots),
+            armor: doll_graphic(eif, self.armor),
+            hat: doll_graphic(eif, self.hat),
+            shield: doll_graphic(eif, self.shield),
+            weapon: doll_graphic(eif, self.weapon),
+        }
+    }
+}
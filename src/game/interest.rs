@@ -0,0 +1,169 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::protocol::Coords;
+
+use super::range::in_view;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Who a connection started and stopped seeing after it moved, see [InterestGroups::move_to]
+///
+/// sight is mutual, so these are also the connections that started and stopped seeing it
+pub struct InterestChange<C> {
+    /// connections now in view that weren't before
+    pub entered: Vec<C>,
+    /// connections in view before that aren't any more
+    pub left: Vec<C>,
+}
+
+impl<C> Default for InterestChange<C> {
+    fn default() -> Self {
+        Self {
+            entered: Vec::new(),
+            left: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The map and position of every connection, for deciding who receives a broadcast
+///
+/// `C` identifies a connection, usually a player id. Broadcasts go to the connections in
+/// view of a position (see [VIEW_DISTANCE](super::range::VIEW_DISTANCE)) or on a map. Build
+/// the packet once and send a clone of its [Bytes](bytes::Bytes) to each of them.
+///
+/// Moving a connection returns who it started and stopped seeing, which is who should be
+/// sent its appearance and removal packets.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{game::interest::InterestGroups, protocol::Coords};
+///
+/// let mut groups = InterestGroups::new();
+/// groups.insert(1, 5, Coords { x: 10, y: 10 });
+/// groups.insert(2, 5, Coords { x: 30, y: 10 });
+///
+/// let change = groups.move_to(2, 5, Coords { x: 20, y: 10 });
+/// assert_eq!(change.entered, [1]);
+///
+/// let change = groups.move_to(2, 6, Coords { x: 20, y: 10 });
+/// assert_eq!(change.left, [1]);
+/// assert_eq!(groups.on_map(5).collect::<Vec<_>>(), [1]);
+/// ```
+pub struct InterestGroups<C> {
+    positions: HashMap<C, (i32, Coords)>,
+    maps: HashMap<i32, HashSet<C>>,
+}
+
+impl<C: Copy + Eq + Hash> InterestGroups<C> {
+    /// creates a new empty [InterestGroups]
+    pub fn new() -> Self {
+        Self {
+            positions: HashMap::new(),
+            maps: HashMap::new(),
+        }
+    }
+
+    /// adds a connection at `coords` on `map_id`, returning the connections in view of it
+    ///
+    /// a connection that was already added is moved instead
+    pub fn insert(&mut self, connection: C, map_id: i32, coords: Coords) -> Vec<C> {
+        self.move_to(connection, map_id, coords).entered
+    }
+
+    /// moves a connection to `coords` on `map_id`
+    pub fn move_to(&mut self, connection: C, map_id: i32, coords: Coords) -> InterestChange<C> {
+        let before: HashSet<C> = self.viewers_of(connection).collect();
+
+        if let Some((old_map, _)) = self.positions.insert(connection, (map_id, coords)) {
+            if old_map != map_id {
+                self.leave_map(connection, old_map);
+            }
+        }
+        self.maps.entry(map_id).or_default().insert(connection);
+
+        let after: HashSet<C> = self.viewers_of(connection).collect();
+        InterestChange {
+            entered: after.difference(&before).copied().collect(),
+            left: before.difference(&after).copied().collect(),
+        }
+    }
+
+    /// removes a connection, returning the connections that could see it
+    pub fn remove(&mut self, connection: C) -> Vec<C> {
+        let viewers = self.viewers_of(connection).collect();
+        if let Some((map_id, _)) = self.positions.remove(&connection) {
+            self.leave_map(connection, map_id);
+        }
+        viewers
+    }
+
+    /// returns the map and coordinates of a connection
+    pub fn position(&self, connection: C) -> Option<(i32, Coords)> {
+        self.positions.get(&connection).copied()
+    }
+
+    /// returns every connection on `map_id`
+    pub fn on_map(&self, map_id: i32) -> impl Iterator<Item = C> + '_ {
+        self.maps.get(&map_id).into_iter().flatten().copied()
+    }
+
+    /// returns every connection on `map_id` in view of `coords`
+    pub fn in_view_of(&self, map_id: i32, coords: Coords) -> impl Iterator<Item = C> + '_ {
+        self.on_map(map_id).filter(move |connection| {
+            let (_, position) = self.positions[connection];
+            in_view(coords, position)
+        })
+    }
+
+    /// returns the other connections in view of a connection
+    pub fn viewers_of(&self, connection: C) -> impl Iterator<Item = C> + '_ {
+        let position = self.positions.get(&connection).copied();
+        position
+            .into_iter()
+            .flat_map(move |(map_id, coords)| self.in_view_of(map_id, coords))
+            .filter(move |other| *other != connection)
+    }
+
+    fn leave_map(&mut self, connection: C, map_id: i32) {
+        if let Some(connections) = self.maps.get_mut(&map_id) {
+            connections.remove(&connection);
+            if connections.is_empty() {
+                self.maps.remove(&map_id);
+            }
+        }
+    }
+}
+
+impl<C: Copy + Eq + Hash> Default for InterestGroups<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InterestGroups;
+    use crate::protocol::Coords;
+
+    #[test]
+    fn walking_out_of_view() {
+        let mut groups = InterestGroups::new();
+        assert!(groups.insert(1, 5, Coords { x: 10, y: 10 }).is_empty());
+        assert_eq!(groups.insert(2, 5, Coords { x: 21, y: 10 }), [1]);
+
+        let change = groups.move_to(2, 5, Coords { x: 22, y: 10 });
+        assert!(change.entered.is_empty());
+        assert_eq!(change.left, [1]);
+
+        assert_eq!(groups.in_view_of(5, Coords { x: 16, y: 10 }).count(), 2);
+        assert!(groups.remove(1).is_empty());
+        assert_eq!(groups.position(1), None);
+        assert_eq!(groups.on_map(5).collect::<Vec<_>>(), [2]);
+        assert_eq!(groups.remove(2), Vec::<i32>::new());
+        assert_eq!(groups.on_map(5).count(), 0);
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use crate::protocol::{
+    map::{Emf, MapSign, MapTileSpec, MapWarp},
+    Coords,
+};
+
+use super::spawn::{ItemSpawn, NpcSpawn, SpawnError};
+
+#[derive(Debug, Clone)]
+/// An [Emf] with its tile specs and warps indexed by coordinates
+///
+/// Map files store tile specs and warps as sparse rows, so finding the one at a position means
+/// searching every row. [Map] builds the lookup tables once.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     game::map::Map,
+///     protocol::map::{Emf, MapTileSpec, MapTileSpecRow, MapTileSpecRowTile},
+/// };
+///
+/// let map = Map::new(Emf {
+///     width: 10,
+///     height: 10,
+///     tile_spec_rows: vec![MapTileSpecRow {
+///         y: 2,
+///         tiles: vec![MapTileSpecRowTile {
+///             x: 3,
+///             tile_spec: MapTileSpec::Wall,
+///         }],
+///     }],
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(map.tile_spec(3, 2), Some(MapTileSpec::Wall));
+/// assert!(!map.is_walkable(3, 2));
+/// assert!(map.is_walkable(2, 3));
+/// assert!(!map.is_walkable(11, 3));
+/// ```
+pub struct Map {
+    emf: Emf,
+    tile_specs: HashMap<Coords, MapTileSpec>,
+    warps: HashMap<Coords, (usize, usize)>,
+}
+
+impl Map {
+    /// creates a new [Map] from a map file
+    pub fn new(emf: Emf) -> Self {
+        let mut tile_specs = HashMap::new();
+        for row in &emf.tile_spec_rows {
+            for tile in &row.tiles {
+                tile_specs.insert(
+                    Coords {
+                        x: tile.x,
+                        y: row.y,
+                    },
+                    tile.tile_spec,
+                );
+            }
+        }
+
+        let mut warps = HashMap::new();
+        for (row_index, row) in emf.warp_rows.iter().enumerate() {
+            for (tile_index, tile) in row.tiles.iter().enumerate() {
+                warps.insert(
+                    Coords {
+                        x: tile.x,
+                        y: row.y,
+                    },
+                    (row_index, tile_index),
+                );
+            }
+        }
+
+        Self {
+            emf,
+            tile_specs,
+            warps,
+        }
+    }
+
+    /// returns the map file
+    pub fn emf(&self) -> &Emf {
+        &self.emf
+    }
+
+    /// returns the map file, dropping the lookup tables
+    pub fn into_emf(self) -> Emf {
+        self.emf
+    }
+
+    /// returns true if the coordinates are on the map
+    ///
+    /// the map's width and height are its largest coordinates, not its size
+    pub fn is_in_bounds(&self, x: i32, y: i32) -> bool {
+        (0..=self.emf.width).contains(&x) && (0..=self.emf.height).contains(&y)
+    }
+
+    /// returns the tile spec at the coordinates, or [None] for a plain tile
+    pub fn tile_spec(&self, x: i32, y: i32) -> Option<MapTileSpec> {
+        self.tile_specs.get(&Coords { x, y }).copied()
+    }
+
+    /// returns true if a player can walk onto the coordinates
+    ///
+    /// walls, chairs, chests, bank vaults, boards, jukeboxes and the map edge block players.
+    /// NPC boundaries only block NPCs, see [is_npc_walkable](Map::is_npc_walkable)
+    pub fn is_walkable(&self, x: i32, y: i32) -> bool {
+        if !self.is_in_bounds(x, y) {
+            return false;
+        }
+
+        !matches!(
+            self.tile_spec(x, y),
+            Some(
+                MapTileSpec::Wall
+                    | MapTileSpec::ChairDown
+                    | MapTileSpec::ChairLeft
+                    | MapTileSpec::ChairRight
+                    | MapTileSpec::ChairUp
+                    | MapTileSpec::ChairDownRight
+                    | MapTileSpec::ChairUpLeft
+                    | MapTileSpec::ChairAll
+                    | MapTileSpec::Chest
+                    | MapTileSpec::BankVault
+                    | MapTileSpec::Edge
+                    | MapTileSpec::Board1
+                    | MapTileSpec::Board2
+                    | MapTileSpec::Board3
+                    | MapTileSpec::Board4
+                    | MapTileSpec::Board5
+                    | MapTileSpec::Board6
+                    | MapTileSpec::Board7
+                    | MapTileSpec::Board8
+                    | MapTileSpec::Jukebox
+            )
+        )
+    }
+
+    /// returns true if an NPC can walk onto the coordinates
+    ///
+    /// like [is_walkable](Map::is_walkable) but NPC boundaries and warps block NPCs too
+    pub fn is_npc_walkable(&self, x: i32, y: i32) -> bool {
+        self.is_walkable(x, y)
+            && self.tile_spec(x, y) != Some(MapTileSpec::NpcBoundary)
+            && self.warp_at(x, y).is_none()
+    }
+
+    /// returns the warp at the coordinates
+    pub fn warp_at(&self, x: i32, y: i32) -> Option<&MapWarp> {
+        self.warps
+            .get(&Coords { x, y })
+            .map(|(row, tile)| &self.emf.warp_rows[*row].tiles[*tile].warp)
+    }
+
+    /// returns the signs on the map
+    pub fn signs(&self) -> &[MapSign] {
+        &self.emf.signs
+    }
+
+    /// returns the NPC spawns on the map
+    pub fn npc_spawns(&self) -> impl Iterator<Item = NpcSpawn> + '_ {
+        self.emf.npcs.iter().map(NpcSpawn::from)
+    }
+
+    /// returns the chest item spawns on the map
+    pub fn item_spawns(&self) -> impl Iterator<Item = ItemSpawn> + '_ {
+        self.emf.items.iter().map(ItemSpawn::from)
+    }
+
+    /// checks every NPC and item spawn on the map, see [NpcSpawn::validate] and
+    /// [ItemSpawn::validate]
+    ///
+    /// returns every problem found, or an empty [Vec] if the spawns are valid
+    pub fn validate_spawns(&self) -> Vec<SpawnError> {
+        let npc_errors = self
+            .npc_spawns()
+            .filter_map(|spawn| spawn.validate(self).err());
+        let item_errors = self
+            .item_spawns()
+            .filter_map(|spawn| spawn.validate(self).err());
+        npc_errors.chain(item_errors).collect()
+    }
+}
+
+impl From<Emf> for Map {
+    fn from(emf: Emf) -> Self {
+        Self::new(emf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Map;
+    use crate::{
+        game::spawn::SpawnError,
+        protocol::{
+            map::{
+                Emf, MapItem, MapNpc, MapSign, MapTileSpec, MapTileSpecRow, MapTileSpecRowTile,
+                MapWarp, MapWarpRow, MapWarpRowTile,
+            },
+            Coords,
+        },
+    };
+
+    fn map() -> Map {
+        Map::new(Emf {
+            width: 5,
+            height: 5,
+            tile_spec_rows: vec![MapTileSpecRow {
+                y: 1,
+                tiles: vec![
+                    MapTileSpecRowTile {
+                        x: 1,
+                        tile_spec: MapTileSpec::NpcBoundary,
+                    },
+                    MapTileSpecRowTile {
+                        x: 2,
+                        tile_spec: MapTileSpec::ChairAll,
+                    },
+                    MapTileSpecRowTile {
+                        x: 3,
+                        tile_spec: MapTileSpec::Unrecognized(8),
+                    },
+                ],
+            }],
+            warp_rows: vec![MapWarpRow {
+                y: 4,
+                tiles: vec![MapWarpRowTile {
+                    x: 0,
+                    warp: MapWarp {
+                        destination_map: 2,
+                        destination_coords: Coords { x: 7, y: 8 },
+                        ..Default::default()
+                    },
+                }],
+            }],
+            signs: vec![MapSign {
+                coords: Coords { x: 5, y: 5 },
+                string_data: "Welcome".to_owned(),
+                title_length: 3,
+            }],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn walkable() {
+        let map = map();
+        assert!(map.is_walkable(1, 1));
+        assert!(!map.is_npc_walkable(1, 1));
+        assert!(!map.is_walkable(2, 1));
+        assert!(map.is_walkable(3, 1));
+        assert!(map.is_walkable(5, 5));
+        assert!(!map.is_walkable(-1, 0));
+        assert!(!map.is_walkable(0, 6));
+    }
+
+    #[test]
+    fn spawns() {
+        let mut emf = map().into_emf();
+        emf.npcs = vec![
+            MapNpc {
+                coords: Coords { x: 0, y: 0 },
+                ..Default::default()
+            },
+            MapNpc {
+                coords: Coords { x: 1, y: 1 },
+                ..Default::default()
+            },
+        ];
+        emf.items = vec![
+            MapItem {
+                coords: Coords { x: 3, y: 1 },
+                ..Default::default()
+            },
+            MapItem {
+                coords: Coords { x: 6, y: 1 },
+                ..Default::default()
+            },
+        ];
+
+        let map = Map::new(emf);
+        assert_eq!(map.npc_spawns().count(), 2);
+        assert_eq!(
+            map.validate_spawns(),
+            [
+                SpawnError::NotWalkable(Coords { x: 1, y: 1 }),
+                SpawnError::NotChest(Coords { x: 3, y: 1 }),
+                SpawnError::OutOfBounds(Coords { x: 6, y: 1 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn warps_and_signs() {
+        let map = map();
+        assert_eq!(map.warp_at(0, 4).unwrap().destination_map, 2);
+        assert!(map.warp_at(4, 0).is_none());
+        assert!(map.is_walkable(0, 4));
+        assert!(!map.is_npc_walkable(0, 4));
+        assert_eq!(map.signs()[0].string_data, "Welcome");
+    }
+}
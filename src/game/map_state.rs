@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use thiserror::Error;
+
+use crate::{
+    data::{EoReader, EoReaderError, EoSerialize, EoSerializeError, EoWriter},
+    protocol::{
+        net::{ItemMapInfo, NpcMapInfo},
+        Coords,
+    },
+};
+
+/// The snapshot format written by [Snapshot::to_bytes]
+///
+/// bumped whenever the format changes. older versions are still read
+pub const SNAPSHOT_VERSION: i32 = 1;
+
+const SNAPSHOT_FILE_TYPE: &str = "EMS";
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error(
+        "Snapshot version {0} is newer than the supported version {}",
+        SNAPSHOT_VERSION
+    )]
+    UnsupportedVersion(i32),
+    #[error("Not a map snapshot")]
+    InvalidFileType,
+    #[error("{0}")]
+    Read(EoReaderError),
+}
+
+impl From<EoReaderError> for SnapshotError {
+    fn from(e: EoReaderError) -> Self {
+        Self::Read(e)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// An NPC on the map and the hit points it has left
+pub struct NpcState {
+    pub info: NpcMapInfo,
+    pub hp: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// An open door and how long until it closes
+pub struct DoorTimer {
+    pub coords: Coords,
+    pub remaining: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The live state of a map, for moving it to another process or reloading it without
+/// disconnecting players
+///
+/// Items and NPCs use the same structures as the nearby info the client is sent. Door
+/// timers are kept to the millisecond.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use eolib::{
+///     game::map_state::{DoorTimer, Snapshot},
+///     protocol::{net::ItemMapInfo, Coords},
+/// };
+///
+/// let snapshot = Snapshot {
+///     map_id: 5,
+///     items: vec![ItemMapInfo {
+///         uid: 1,
+///         id: 1,
+///         coords: Coords { x: 4, y: 2 },
+///         amount: 100,
+///     }],
+///     doors: vec![DoorTimer {
+///         coords: Coords { x: 7, y: 7 },
+///         remaining: Duration::from_millis(1500),
+///     }],
+///     ..Default::default()
+/// };
+///
+/// let bytes = snapshot.to_bytes().unwrap();
+/// assert_eq!(Snapshot::from_bytes(&bytes).unwrap(), snapshot);
+/// ```
+pub struct Snapshot {
+    pub map_id: i32,
+    pub items: Vec<ItemMapInfo>,
+    pub npcs: Vec<NpcState>,
+    pub doors: Vec<DoorTimer>,
+}
+
+impl Snapshot {
+    /// reads a snapshot written by [to_bytes](Snapshot::to_bytes) in this or an earlier
+    /// version
+    pub fn from_bytes(bytes: &Bytes) -> Result<Self, SnapshotError> {
+        let reader = EoReader::new(bytes.clone());
+        if reader.get_fixed_string(3) != SNAPSHOT_FILE_TYPE {
+            return Err(SnapshotError::InvalidFileType);
+        }
+
+        let version = reader.get_char();
+        if version > SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let map_id = reader.get_short();
+
+        let items_count = reader.get_short() as usize;
+        reader.check_array_length(items_count)?;
+        let mut items = Vec::with_capacity(items_count);
+        for _ in 0..items_count {
+            items.push(ItemMapInfo::deserialize(&reader)?);
+        }
+
+        let npcs_count = reader.get_char() as usize;
+        let mut npcs = Vec::with_capacity(npcs_count);
+        for _ in 0..npcs_count {
+            let info = NpcMapInfo::deserialize(&reader)?;
+            reader.check_remaining(4)?;
+            let hp = reader.get_int();
+            npcs.push(NpcState { info, hp });
+        }
+
+        let doors_count = reader.get_short() as usize;
+        reader.check_array_length(doors_count)?;
+        let mut doors = Vec::with_capacity(doors_count);
+        for _ in 0..doors_count {
+            let coords = Coords::deserialize(&reader)?;
+            reader.check_remaining(4)?;
+            let remaining = Duration::from_millis(reader.get_int().max(0) as u64);
+            doors.push(DoorTimer { coords, remaining });
+        }
+
+        Ok(Self {
+            map_id,
+            items,
+            npcs,
+            doors,
+        })
+    }
+
+    /// writes the snapshot in the [SNAPSHOT_VERSION] format
+    pub fn to_bytes(&self) -> Result<Bytes, EoSerializeError> {
+        let mut writer = EoWriter::new();
+        writer.add_fixed_string(SNAPSHOT_FILE_TYPE, 3, false)?;
+        writer.add_char(SNAPSHOT_VERSION)?;
+        writer.add_short(self.map_id)?;
+
+        writer.add_short(self.items.len() as i32)?;
+        for item in &self.items {
+            item.serialize(&mut writer)?;
+        }
+
+        writer.add_char(self.npcs.len() as i32)?;
+        for npc in &self.npcs {
+            npc.info.serialize(&mut writer)?;
+            writer.add_int(npc.hp)?;
+        }
+
+        writer.add_short(self.doors.len() as i32)?;
+        for door in &self.doors {
+            door.coords.serialize(&mut writer)?;
+            let remaining = i32::try_from(door.remaining.as_millis()).unwrap_or(i32::MAX);
+            writer.add_int(remaining)?;
+        }
+
+        Ok(writer.to_byte_array())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{NpcState, Snapshot, SnapshotError};
+    use crate::protocol::{net::NpcMapInfo, Coords, Direction};
+
+    #[test]
+    fn npcs_round_trip() {
+        let snapshot = Snapshot {
+            map_id: 192,
+            npcs: vec![NpcState {
+                info: NpcMapInfo {
+                    index: 3,
+                    id: 170,
+                    coords: Coords { x: 10, y: 12 },
+                    direction: Direction::Left,
+                },
+                hp: 4000,
+            }],
+            ..Default::default()
+        };
+        let bytes = snapshot.to_bytes().unwrap();
+        assert_eq!(Snapshot::from_bytes(&bytes).unwrap(), snapshot);
+
+        // a truncated snapshot fails instead of losing the npc's hp
+        assert!(matches!(
+            Snapshot::from_bytes(&bytes.slice(..bytes.len() - 4)),
+            Err(SnapshotError::Read(_))
+        ));
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let mut bytes = Snapshot::default().to_bytes().unwrap().to_vec();
+        bytes[3] = 3;
+        assert!(matches!(
+            Snapshot::from_bytes(&Bytes::from(bytes)),
+            Err(SnapshotError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            Snapshot::from_bytes(&Bytes::from_static(b"EMF")),
+            Err(SnapshotError::InvalidFileType)
+        ));
+    }
+}
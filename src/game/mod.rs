@@ -0,0 +1,9 @@
+pub mod calculations;
+pub mod equipment;
+pub mod interest;
+pub mod map;
+pub mod map_state;
+pub mod range;
+pub mod raw_emf;
+pub mod spawn;
+pub mod tick;
@@ -0,0 +1,79 @@
+use crate::{data::CHAR_MAX, protocol::Coords};
+
+/// The distance in steps that players can see other game objects from
+///
+/// Game objects further away than this should be removed from the player's view.
+pub const VIEW_DISTANCE: i32 = 11;
+
+/// returns the distance in steps between two coordinates
+///
+/// there is no diagonal movement so this is the sum of the horizontal and vertical distances
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{game::range::distance, protocol::Coords};
+///
+/// let a = Coords { x: 1, y: 1 };
+/// let b = Coords { x: 4, y: 5 };
+///
+/// assert_eq!(distance(a, b), 7);
+/// ```
+pub fn distance(a: Coords, b: Coords) -> i32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
+
+/// returns true if `target` is within [VIEW_DISTANCE] of `center`
+pub fn in_view(center: Coords, target: Coords) -> bool {
+    distance(center, target) <= VIEW_DISTANCE
+}
+
+/// returns every coordinate within [VIEW_DISTANCE] of `center`
+///
+/// coordinates that can't be represented as an EO char (negative or greater than `CHAR_MAX - 1`)
+/// are skipped
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{game::range::{coords_in_view, in_view}, protocol::Coords};
+///
+/// let center = Coords { x: 0, y: 0 };
+///
+/// assert!(coords_in_view(center).all(|coords| in_view(center, coords)));
+/// assert_eq!(coords_in_view(center).count(), 78);
+/// ```
+pub fn coords_in_view(center: Coords) -> impl Iterator<Item = Coords> {
+    (-VIEW_DISTANCE..=VIEW_DISTANCE)
+        .flat_map(move |dy| {
+            let span = VIEW_DISTANCE - dy.abs();
+            (-span..=span).map(move |dx| Coords {
+                x: center.x + dx,
+                y: center.y + dy,
+            })
+        })
+        .filter(|coords| (0..CHAR_MAX).contains(&coords.x) && (0..CHAR_MAX).contains(&coords.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocol::Coords;
+
+    use super::{coords_in_view, in_view, VIEW_DISTANCE};
+
+    #[test]
+    fn view_is_a_diamond() {
+        let center = Coords { x: 50, y: 50 };
+        let count = (2 * VIEW_DISTANCE * (VIEW_DISTANCE + 1) + 1) as usize;
+        assert_eq!(coords_in_view(center).count(), count);
+    }
+
+    #[test]
+    fn view_edge() {
+        let center = Coords { x: 50, y: 50 };
+        assert!(in_view(center, Coords { x: 61, y: 50 }));
+        assert!(in_view(center, Coords { x: 45, y: 44 }));
+        assert!(!in_view(center, Coords { x: 45, y: 43 }));
+        assert!(!in_view(center, Coords { x: 56, y: 56 }));
+    }
+}
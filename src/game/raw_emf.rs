@@ -0,0 +1,155 @@
+use std::ops::Range;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::{
+    data::{EoReader, EoReaderError, EoSerialize, EoSerializeError},
+    protocol::map::Emf,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Bytes of a map file that [Emf] doesn't read, and where they were in the file
+pub struct UnknownRegion {
+    /// offset of the first byte in the original file
+    pub offset: usize,
+    /// the bytes as they were in the file
+    pub bytes: Bytes,
+}
+
+#[derive(Debug, Clone)]
+/// An [Emf] kept together with the bytes it was read from, so data [Emf] doesn't model
+/// survives being edited and saved again
+///
+/// Map editors and clients with experimental features sometimes append their own data to a
+/// map file. The generated [Emf] stops reading after the signs, so anything past them is
+/// kept as an [UnknownRegion] and written back after the map by
+/// [to_bytes](RawEmf::to_bytes).
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{BufMut, BytesMut};
+/// use eolib::{data::EoSerialize, game::raw_emf::RawEmf, protocol::map::Emf};
+///
+/// let mut file = BytesMut::from(&Emf::default().to_bytes().unwrap()[..]);
+/// let map_length = file.len();
+/// file.put_slice(b"editor data");
+///
+/// let mut map = RawEmf::parse(file.freeze()).unwrap();
+/// assert_eq!(map.unknown()[0].offset, map_length);
+/// assert_eq!(map.raw_region(map_length..map_length + 6), Some(&b"editor"[..]));
+///
+/// map.emf_mut().name = "Aeven".to_owned();
+/// let saved = map.to_bytes().unwrap();
+/// assert!(saved.ends_with(b"editor data"));
+/// assert_eq!(RawEmf::parse(saved).unwrap().emf().name, "Aeven");
+/// ```
+pub struct RawEmf {
+    bytes: Bytes,
+    emf: Emf,
+    unknown: Vec<UnknownRegion>,
+}
+
+impl RawEmf {
+    /// reads a map file, keeping the bytes after the map as an [UnknownRegion]
+    pub fn parse(bytes: Bytes) -> Result<Self, EoReaderError> {
+        let reader = EoReader::new(bytes.clone());
+        let emf = Emf::deserialize(&reader)?;
+
+        let mut unknown = Vec::new();
+        let offset = reader.position();
+        if offset < bytes.len() {
+            unknown.push(UnknownRegion {
+                offset,
+                bytes: bytes.slice(offset..),
+            });
+        }
+
+        Ok(Self {
+            bytes,
+            emf,
+            unknown,
+        })
+    }
+
+    /// returns the map
+    pub fn emf(&self) -> &Emf {
+        &self.emf
+    }
+
+    /// returns the map for editing
+    ///
+    /// the original bytes and unknown regions are left unchanged
+    pub fn emf_mut(&mut self) -> &mut Emf {
+        &mut self.emf
+    }
+
+    /// returns the bytes the map doesn't read, in file order
+    pub fn unknown(&self) -> &[UnknownRegion] {
+        &self.unknown
+    }
+
+    /// returns the bytes in `range` of the original file, or [None] if it's out of bounds
+    pub fn raw_region(&self, range: Range<usize>) -> Option<&[u8]> {
+        self.bytes.get(range)
+    }
+
+    /// returns the original file
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// returns the map serialized, followed by the unknown regions
+    pub fn to_bytes(&self) -> Result<Bytes, EoSerializeError> {
+        let emf = self.emf.to_bytes()?;
+        let mut bytes = BytesMut::with_capacity(
+            emf.len()
+                + self
+                    .unknown
+                    .iter()
+                    .map(|region| region.bytes.len())
+                    .sum::<usize>(),
+        );
+        bytes.extend_from_slice(&emf);
+        for region in &self.unknown {
+            bytes.extend_from_slice(&region.bytes);
+        }
+        Ok(bytes.freeze())
+    }
+
+    /// returns the map, dropping the original bytes
+    pub fn into_emf(self) -> Emf {
+        self.emf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::RawEmf;
+    use crate::{data::EoSerialize, protocol::map::Emf};
+
+    #[test]
+    fn unchanged_files_round_trip() {
+        let emf = Emf {
+            width: 10,
+            height: 12,
+            ..Default::default()
+        };
+        let bytes = emf.to_bytes().unwrap();
+
+        let map = RawEmf::parse(bytes.clone()).unwrap();
+        assert!(map.unknown().is_empty());
+        assert_eq!(map.to_bytes().unwrap(), bytes);
+
+        let mut extended = bytes.to_vec();
+        extended.extend_from_slice(&[1, 2, 255, 0]);
+        let extended = Bytes::from(extended);
+        let map = RawEmf::parse(extended.clone()).unwrap();
+        assert_eq!(map.unknown()[0].bytes[..], [1, 2, 255, 0]);
+        assert_eq!(map.emf(), &emf);
+        assert_eq!(map.to_bytes().unwrap(), extended);
+        assert_eq!(map.raw_region(0..extended.len() + 1), None);
+    }
+}
@@ -0,0 +1,170 @@
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::protocol::{
+    map::{MapItem, MapNpc, MapTileSpec},
+    Coords,
+};
+
+use super::map::Map;
+
+/// The NPC spawn type for NPCs that never move
+pub const STATIONARY_SPAWN_TYPE: i32 = 7;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SpawnError {
+    #[error("Spawn at {}, {} is outside of the map", .0.x, .0.y)]
+    OutOfBounds(Coords),
+    #[error("NPC spawn at {}, {} is not walkable", .0.x, .0.y)]
+    NotWalkable(Coords),
+    #[error("Item spawn at {}, {} is not on a chest", .0.x, .0.y)]
+    NotChest(Coords),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An NPC spawn from a map file
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use eolib::{game::spawn::NpcSpawn, protocol::map::MapNpc};
+///
+/// let spawn = NpcSpawn::from(&MapNpc {
+///     id: 170,
+///     spawn_type: 7,
+///     spawn_time: 30,
+///     amount: 2,
+///     ..Default::default()
+/// });
+///
+/// assert!(spawn.is_stationary());
+///
+/// let died_at = Instant::now();
+/// assert_eq!(spawn.respawn_at(died_at), died_at + Duration::from_secs(30));
+/// ```
+pub struct NpcSpawn {
+    /// where the NPCs spawn
+    pub coords: Coords,
+    /// the NPC's id in the ENF file
+    pub npc_id: i32,
+    /// how often the NPCs move, `0` to `6` from fast to slow or [STATIONARY_SPAWN_TYPE]
+    pub spawn_type: i32,
+    /// how long after dying each NPC spawns again
+    pub respawn_time: Duration,
+    /// how many NPCs spawn here
+    pub amount: i32,
+}
+
+impl NpcSpawn {
+    /// returns true if the NPCs never move
+    pub fn is_stationary(&self) -> bool {
+        self.spawn_type == STATIONARY_SPAWN_TYPE
+    }
+
+    /// returns when an NPC that died at `died_at` spawns again
+    pub fn respawn_at(&self, died_at: Instant) -> Instant {
+        died_at + self.respawn_time
+    }
+
+    /// returns an error if the NPCs can't spawn at their coordinates on `map`
+    pub fn validate(&self, map: &Map) -> Result<(), SpawnError> {
+        let Coords { x, y } = self.coords;
+        if !map.is_in_bounds(x, y) {
+            return Err(SpawnError::OutOfBounds(self.coords));
+        }
+
+        if !map.is_npc_walkable(x, y) {
+            return Err(SpawnError::NotWalkable(self.coords));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&MapNpc> for NpcSpawn {
+    fn from(npc: &MapNpc) -> Self {
+        Self {
+            coords: npc.coords,
+            npc_id: npc.id,
+            spawn_type: npc.spawn_type,
+            respawn_time: Duration::from_secs(npc.spawn_time.max(0) as u64),
+            amount: npc.amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An item that spawns in a chest, from a map file
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use eolib::{game::spawn::ItemSpawn, protocol::map::MapItem};
+///
+/// let spawn = ItemSpawn::from(&MapItem {
+///     item_id: 1,
+///     spawn_time: 5,
+///     amount: 100,
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(spawn.key, None);
+/// assert_eq!(spawn.respawn_time, Duration::from_secs(5 * 60));
+/// ```
+pub struct ItemSpawn {
+    /// the chest the item spawns in
+    pub coords: Coords,
+    /// the key needed to open the chest, [None] if it isn't locked
+    pub key: Option<i32>,
+    /// the chest slot the item spawns in
+    pub chest_slot: i32,
+    /// the item's id in the EIF file
+    pub item_id: i32,
+    /// how long after being taken the item spawns again
+    pub respawn_time: Duration,
+    /// how many of the item spawn
+    pub amount: i32,
+}
+
+impl ItemSpawn {
+    /// returns when an item that was taken at `taken_at` spawns again
+    pub fn respawn_at(&self, taken_at: Instant) -> Instant {
+        taken_at + self.respawn_time
+    }
+
+    /// returns an error if the item's coordinates on `map` aren't a chest
+    pub fn validate(&self, map: &Map) -> Result<(), SpawnError> {
+        let Coords { x, y } = self.coords;
+        if !map.is_in_bounds(x, y) {
+            return Err(SpawnError::OutOfBounds(self.coords));
+        }
+
+        if map.tile_spec(x, y) != Some(MapTileSpec::Chest) {
+            return Err(SpawnError::NotChest(self.coords));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&MapItem> for ItemSpawn {
+    // item spawn times are stored in minutes, unlike NPC spawn times
+    fn from(item: &MapItem) -> Self {
+        Self {
+            coords: item.coords,
+            key: match item.key {
+                0 => None,
+                key => Some(key),
+            },
+            chest_slot: item.chest_slot,
+            item_id: item.item_id,
+            respawn_time: Duration::from_secs(item.spawn_time.max(0) as u64 * 60),
+            amount: item.amount,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+#[derive(Debug)]
+struct Task<T> {
+    every: u64,
+    task: T,
+}
+
+#[derive(Debug)]
+/// A fixed tick clock for a server's main loop, and the tasks that run on it
+///
+/// Tasks are registered to run every so many ticks (NPCs acting, spikes hurting, dropped
+/// items expiring). [advance](TickScheduler::advance) is given the real time that passed and
+/// returns the tasks due on each tick that elapsed. Time left over from a partial tick is
+/// carried to the next call, so ticks don't drift when the loop wakes up late.
+///
+/// If the loop stalls for longer than [max_catch_up](TickScheduler::max_catch_up) ticks, the
+/// extra ticks are skipped rather than run back to back, and counted by
+/// [skipped](TickScheduler::skipped).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use eolib::game::tick::TickScheduler;
+///
+/// let mut scheduler = TickScheduler::new(Duration::from_millis(120));
+/// scheduler.every(1, "npcs");
+/// scheduler.every(5, "spikes");
+///
+/// // not a full tick yet
+/// assert!(scheduler.advance(Duration::from_millis(100)).is_empty());
+///
+/// // the 20ms left over completes the first tick
+/// assert_eq!(
+///     scheduler.advance(Duration::from_millis(500)),
+///     [(1, "npcs"), (2, "npcs"), (3, "npcs"), (4, "npcs"), (5, "npcs"), (5, "spikes")]
+/// );
+/// assert_eq!(scheduler.until_next_tick(), Duration::from_millis(120));
+/// ```
+pub struct TickScheduler<T> {
+    tick_length: Duration,
+    tick: u64,
+    elapsed: Duration,
+    max_catch_up: u64,
+    skipped: u64,
+    tasks: Vec<Task<T>>,
+}
+
+impl<T: Clone> TickScheduler<T> {
+    /// creates a new [TickScheduler] with ticks of `tick_length`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick_length` is zero
+    pub fn new(tick_length: Duration) -> Self {
+        assert!(!tick_length.is_zero(), "tick length must not be zero");
+        Self {
+            tick_length,
+            tick: 0,
+            elapsed: Duration::ZERO,
+            max_catch_up: u64::MAX,
+            skipped: 0,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// registers `task` to run every `ticks` ticks, starting with tick `ticks`
+    ///
+    /// `0` is treated as `1`
+    pub fn every(&mut self, ticks: u64, task: T) {
+        self.tasks.push(Task {
+            every: ticks.max(1),
+            task,
+        });
+    }
+
+    /// returns the length of a tick
+    pub fn tick_length(&self) -> Duration {
+        self.tick_length
+    }
+
+    /// returns the number of ticks run so far, including skipped ones
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// returns the most ticks a single [advance](TickScheduler::advance) runs
+    pub fn max_catch_up(&self) -> u64 {
+        self.max_catch_up
+    }
+
+    /// sets the most ticks a single [advance](TickScheduler::advance) runs, skipping the rest
+    pub fn set_max_catch_up(&mut self, ticks: u64) {
+        self.max_catch_up = ticks;
+    }
+
+    /// returns the number of ticks skipped because the loop fell too far behind
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// returns how long until the next tick, for sleeping between calls to
+    /// [advance](TickScheduler::advance)
+    pub fn until_next_tick(&self) -> Duration {
+        self.tick_length - self.elapsed
+    }
+
+    /// moves the clock forward by `elapsed`, returning each task due with the tick it's due on,
+    /// in order
+    pub fn advance(&mut self, elapsed: Duration) -> Vec<(u64, T)> {
+        self.elapsed += elapsed;
+        let ticks = (self.elapsed.as_nanos() / self.tick_length.as_nanos()) as u64;
+        self.elapsed =
+            Duration::from_nanos((self.elapsed.as_nanos() % self.tick_length.as_nanos()) as u64);
+
+        let run = ticks.min(self.max_catch_up);
+        let skipped = ticks - run;
+        self.skipped += skipped;
+        self.tick += skipped;
+
+        let mut due = Vec::new();
+        for _ in 0..run {
+            self.tick += 1;
+            for task in &self.tasks {
+                if self.tick % task.every == 0 {
+                    due.push((self.tick, task.task.clone()));
+                }
+            }
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::TickScheduler;
+
+    #[test]
+    fn leftover_time_is_carried() {
+        let mut scheduler = TickScheduler::new(Duration::from_millis(100));
+        scheduler.every(1, ());
+
+        let mut ticks = 0;
+        for _ in 0..30 {
+            ticks += scheduler.advance(Duration::from_millis(70)).len();
+        }
+        assert_eq!(ticks, 21);
+        assert_eq!(scheduler.until_next_tick(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn stalls_skip_ticks() {
+        let mut scheduler = TickScheduler::new(Duration::from_millis(100));
+        scheduler.every(2, "items");
+        scheduler.set_max_catch_up(3);
+
+        let due = scheduler.advance(Duration::from_secs(1));
+        assert_eq!(due, [(8, "items"), (10, "items")]);
+        assert_eq!(scheduler.tick(), 10);
+        assert_eq!(scheduler.skipped(), 7);
+    }
+}
@@ -1,4 +1,24 @@
+pub mod checksum;
+pub mod client;
 pub mod data;
+pub mod debug;
+pub mod egf;
 pub mod encrypt;
+mod error;
+pub mod extended_pub;
+pub use error::Error;
+pub mod game;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "mmap")]
+pub mod mmap;
 pub mod packet;
 pub mod protocol;
+#[cfg(feature = "protocol_schema")]
+pub mod protocol_schema;
+mod self_test;
+pub use self_test::{self_test, SelfTestCheck, SelfTestReport};
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod time;
+pub mod validation;
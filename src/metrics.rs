@@ -0,0 +1,206 @@
+//! Counters recorded through the [metrics](https://docs.rs/metrics) facade
+//!
+//! [Codec](crate::packet::Codec), [Connection](crate::packet::Connection) and
+//! [ClientSession](crate::packet::ClientSession) record every packet they decode or encode,
+//! and every packet they reject. Nothing is recorded until an exporter (Prometheus, StatsD,
+//! etc.) is installed as the global recorder.
+//!
+//! Packet and byte counters are labeled with the packet's `family` and `action`, using the
+//! names of the [PacketFamily] and [PacketAction] variants. Bytes include the two byte length
+//! prefix.
+//!
+//! # Examples
+//!
+//! ```
+//! use eolib::metrics::{describe, PACKETS_RECEIVED};
+//!
+//! // after installing an exporter
+//! describe();
+//! assert_eq!(PACKETS_RECEIVED, "eolib_packets_received_total");
+//! ```
+
+use crate::protocol::net::{PacketAction, PacketFamily};
+
+/// Packets received, by family and action
+pub const PACKETS_RECEIVED: &str = "eolib_packets_received_total";
+
+/// Bytes received, by family and action
+pub const BYTES_RECEIVED: &str = "eolib_bytes_received_total";
+
+/// Packets sent, by family and action
+pub const PACKETS_SENT: &str = "eolib_packets_sent_total";
+
+/// Bytes sent, by family and action
+pub const BYTES_SENT: &str = "eolib_bytes_sent_total";
+
+/// Packets that decrypted to something too short to have a family and action
+pub const DECODE_ERRORS: &str = "eolib_decode_errors_total";
+
+/// Packets received with the wrong sequence number
+pub const SEQUENCE_ERRORS: &str = "eolib_sequence_errors_total";
+
+/// Init_Init handshakes that couldn't be completed
+pub const HANDSHAKE_FAILURES: &str = "eolib_handshake_failures_total";
+
+/// registers the units and descriptions of every counter with the installed recorder
+pub fn describe() {
+    metrics::describe_counter!(PACKETS_RECEIVED, "Packets received");
+    metrics::describe_counter!(
+        BYTES_RECEIVED,
+        metrics::Unit::Bytes,
+        "Bytes received, including length prefixes"
+    );
+    metrics::describe_counter!(PACKETS_SENT, "Packets sent");
+    metrics::describe_counter!(
+        BYTES_SENT,
+        metrics::Unit::Bytes,
+        "Bytes sent, including length prefixes"
+    );
+    metrics::describe_counter!(
+        DECODE_ERRORS,
+        "Packets too short to have a family and action"
+    );
+    metrics::describe_counter!(
+        SEQUENCE_ERRORS,
+        "Packets received with the wrong sequence number"
+    );
+    metrics::describe_counter!(
+        HANDSHAKE_FAILURES,
+        "Init_Init handshakes that couldn't be completed"
+    );
+}
+
+// `size` is the number of bytes read or written, including the length prefix
+pub(crate) fn packet_received(family: PacketFamily, action: PacketAction, size: usize) {
+    let (family, action) = (format!("{:?}", family), format!("{:?}", action));
+    metrics::counter!(PACKETS_RECEIVED, "family" => family.clone(), "action" => action.clone())
+        .increment(1);
+    metrics::counter!(BYTES_RECEIVED, "family" => family, "action" => action)
+        .increment(size as u64);
+}
+
+pub(crate) fn packet_sent(family: PacketFamily, action: PacketAction, size: usize) {
+    let (family, action) = (format!("{:?}", family), format!("{:?}", action));
+    metrics::counter!(PACKETS_SENT, "family" => family.clone(), "action" => action.clone())
+        .increment(1);
+    metrics::counter!(BYTES_SENT, "family" => family, "action" => action).increment(size as u64);
+}
+
+pub(crate) fn decode_error() {
+    metrics::counter!(DECODE_ERRORS).increment(1);
+}
+
+pub(crate) fn sequence_error() {
+    metrics::counter!(SEQUENCE_ERRORS).increment(1);
+}
+
+pub(crate) fn handshake_failure() {
+    metrics::counter!(HANDSHAKE_FAILURES).increment(1);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use metrics::{
+        Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+
+    use super::{BYTES_RECEIVED, BYTES_SENT, DECODE_ERRORS, PACKETS_RECEIVED, PACKETS_SENT};
+    use crate::packet::{Codec, PacketBuilder};
+    use crate::protocol::net::{PacketAction, PacketFamily};
+
+    struct Value(AtomicU64);
+
+    impl CounterFn for Value {
+        fn increment(&self, value: u64) {
+            self.0.fetch_add(value, Ordering::Relaxed);
+        }
+
+        fn absolute(&self, value: u64) {
+            self.0.fetch_max(value, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(Default)]
+    /// Keeps every counter so tests can check them, keyed by name and labels
+    pub(crate) struct TestRecorder {
+        counters: Mutex<BTreeMap<String, Arc<Value>>>,
+    }
+
+    impl TestRecorder {
+        /// returns a counter as `name{label=value,...}`, or 0 if it was never registered
+        pub(crate) fn get(&self, key: &str) -> u64 {
+            match self.counters.lock().unwrap().get(key) {
+                Some(value) => value.0.load(Ordering::Relaxed),
+                None => 0,
+            }
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            let name = if labels.is_empty() {
+                key.name().to_owned()
+            } else {
+                format!("{}{{{}}}", key.name(), labels.join(","))
+            };
+
+            let mut counters = self.counters.lock().unwrap();
+            let value = counters
+                .entry(name)
+                .or_insert_with(|| Arc::new(Value(AtomicU64::new(0))));
+            Counter::from_arc(value.clone())
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn codec_counts_packets_and_bytes() {
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let codec = Codec::new();
+            let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+                .add_char(1)
+                .add_char(2)
+                .build()
+                .unwrap();
+
+            let mut buf = bytes::BytesMut::from(&packet[..]);
+            let decoded = codec.decode_packet(&mut buf).unwrap();
+            codec.encode_packet(&decoded, &mut buf).unwrap();
+
+            // a length prefix followed by a single byte
+            let mut truncated = bytes::BytesMut::from(&[2, 1, 1][..]);
+            codec.decode_packet(&mut truncated).unwrap();
+        });
+
+        let labels = "{family=Walk,action=Player}";
+        assert_eq!(recorder.get(&format!("{}{}", PACKETS_RECEIVED, labels)), 1);
+        assert_eq!(recorder.get(&format!("{}{}", BYTES_RECEIVED, labels)), 6);
+        assert_eq!(recorder.get(&format!("{}{}", PACKETS_SENT, labels)), 1);
+        assert_eq!(recorder.get(&format!("{}{}", BYTES_SENT, labels)), 6);
+        assert_eq!(recorder.get(DECODE_ERRORS), 1);
+    }
+}
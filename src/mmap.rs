@@ -0,0 +1,248 @@
+use std::{fs::File, io, path::Path};
+
+use bytes::Bytes;
+use thiserror::Error;
+
+use crate::{
+    data::{EoReader, EoReaderError, EoSerialize, EoSerializeError},
+    protocol::map::Emf,
+};
+
+#[derive(Error, Debug)]
+pub enum MmapError {
+    #[error("{0}")]
+    Io(io::Error),
+    #[error("{0}")]
+    ReadError(EoReaderError),
+}
+
+impl From<io::Error> for MmapError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<EoReaderError> for MmapError {
+    fn from(e: EoReaderError) -> Self {
+        Self::ReadError(e)
+    }
+}
+
+/// maps the map or pub file at `path` into memory and deserializes it
+///
+/// the file is parsed straight from the mapping with [EoReader::from_mmap] instead of being
+/// read into a buffer first, and is unmapped before this returns
+///
+/// # Safety
+///
+/// the file must not be modified or truncated by any process while it is being parsed.
+/// see `memmap2::Mmap::map`
+///
+/// # Examples
+///
+/// ```no_run
+/// use eolib::{mmap, protocol::r#pub::Eif};
+///
+/// let eif: Eif = unsafe { mmap::load("pub/dat001.eif") }.unwrap();
+/// ```
+pub unsafe fn load<T: EoSerialize>(path: impl AsRef<Path>) -> Result<T, MmapError> {
+    let file = File::open(path)?;
+    let reader = EoReader::from_mmap(&file)?;
+    Ok(T::deserialize(&reader)?)
+}
+
+#[derive(Debug)]
+/// A map or pub file mapped into memory, parsed the first time it is used and copied only
+/// once it is edited
+///
+/// The raw bytes are borrowed from the mapping, so tools that only checksum, compare or
+/// forward files never parse them. [get](MappedFile::get) parses the file once and
+/// [to_mut](MappedFile::to_mut) marks it as edited, after which
+/// [to_bytes](MappedFile::to_bytes) serializes the edited copy instead of returning the
+/// mapped bytes.
+///
+/// # Examples
+///
+/// ```no_run
+/// use eolib::{mmap::MappedFile, protocol::map::Emf};
+///
+/// let mut emf = unsafe { Emf::open_readonly("maps/00001.emf") }.unwrap();
+/// assert!(emf.get().unwrap().width > 0);
+///
+/// emf.to_mut().unwrap().name = "Aeven".to_owned();
+/// assert!(emf.is_modified());
+/// std::fs::write("maps/00001.emf", emf.to_bytes().unwrap()).unwrap();
+/// ```
+pub struct MappedFile<T> {
+    data: Bytes,
+    file: Option<T>,
+    modified: bool,
+}
+
+impl<T: EoSerialize> MappedFile<T> {
+    /// maps the file at `path` into memory without parsing it
+    ///
+    /// # Safety
+    ///
+    /// the file must not be modified or truncated by any process while the [MappedFile] or
+    /// any [Bytes] taken from it is alive. see `memmap2::Mmap::map`
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mmap = memmap2::Mmap::map(&file)?;
+        Ok(Self {
+            data: Bytes::from_owner(mmap),
+            file: None,
+            modified: false,
+        })
+    }
+
+    /// returns the mapped bytes of the file as it is on disk
+    pub fn bytes(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// returns the parsed file, parsing it the first time this is called
+    pub fn get(&mut self) -> Result<&T, EoReaderError> {
+        Ok(self.parse()?)
+    }
+
+    /// returns the parsed file for editing
+    ///
+    /// the mapped bytes are left unchanged, edits are only made to the parsed copy
+    pub fn to_mut(&mut self) -> Result<&mut T, EoReaderError> {
+        self.parse()?;
+        self.modified = true;
+        self.parse()
+    }
+
+    /// returns true once [to_mut](MappedFile::to_mut) has been called
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// returns the file as bytes, the mapped bytes unless it was edited
+    pub fn to_bytes(&self) -> Result<Bytes, EoSerializeError> {
+        match &self.file {
+            Some(file) if self.modified => file.to_bytes(),
+            _ => Ok(self.data.clone()),
+        }
+    }
+
+    /// returns the parsed file, dropping the mapping
+    pub fn into_inner(self) -> Result<T, EoReaderError> {
+        match self.file {
+            Some(file) => Ok(file),
+            None => T::deserialize(&EoReader::new(self.data)),
+        }
+    }
+
+    fn parse(&mut self) -> Result<&mut T, EoReaderError> {
+        let file = match self.file.take() {
+            Some(file) => file,
+            None => T::deserialize(&EoReader::new(self.data.clone()))?,
+        };
+        Ok(self.file.insert(file))
+    }
+}
+
+impl Emf {
+    /// maps the map file at `path` into memory without parsing it, see [MappedFile]
+    ///
+    /// # Safety
+    ///
+    /// the file must not be modified or truncated while it is mapped, see [MappedFile::open]
+    pub unsafe fn open_readonly(path: impl AsRef<Path>) -> io::Result<MappedFile<Self>> {
+        MappedFile::open(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{load, MmapError};
+    use crate::{
+        checksum::PubFile,
+        data::EoSerialize,
+        protocol::{
+            map::Emf,
+            r#pub::{Eif, EifRecord},
+        },
+    };
+
+    // a file in the temp directory that is removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &[u8]) -> Self {
+            let path = std::env::temp_dir().join(format!("eolib-{}-{}", std::process::id(), name));
+            fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn loads_mapped_pub_file() {
+        let eif = Eif {
+            total_items_count: 1,
+            items: vec![EifRecord {
+                name: "Gold".to_owned(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let bytes = eif.to_bytes().unwrap();
+        let file = TempFile::new("load.eif", &bytes);
+
+        let loaded: Eif = unsafe { load(&file.0) }.unwrap();
+        assert_eq!(loaded, eif);
+
+        assert!(matches!(
+            unsafe { load::<Eif>(file.0.with_extension("missing")) },
+            Err(MmapError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn edits_are_copied_from_the_mapping() {
+        let emf = Emf {
+            width: 10,
+            height: 12,
+            ..Default::default()
+        };
+        let bytes = emf.to_bytes().unwrap();
+        let file = TempFile::new("edit.emf", &bytes);
+
+        let mut mapped = unsafe { Emf::open_readonly(&file.0) }.unwrap();
+        assert_eq!(mapped.bytes(), &bytes);
+        assert_eq!(mapped.get().unwrap(), &emf);
+        assert!(!mapped.is_modified());
+
+        mapped.to_mut().unwrap().width = 20;
+        assert!(mapped.is_modified());
+        assert_eq!(mapped.bytes(), &bytes);
+
+        let edited = Emf::from_bytes(&mapped.to_bytes().unwrap()).unwrap();
+        assert_eq!(edited.width, 20);
+        assert_eq!(mapped.into_inner().unwrap(), edited);
+    }
+
+    #[test]
+    fn pub_files_open_readonly() {
+        let bytes = Eif::default().to_bytes().unwrap();
+        let file = TempFile::new("readonly.eif", &bytes);
+
+        let mapped = unsafe { Eif::open_readonly(&file.0) }.unwrap();
+        assert_eq!(mapped.to_bytes().unwrap(), bytes);
+        assert_eq!(
+            mapped.into_inner().unwrap(),
+            Eif::from_bytes(&bytes).unwrap()
+        );
+    }
+}
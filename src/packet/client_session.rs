@@ -0,0 +1,457 @@
+use bytes::Bytes;
+use thiserror::Error;
+
+use crate::{
+    data::{EoReader, EoReaderError, EoSerialize, StrictnessProfile},
+    encrypt::{parse_swap_multiples, SwapMultipleError, SwapMultiples},
+    protocol::net::{
+        server::{
+            ConnectionPlayerServerPacket, InitInitServerPacket, InitInitServerPacketReplyCodeData,
+        },
+        PacketAction, PacketFamily,
+    },
+};
+
+use super::{
+    get_init_sequence_start, get_ping_sequence_start, FrameDecoder, PacketBuilder,
+    PacketBuilderError, SequenceReset, Sequencer, SessionState,
+};
+
+#[derive(Error, Debug)]
+pub enum ClientSessionError {
+    #[error("Can't send {0:?}_{1:?} before the Init_Init handshake is complete")]
+    HandshakeIncomplete(PacketFamily, PacketAction),
+    #[error("Packet of length {0} is missing its family and action")]
+    TruncatedPacket(usize),
+    #[error("{0}")]
+    ReadError(EoReaderError),
+    #[error("{0}")]
+    SwapMultipleError(SwapMultipleError),
+    #[error("{0}")]
+    PacketBuilderError(PacketBuilderError),
+}
+
+impl From<EoReaderError> for ClientSessionError {
+    fn from(e: EoReaderError) -> Self {
+        Self::ReadError(e)
+    }
+}
+
+impl From<SwapMultipleError> for ClientSessionError {
+    fn from(e: SwapMultipleError) -> Self {
+        Self::SwapMultipleError(e)
+    }
+}
+
+impl From<PacketBuilderError> for ClientSessionError {
+    fn from(e: PacketBuilderError) -> Self {
+        Self::PacketBuilderError(e)
+    }
+}
+
+#[derive(Debug)]
+/// The client side of a connection, encrypting and sequencing packets as the handshake requires
+///
+/// Before the handshake only the Init_Init packet can be sent, and it is neither encrypted nor
+/// sequenced. Once the server's Init_Init reply is [decoded](ClientSession::decode) every packet
+/// is encrypted and sequenced with the values it sent. The sequence start sent in
+/// Connection_Player pings is applied too.
+///
+/// No IO is done so it works the same with blocking sockets or any async runtime.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     packet::{ClientSession, ClientSessionError, InitReplyBuilder},
+///     protocol::net::{
+///         client::{InitInitClientPacket, WalkPlayerClientPacket},
+///         PacketAction, PacketFamily,
+///     },
+/// };
+///
+/// let mut session = ClientSession::new();
+///
+/// let walk = WalkPlayerClientPacket::default();
+/// assert!(matches!(
+///     session.encode(PacketFamily::Walk, PacketAction::Player, &walk),
+///     Err(ClientSessionError::HandshakeIncomplete(_, _))
+/// ));
+///
+/// let init = InitInitClientPacket::default();
+/// let packet = session
+///     .encode(PacketFamily::Init, PacketAction::Init, &init)
+///     .unwrap();
+/// assert_eq!(packet[2..4], [255, 255]);
+///
+/// // the server's reply
+/// let reply = InitReplyBuilder::new(0, 1).build().unwrap();
+/// session.feed(&reply);
+/// let (family, action, _) = session.decode().unwrap().unwrap();
+/// assert_eq!((family, action), (PacketFamily::Init, PacketAction::Init));
+///
+/// assert!(session.is_handshake_complete());
+/// assert!(session
+///     .encode(PacketFamily::Walk, PacketAction::Player, &walk)
+///     .is_ok());
+/// ```
+pub struct ClientSession {
+    decoder: FrameDecoder,
+    sequencer: Sequencer,
+    swap_multiples: Option<SwapMultiples>,
+    packets_received: u64,
+    packets_sent: u64,
+    strictness_profile: StrictnessProfile,
+}
+
+impl ClientSession {
+    /// creates a new [ClientSession] waiting for the Init_Init handshake
+    pub fn new() -> Self {
+        Self {
+            decoder: FrameDecoder::new(),
+            sequencer: Sequencer::new(0),
+            swap_multiples: None,
+            packets_received: 0,
+            packets_sent: 0,
+            strictness_profile: StrictnessProfile::default(),
+        }
+    }
+
+    /// returns true once the server's Init_Init reply has been decoded
+    pub fn is_handshake_complete(&self) -> bool {
+        self.swap_multiples.is_some()
+    }
+
+    /// returns the swap multiples received in the Init_Init reply
+    pub fn swap_multiples(&self) -> Option<SwapMultiples> {
+        self.swap_multiples
+    }
+
+    /// returns the sequencer sent packets are numbered with
+    pub fn sequencer(&self) -> &Sequencer {
+        &self.sequencer
+    }
+
+    /// returns the sequencer sent packets are numbered with
+    ///
+    /// resets from Init_Init and Connection_Player are applied automatically, others like
+    /// Account_Reply have to be applied with [reset](Sequencer::reset)
+    pub fn sequencer_mut(&mut self) -> &mut Sequencer {
+        &mut self.sequencer
+    }
+
+    /// returns the strictness profile received packets are read with
+    pub fn strictness_profile(&self) -> StrictnessProfile {
+        self.strictness_profile
+    }
+
+    /// reads every packet decoded after this call with `profile`
+    pub fn set_strictness_profile(&mut self, profile: StrictnessProfile) {
+        self.strictness_profile = profile;
+    }
+
+    /// returns the number of whole packets decoded, including rejected ones
+    pub fn packets_received(&self) -> u64 {
+        self.packets_received
+    }
+
+    /// returns the number of packets encoded
+    pub fn packets_sent(&self) -> u64 {
+        self.packets_sent
+    }
+
+    /// returns a copy of the sequencer, swap multiples and packet counts
+    pub fn state(&self) -> SessionState {
+        SessionState {
+            sequencer: self.sequencer.state(),
+            swap_multiples: self.swap_multiples,
+            packets_received: self.packets_received,
+            packets_sent: self.packets_sent,
+        }
+    }
+
+    /// returns a packet ready to send to the server, with its length prefix
+    ///
+    /// Init_Init is sent as it is, every packet after the handshake is sequenced and encrypted
+    pub fn encode<T: EoSerialize>(
+        &mut self,
+        family: PacketFamily,
+        action: PacketAction,
+        packet: &T,
+    ) -> Result<Bytes, ClientSessionError> {
+        let mut builder = PacketBuilder::new(family, action).add_struct(packet);
+        let sequenced = (family, action) != (PacketFamily::Init, PacketAction::Init);
+        if sequenced {
+            let swap_multiples = match self.swap_multiples {
+                Some(swap_multiples) => swap_multiples,
+                None => return Err(ClientSessionError::HandshakeIncomplete(family, action)),
+            };
+
+            builder = builder
+                .sequence(self.sequencer.peek_sequence())
+                .encrypt(swap_multiples.client);
+        }
+
+        // the sequence is only used up once the packet is ready to send, so a packet that
+        // fails to serialize doesn't put the server out of step
+        let packet = builder.build()?;
+        if sequenced {
+            self.sequencer.next_sequence();
+        }
+        self.packets_sent += 1;
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::packet_sent(family, action, packet.len());
+
+        Ok(packet)
+    }
+
+    /// adds data read from the socket
+    pub fn feed(&mut self, data: &[u8]) {
+        self.decoder.extend(data);
+    }
+
+    /// returns the next decrypted packet if all of it has been fed
+    ///
+    /// the reader starts after the family and action
+    pub fn decode(
+        &mut self,
+    ) -> Result<Option<(PacketFamily, PacketAction, EoReader)>, ClientSessionError> {
+        let packet = match self.decoder.decode() {
+            Some(packet) => packet,
+            None => return Ok(None),
+        };
+        self.packets_received += 1;
+
+        if packet.len() < 2 {
+            #[cfg(feature = "metrics")]
+            crate::metrics::decode_error();
+            return Err(ClientSessionError::TruncatedPacket(packet.len()));
+        }
+
+        let action = PacketAction::from(packet[0]);
+        let family = PacketFamily::from(packet[1]);
+        let data = packet.slice(2..);
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::packet_received(family, action, packet.len() + 2);
+
+        match (family, action) {
+            (PacketFamily::Init, PacketAction::Init) if !self.is_handshake_complete() => {
+                let result = self.handshake(&data);
+
+                #[cfg(feature = "metrics")]
+                if result.is_err() {
+                    crate::metrics::handshake_failure();
+                }
+
+                result?;
+            }
+            (PacketFamily::Connection, PacketAction::Player) => {
+                let ping = ConnectionPlayerServerPacket::deserialize(&self.reader(data.clone()))?;
+                let start = get_ping_sequence_start(ping.seq1, ping.seq2);
+                self.sequencer.reset(start, SequenceReset::Ping);
+            }
+            _ => {}
+        }
+
+        Ok(Some((family, action, self.reader(data))))
+    }
+}
+
+impl ClientSession {
+    fn reader(&self, data: Bytes) -> EoReader {
+        let reader = EoReader::new(data);
+        reader.set_strictness_profile(self.strictness_profile);
+        reader
+    }
+
+    fn handshake(&mut self, data: &Bytes) -> Result<(), ClientSessionError> {
+        let reply = InitInitServerPacket::deserialize(&self.reader(data.clone()))?;
+        if let Some(InitInitServerPacketReplyCodeData::OK(ok)) = reply.reply_code_data {
+            let swap_multiples = parse_swap_multiples(&ok)?;
+            let start = get_init_sequence_start(ok.seq1 as i32, ok.seq2 as i32);
+            self.sequencer.reset(start, SequenceReset::Init);
+            self.decoder.set_swap_multiple(swap_multiples.server);
+            self.swap_multiples = Some(swap_multiples);
+        }
+        Ok(())
+    }
+}
+
+impl Default for ClientSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClientSession;
+    use crate::{
+        data::EoSerialize,
+        packet::{get_ping_sequence_bytes, Connection, ConnectionEvent},
+        protocol::net::{
+            client::{InitInitClientPacket, WalkAction, WalkPlayerClientPacket},
+            server::ConnectionPlayerServerPacket,
+            PacketAction, PacketFamily,
+        },
+    };
+
+    fn poll_packet(connection: &mut Connection) -> (PacketFamily, PacketAction) {
+        match connection.poll_event().unwrap() {
+            ConnectionEvent::Packet(family, action, _) => (family, action),
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+
+    fn handshake(session: &mut ClientSession, connection: &mut Connection) {
+        let init = session
+            .encode(
+                PacketFamily::Init,
+                PacketAction::Init,
+                &InitInitClientPacket::default(),
+            )
+            .unwrap();
+        connection.feed(&init);
+        assert!(matches!(
+            connection.poll_event().unwrap(),
+            ConnectionEvent::HandshakeComplete(_)
+        ));
+
+        session.feed(&connection.poll_transmit().unwrap());
+        session.decode().unwrap().unwrap();
+        assert_eq!(session.swap_multiples(), connection.swap_multiples());
+    }
+
+    #[test]
+    fn state_counts_packets() {
+        let mut session = ClientSession::new();
+        let mut connection = Connection::new(1);
+        handshake(&mut session, &mut connection);
+
+        let packet = session
+            .encode(
+                PacketFamily::Walk,
+                PacketAction::Player,
+                &WalkPlayerClientPacket::default(),
+            )
+            .unwrap();
+        connection.feed(&packet);
+        poll_packet(&mut connection);
+
+        let state = session.state();
+        assert_eq!((state.packets_sent, state.packets_received), (2, 1));
+        assert_eq!(state.sequencer, session.sequencer().state());
+
+        let state = connection.state();
+        assert_eq!((state.packets_sent, state.packets_received), (1, 2));
+        assert_eq!(state.swap_multiples, session.state().swap_multiples);
+        assert_eq!(state.sequencer, connection.sequencer().state());
+    }
+
+    #[test]
+    fn failed_packets_keep_the_sequence() {
+        let mut session = ClientSession::new();
+        let mut connection = Connection::new(1);
+        handshake(&mut session, &mut connection);
+
+        let invalid = WalkPlayerClientPacket {
+            walk_action: WalkAction {
+                timestamp: -1,
+                ..Default::default()
+            },
+        };
+        assert!(session
+            .encode(PacketFamily::Walk, PacketAction::Player, &invalid)
+            .is_err());
+        assert_eq!(session.state().packets_sent, 1);
+
+        let packet = session
+            .encode(
+                PacketFamily::Walk,
+                PacketAction::Player,
+                &WalkPlayerClientPacket::default(),
+            )
+            .unwrap();
+        connection.feed(&packet);
+        assert_eq!(
+            poll_packet(&mut connection),
+            (PacketFamily::Walk, PacketAction::Player)
+        );
+    }
+
+    #[test]
+    fn talks_to_a_server_connection() {
+        let mut session = ClientSession::new();
+        let mut connection = Connection::new(1);
+        handshake(&mut session, &mut connection);
+
+        let walk = WalkPlayerClientPacket::default();
+        for _ in 0..12 {
+            let packet = session
+                .encode(PacketFamily::Walk, PacketAction::Player, &walk)
+                .unwrap();
+            connection.feed(&packet);
+            assert_eq!(
+                poll_packet(&mut connection),
+                (PacketFamily::Walk, PacketAction::Player)
+            );
+        }
+
+        // a ping moves the sequence start on both sides
+        let [seq1, seq2] = get_ping_sequence_bytes(100);
+        connection
+            .send(
+                PacketFamily::Connection,
+                PacketAction::Player,
+                &ConnectionPlayerServerPacket { seq1, seq2 },
+            )
+            .unwrap();
+        connection.sequencer_mut().set_start(100);
+
+        session.feed(&connection.poll_transmit().unwrap());
+        let (family, action, reader) = session.decode().unwrap().unwrap();
+        assert_eq!(
+            (family, action),
+            (PacketFamily::Connection, PacketAction::Player)
+        );
+        assert_eq!(
+            ConnectionPlayerServerPacket::deserialize(&reader).unwrap(),
+            ConnectionPlayerServerPacket { seq1, seq2 }
+        );
+        assert_eq!(session.sequencer_mut().get_start(), 100);
+
+        let packet = session
+            .encode(PacketFamily::Walk, PacketAction::Player, &walk)
+            .unwrap();
+        connection.feed(&packet);
+        assert_eq!(
+            poll_packet(&mut connection),
+            (PacketFamily::Walk, PacketAction::Player)
+        );
+    }
+
+    #[test]
+    fn sequence_width_changes_as_the_counter_wraps() {
+        let mut session = ClientSession::new();
+        let mut connection = Connection::new(1);
+        handshake(&mut session, &mut connection);
+
+        // 251 and 252 are sent as chars, 253 to 259 as shorts
+        session.sequencer_mut().set_start(250);
+        connection.sequencer_mut().set_start(250);
+
+        let walk = WalkPlayerClientPacket::default();
+        for _ in 0..25 {
+            let packet = session
+                .encode(PacketFamily::Walk, PacketAction::Player, &walk)
+                .unwrap();
+            connection.feed(&packet);
+            assert_eq!(
+                poll_packet(&mut connection),
+                (PacketFamily::Walk, PacketAction::Player)
+            );
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::encrypt::{encrypt_packet, SwapMultiples};
+
+use super::{encode_packet_length, frame_decoder::decode_frame, PacketLengthError};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Frames and encrypts the packets of one server connection without owning a buffer
+///
+/// Packets are decoded from and encoded into buffers owned by the caller, so a [Codec] is
+/// only the encryption state of a connection. It is `Send`, `Copy` and can be handed to a
+/// task per connection. Decoded packets are decrypted with the client's swap multiple and
+/// have their length prefix removed, encoded packets are encrypted with the server's and
+/// given one. Init_Init packets are never encrypted.
+///
+/// With the `tokio` feature it implements `tokio_util::codec::Decoder` and `Encoder<Bytes>`
+/// for use with `Framed`.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use eolib::{
+///     encrypt::SwapMultiples,
+///     packet::{Codec, PacketBuilder},
+///     protocol::net::{PacketAction, PacketFamily},
+/// };
+///
+/// let multiples = SwapMultiples::new(6, 8).unwrap();
+/// let mut codec = Codec::new();
+/// codec.set_swap_multiples(multiples);
+///
+/// let packet = PacketBuilder::new(PacketFamily::Talk, PacketAction::Report)
+///     .sequence(1)
+///     .add_char(2)
+///     .encrypt(multiples.client)
+///     .build()
+///     .unwrap();
+///
+/// let mut buf = BytesMut::from(&packet[..3]);
+/// assert_eq!(codec.decode_packet(&mut buf), None);
+///
+/// buf.extend_from_slice(&packet[3..]);
+/// let decoded = codec.decode_packet(&mut buf).unwrap();
+/// assert_eq!(decoded[..], [21, 18, 2, 3]);
+///
+/// let mut out = BytesMut::new();
+/// codec.encode_packet(&decoded, &mut out).unwrap();
+/// assert_eq!(out.len(), packet.len());
+/// ```
+pub struct Codec {
+    swap_multiples: Option<SwapMultiples>,
+}
+
+impl Codec {
+    /// creates a new [Codec] that doesn't encrypt or decrypt packets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// returns the swap multiples packets are encrypted and decrypted with
+    pub fn swap_multiples(&self) -> Option<SwapMultiples> {
+        self.swap_multiples
+    }
+
+    /// encrypts and decrypts every packet after this call with the swap multiples sent in the
+    /// Init_Init reply
+    pub fn set_swap_multiples(&mut self, swap_multiples: SwapMultiples) {
+        self.swap_multiples = Some(swap_multiples);
+    }
+
+    /// removes the next packet from `src` if all of it has been buffered, returning it
+    /// decrypted and without its length prefix
+    pub fn decode_packet(&self, src: &mut BytesMut) -> Option<Bytes> {
+        let packet = decode_frame(src, self.swap_multiples.map(|m| m.client));
+
+        #[cfg(feature = "metrics")]
+        if let Some(packet) = &packet {
+            match packet[..] {
+                [action, family, ..] => {
+                    crate::metrics::packet_received(family.into(), action.into(), packet.len() + 2)
+                }
+                _ => crate::metrics::decode_error(),
+            }
+        }
+
+        packet
+    }
+
+    /// appends `packet` (the header and data) to `dst`, encrypted and with its length prefix
+    pub fn encode_packet(
+        &self,
+        packet: &[u8],
+        dst: &mut BytesMut,
+    ) -> Result<(), PacketLengthError> {
+        let length = encode_packet_length(packet.len())?;
+        dst.reserve(packet.len() + 2);
+        dst.put_slice(&length);
+
+        let start = dst.len();
+        dst.put_slice(packet);
+        if let Some(swap_multiples) = self.swap_multiples {
+            encrypt_packet(&mut dst[start..], swap_multiples.server);
+        }
+
+        #[cfg(feature = "metrics")]
+        if let [action, family, ..] = *packet {
+            crate::metrics::packet_sent(family.into(), action.into(), packet.len() + 2);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio_util::codec::Decoder for Codec {
+    type Item = Bytes;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self.decode_packet(src))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio_util::codec::Encoder<Bytes> for Codec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode_packet(&item, dst)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::Codec;
+    use crate::{
+        encrypt::{decrypt_packet, SwapMultiples},
+        packet::{InitReplyBuilder, PacketLengthError, MAX_PACKET_SIZE},
+    };
+
+    fn assert_send_clone<T: Send + Sync + Clone>() {}
+
+    #[test]
+    fn is_send_and_clone() {
+        assert_send_clone::<Codec>();
+    }
+
+    #[test]
+    fn init_reply_is_not_encrypted() {
+        let mut codec = Codec::new();
+        codec.set_swap_multiples(SwapMultiples::new(7, 9).unwrap());
+
+        let reply = InitReplyBuilder::new(0, 1).build().unwrap();
+        let mut out = BytesMut::new();
+        codec.encode_packet(&reply[2..], &mut out).unwrap();
+        assert_eq!(out[..], reply[..]);
+    }
+
+    #[test]
+    fn encodes_with_the_server_multiple() {
+        let mut codec = Codec::new();
+        codec.set_swap_multiples(SwapMultiples::new(7, 9).unwrap());
+
+        let packet = [4, 5, 6, 7, 14, 21, 100];
+        let mut out = BytesMut::new();
+        codec.encode_packet(&packet, &mut out).unwrap();
+        codec.encode_packet(&packet, &mut out).unwrap();
+        assert_eq!(out.len(), (packet.len() + 2) * 2);
+
+        let mut second = out[packet.len() + 4..].to_vec();
+        decrypt_packet(&mut second, 7);
+        assert_eq!(second, packet);
+
+        assert_eq!(
+            codec.encode_packet(&vec![0; MAX_PACKET_SIZE + 1], &mut out),
+            Err(PacketLengthError::TooLarge(MAX_PACKET_SIZE + 1))
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn tokio_codec() {
+        use bytes::Bytes;
+        use tokio_util::codec::{Decoder, Encoder};
+
+        let mut codec = Codec::new();
+        let mut buf = BytesMut::new();
+        codec
+            .encode(Bytes::from_static(&[1, 2, 3]), &mut buf)
+            .unwrap();
+        buf.extend_from_slice(&[2, 254]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap()[..], [1, 2, 3]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert_eq!(buf.len(), 2);
+    }
+}
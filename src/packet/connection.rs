@@ -0,0 +1,500 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+use thiserror::Error;
+
+use crate::{
+    data::{EoReader, EoReaderError, EoSerialize, StrictnessProfile},
+    encrypt::{negotiate_swap_multiples, SwapMultiples},
+    protocol::net::{client::InitInitClientPacket, PacketAction, PacketFamily},
+};
+
+use super::{
+    generate_sequence_start, FrameDecoder, InitReplyBuilder, InitReplyError, PacketBuilder,
+    PacketBuilderError, SequenceCheck, SequenceReset, Sequencer, SequencerState,
+};
+
+#[derive(Error, Debug)]
+pub enum ConnectionError {
+    #[error("Packet of length {0} is missing its family and action")]
+    TruncatedPacket(usize),
+    #[error("Expected Init_Init but received {0:?}_{1:?}")]
+    ExpectedInit(PacketFamily, PacketAction),
+    #[error("Invalid sequence {0} ({1:?})")]
+    InvalidSequence(i32, SequenceCheck),
+    #[error("{0}")]
+    ReadError(EoReaderError),
+    #[error("{0}")]
+    InitReplyError(InitReplyError),
+    #[error("{0}")]
+    PacketBuilderError(PacketBuilderError),
+}
+
+impl From<EoReaderError> for ConnectionError {
+    fn from(e: EoReaderError) -> Self {
+        Self::ReadError(e)
+    }
+}
+
+impl From<InitReplyError> for ConnectionError {
+    fn from(e: InitReplyError) -> Self {
+        Self::InitReplyError(e)
+    }
+}
+
+impl From<PacketBuilderError> for ConnectionError {
+    fn from(e: PacketBuilderError) -> Self {
+        Self::PacketBuilderError(e)
+    }
+}
+
+#[derive(Debug)]
+/// Returned by [Connection::poll_event]
+pub enum ConnectionEvent {
+    /// the client's Init_Init packet was accepted and the reply queued. every packet after it
+    /// is encrypted and sequenced
+    HandshakeComplete(InitInitClientPacket),
+    /// a decrypted packet with its sequence checked, the reader starts after the sequence
+    Packet(PacketFamily, PacketAction, EoReader),
+    /// no whole packet is buffered, [feed](Connection::feed) more data from the socket
+    NeedMoreData,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A copy of the state of a [Connection] or [ClientSession](super::ClientSession)
+///
+/// for logging or analysing a live session offline. It can't be used to resume one
+pub struct SessionState {
+    pub sequencer: SequencerState,
+    pub swap_multiples: Option<SwapMultiples>,
+    pub packets_received: u64,
+    pub packets_sent: u64,
+}
+
+#[derive(Debug)]
+/// The server side of a client connection as a state machine that does no IO
+///
+/// Bytes read from the socket are passed to [feed](Connection::feed) and
+/// [poll_event](Connection::poll_event) is called until it returns
+/// [NeedMoreData](ConnectionEvent::NeedMoreData). Packets to send are queued by
+/// [send](Connection::send) and taken with [poll_transmit](Connection::poll_transmit).
+/// This works the same under mio, a thread per connection or any async runtime.
+///
+/// The Init_Init handshake is answered by the connection itself with a random sequence start
+/// and swap multiples. A server that wants to reject old clients can check the version in
+/// [HandshakeComplete](ConnectionEvent::HandshakeComplete) and close the connection.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     packet::{Connection, ConnectionEvent, PacketBuilder},
+///     protocol::net::{client::InitInitClientPacket, PacketAction, PacketFamily},
+/// };
+///
+/// let mut connection = Connection::new(1);
+/// assert!(matches!(
+///     connection.poll_event().unwrap(),
+///     ConnectionEvent::NeedMoreData
+/// ));
+///
+/// let init = PacketBuilder::new(PacketFamily::Init, PacketAction::Init)
+///     .add_struct(&InitInitClientPacket {
+///         challenge: 123456,
+///         ..Default::default()
+///     })
+///     .build()
+///     .unwrap();
+/// connection.feed(&init);
+///
+/// match connection.poll_event().unwrap() {
+///     ConnectionEvent::HandshakeComplete(init) => assert_eq!(init.challenge, 123456),
+///     _ => unreachable!(),
+/// }
+///
+/// // the Init_Init reply
+/// assert!(connection.poll_transmit().is_some());
+/// assert!(connection.is_handshake_complete());
+/// ```
+pub struct Connection {
+    player_id: i32,
+    decoder: FrameDecoder,
+    sequencer: Sequencer,
+    swap_multiples: Option<SwapMultiples>,
+    outgoing: VecDeque<Bytes>,
+    packets_received: u64,
+    packets_sent: u64,
+    strictness_profile: StrictnessProfile,
+}
+
+impl Connection {
+    /// creates a new [Connection] waiting for the client's Init_Init packet
+    ///
+    /// `player_id` is sent to the client in the Init_Init reply
+    pub fn new(player_id: i32) -> Self {
+        Self {
+            player_id,
+            decoder: FrameDecoder::new(),
+            sequencer: Sequencer::new(0),
+            swap_multiples: None,
+            outgoing: VecDeque::new(),
+            packets_received: 0,
+            packets_sent: 0,
+            strictness_profile: StrictnessProfile::default(),
+        }
+    }
+
+    /// returns true once the Init_Init packet has been answered
+    pub fn is_handshake_complete(&self) -> bool {
+        self.swap_multiples.is_some()
+    }
+
+    /// returns the swap multiples sent in the Init_Init reply
+    pub fn swap_multiples(&self) -> Option<SwapMultiples> {
+        self.swap_multiples
+    }
+
+    /// returns the sequencer received packets are checked with
+    pub fn sequencer(&self) -> &Sequencer {
+        &self.sequencer
+    }
+
+    /// returns the sequencer received packets are checked with
+    ///
+    /// it has to be reset when the server sends a new sequence start, see [SequenceReset]
+    pub fn sequencer_mut(&mut self) -> &mut Sequencer {
+        &mut self.sequencer
+    }
+
+    /// returns the strictness profile received packets are read with
+    pub fn strictness_profile(&self) -> StrictnessProfile {
+        self.strictness_profile
+    }
+
+    /// reads the Init_Init packet and every packet returned after this call with `profile`,
+    /// and checks sequences with its tolerance
+    pub fn set_strictness_profile(&mut self, profile: StrictnessProfile) {
+        self.sequencer.set_tolerance(profile.sequence_tolerance);
+        self.strictness_profile = profile;
+    }
+
+    /// returns the number of whole packets received, including rejected ones
+    pub fn packets_received(&self) -> u64 {
+        self.packets_received
+    }
+
+    /// returns the number of packets queued to send, including the Init_Init reply
+    pub fn packets_sent(&self) -> u64 {
+        self.packets_sent
+    }
+
+    /// returns a copy of the sequencer, swap multiples and packet counts
+    pub fn state(&self) -> SessionState {
+        SessionState {
+            sequencer: self.sequencer.state(),
+            swap_multiples: self.swap_multiples,
+            packets_received: self.packets_received,
+            packets_sent: self.packets_sent,
+        }
+    }
+
+    /// adds data read from the socket
+    pub fn feed(&mut self, data: &[u8]) {
+        self.decoder.extend(data);
+    }
+
+    /// returns the next event for the data fed so far
+    ///
+    /// an error means the client sent something a well behaved client never would, and the
+    /// connection should be closed
+    pub fn poll_event(&mut self) -> Result<ConnectionEvent, ConnectionError> {
+        let packet = match self.decoder.decode() {
+            Some(packet) => packet,
+            None => return Ok(ConnectionEvent::NeedMoreData),
+        };
+        self.packets_received += 1;
+
+        if packet.len() < 2 {
+            #[cfg(feature = "metrics")]
+            crate::metrics::decode_error();
+            return Err(ConnectionError::TruncatedPacket(packet.len()));
+        }
+
+        let action = PacketAction::from(packet[0]);
+        let family = PacketFamily::from(packet[1]);
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::packet_received(family, action, packet.len() + 2);
+
+        if !self.is_handshake_complete() {
+            let result = self.handshake(family, action, &packet);
+
+            #[cfg(feature = "metrics")]
+            if result.is_err() {
+                crate::metrics::handshake_failure();
+            }
+
+            return result.map(ConnectionEvent::HandshakeComplete);
+        }
+
+        let reader = EoReader::new(packet.slice(2..));
+        let sequence = if Sequencer::sequence_is_short(self.sequencer.peek_sequence()) {
+            reader.get_short()
+        } else {
+            reader.get_char()
+        };
+
+        match self.sequencer.verify(sequence) {
+            SequenceCheck::Ok => {}
+            check => {
+                #[cfg(feature = "metrics")]
+                crate::metrics::sequence_error();
+                return Err(ConnectionError::InvalidSequence(sequence, check));
+            }
+        }
+
+        let data = packet.slice(2 + reader.position()..);
+        Ok(ConnectionEvent::Packet(family, action, self.reader(data)))
+    }
+
+    /// queues a packet to be sent to the client, encrypted once the handshake is complete
+    pub fn send<T: EoSerialize>(
+        &mut self,
+        family: PacketFamily,
+        action: PacketAction,
+        packet: &T,
+    ) -> Result<(), ConnectionError> {
+        let mut builder = PacketBuilder::new(family, action).add_struct(packet);
+        if let Some(swap_multiples) = self.swap_multiples {
+            builder = builder.encrypt(swap_multiples.server);
+        }
+
+        let packet = builder.build()?;
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::packet_sent(family, action, packet.len());
+
+        self.outgoing.push_back(packet);
+        self.packets_sent += 1;
+        Ok(())
+    }
+
+    /// returns the next queued packet to write to the socket
+    pub fn poll_transmit(&mut self) -> Option<Bytes> {
+        self.outgoing.pop_front()
+    }
+
+    fn handshake(
+        &mut self,
+        family: PacketFamily,
+        action: PacketAction,
+        packet: &Bytes,
+    ) -> Result<InitInitClientPacket, ConnectionError> {
+        if (family, action) != (PacketFamily::Init, PacketAction::Init) {
+            return Err(ConnectionError::ExpectedInit(family, action));
+        }
+
+        let reader = self.reader(packet.slice(2..));
+        let init = InitInitClientPacket::deserialize(&reader)?;
+        self.accept(&init)?;
+        Ok(init)
+    }
+
+    fn reader(&self, data: Bytes) -> EoReader {
+        let reader = EoReader::new(data);
+        reader.set_strictness_profile(self.strictness_profile);
+        reader
+    }
+
+    fn accept(&mut self, init: &InitInitClientPacket) -> Result<(), ConnectionError> {
+        let multiples = negotiate_swap_multiples();
+        let sequence_start = generate_sequence_start();
+        let reply = InitReplyBuilder::new(init.challenge, self.player_id)
+            .sequence_start(sequence_start)
+            .swap_multiples(multiples)
+            .build()?;
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::packet_sent(PacketFamily::Init, PacketAction::Init, reply.len());
+
+        self.outgoing.push_back(reply);
+        self.packets_sent += 1;
+        self.decoder.set_swap_multiple(multiples.client);
+        self.sequencer.reset(sequence_start, SequenceReset::Init);
+        self.swap_multiples = Some(multiples);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Connection, ConnectionError, ConnectionEvent};
+    use crate::{
+        data::{EoReader, EoSerialize},
+        encrypt::{decrypt_packet, parse_swap_multiples},
+        packet::{get_init_sequence_start, PacketBuilder, Sequencer},
+        protocol::net::{
+            client::{InitInitClientPacket, WalkPlayerClientPacket},
+            server::{InitInitServerPacket, InitInitServerPacketReplyCodeData},
+            PacketAction, PacketFamily,
+        },
+    };
+
+    fn handshake(connection: &mut Connection) -> (u8, Sequencer) {
+        let init = PacketBuilder::new(PacketFamily::Init, PacketAction::Init)
+            .add_struct(&InitInitClientPacket {
+                challenge: 1000,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        connection.feed(&init);
+        assert!(matches!(
+            connection.poll_event().unwrap(),
+            ConnectionEvent::HandshakeComplete(_)
+        ));
+
+        let reply = connection.poll_transmit().unwrap();
+        let reader = EoReader::new(reply.slice(4..));
+        match InitInitServerPacket::deserialize(&reader)
+            .unwrap()
+            .reply_code_data
+        {
+            Some(InitInitServerPacketReplyCodeData::OK(ok)) => {
+                let multiples = parse_swap_multiples(&ok).unwrap();
+                assert_eq!(connection.swap_multiples(), Some(multiples));
+                let start = get_init_sequence_start(ok.seq1 as i32, ok.seq2 as i32);
+                (multiples.client, Sequencer::new(start))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn packets_after_handshake() {
+        let mut connection = Connection::new(1);
+        let (client_multiple, mut sequencer) = handshake(&mut connection);
+
+        let walk = WalkPlayerClientPacket::default();
+        let mut data = Vec::new();
+        for _ in 0..3 {
+            let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+                .sequence(sequencer.next_sequence())
+                .add_struct(&walk)
+                .encrypt(client_multiple)
+                .build()
+                .unwrap();
+            data.extend_from_slice(&packet);
+        }
+
+        // split across reads
+        let (first, second) = data.split_at(5);
+        connection.feed(first);
+        assert!(matches!(
+            connection.poll_event().unwrap(),
+            ConnectionEvent::NeedMoreData
+        ));
+        connection.feed(second);
+
+        for _ in 0..3 {
+            match connection.poll_event().unwrap() {
+                ConnectionEvent::Packet(PacketFamily::Walk, PacketAction::Player, reader) => {
+                    assert_eq!(WalkPlayerClientPacket::deserialize(&reader).unwrap(), walk);
+                }
+                event => panic!("unexpected event {:?}", event),
+            }
+        }
+        assert!(matches!(
+            connection.poll_event().unwrap(),
+            ConnectionEvent::NeedMoreData
+        ));
+
+        // a replayed sequence is rejected
+        let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+            .sequence(sequencer.get_start())
+            .encrypt(client_multiple)
+            .build()
+            .unwrap();
+        connection.feed(&packet);
+        assert!(matches!(
+            connection.poll_event(),
+            Err(ConnectionError::InvalidSequence(_, _))
+        ));
+    }
+
+    #[test]
+    fn sent_packets_are_encrypted() {
+        let mut connection = Connection::new(1);
+        handshake(&mut connection);
+
+        let server_multiple = connection.swap_multiples().unwrap().server;
+        connection
+            .send(
+                PacketFamily::Walk,
+                PacketAction::Player,
+                &WalkPlayerClientPacket::default(),
+            )
+            .unwrap();
+
+        let packet = connection.poll_transmit().unwrap();
+        let mut buf = packet[2..].to_vec();
+        decrypt_packet(&mut buf, server_multiple);
+        assert_eq!(
+            buf[..2],
+            [u8::from(PacketAction::Player), u8::from(PacketFamily::Walk)]
+        );
+        assert_eq!(connection.poll_transmit(), None);
+    }
+
+    #[test]
+    fn first_packet_must_be_init() {
+        let mut connection = Connection::new(1);
+        let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+            .build()
+            .unwrap();
+        connection.feed(&packet);
+        assert!(matches!(
+            connection.poll_event(),
+            Err(ConnectionError::ExpectedInit(
+                PacketFamily::Walk,
+                PacketAction::Player
+            ))
+        ));
+        assert!(!connection.is_handshake_complete());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn rejected_packets_are_counted() {
+        use crate::metrics::{tests::TestRecorder, HANDSHAKE_FAILURES, SEQUENCE_ERRORS};
+
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let mut connection = Connection::new(1);
+            let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+                .build()
+                .unwrap();
+            connection.feed(&packet);
+            assert!(connection.poll_event().is_err());
+
+            // the same sequence twice
+            let (client_multiple, mut sequencer) = handshake(&mut connection);
+            let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+                .sequence(sequencer.next_sequence())
+                .encrypt(client_multiple)
+                .build()
+                .unwrap();
+            connection.feed(&packet);
+            connection.feed(&packet);
+            assert!(connection.poll_event().is_ok());
+            assert!(connection.poll_event().is_err());
+        });
+
+        assert_eq!(recorder.get(HANDSHAKE_FAILURES), 1);
+        assert_eq!(recorder.get(SEQUENCE_ERRORS), 1);
+        assert_eq!(
+            recorder.get("eolib_packets_sent_total{family=Init,action=Init}"),
+            1
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::encrypt::decrypt_packet;
+
+use super::decode_packet_length;
+
+#[derive(Debug, Default)]
+/// Splits a stream of bytes into packets
+///
+/// Data can be fed in pieces of any size as it arrives from a socket. Once a whole packet
+/// (2-byte length prefix and data) is buffered it is returned by
+/// [decode](FrameDecoder::decode) without the length prefix, decrypted if a swap multiple
+/// has been set.
+///
+/// No IO is done so it works the same with blocking sockets or any async runtime.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::packet::FrameDecoder;
+///
+/// let mut decoder = FrameDecoder::new();
+///
+/// decoder.extend(&[5, 254, 1]);
+/// assert_eq!(decoder.decode(), None);
+///
+/// decoder.extend(&[2, 3, 4, 3, 254, 5]);
+/// assert_eq!(decoder.decode().unwrap()[..], [1, 2, 3, 4]);
+/// assert_eq!(decoder.decode(), None);
+///
+/// decoder.extend(&[6]);
+/// assert_eq!(decoder.decode().unwrap()[..], [5, 6]);
+/// ```
+pub struct FrameDecoder {
+    buf: BytesMut,
+    swap_multiple: Option<u8>,
+}
+
+impl FrameDecoder {
+    /// creates a new [FrameDecoder] that doesn't decrypt packets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// decrypts every packet returned after this call with the specified swap multiple
+    ///
+    /// the swap multiple is sent to the client in the INIT_INIT packet, so packets before it
+    /// are left as they are
+    pub fn set_swap_multiple(&mut self, swap_multiple: u8) {
+        self.swap_multiple = Some(swap_multiple);
+    }
+
+    /// adds data read from the socket to the buffer
+    pub fn extend(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// returns the number of bytes buffered that aren't part of a returned packet
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// returns the next packet if all of it has been buffered
+    pub fn decode(&mut self) -> Option<Bytes> {
+        decode_frame(&mut self.buf, self.swap_multiple)
+    }
+}
+
+// shared with Codec, which is handed its buffer by the caller
+pub(crate) fn decode_frame(buf: &mut BytesMut, swap_multiple: Option<u8>) -> Option<Bytes> {
+    if buf.len() < 2 {
+        return None;
+    }
+
+    let length = decode_packet_length(&[buf[0], buf[1]]);
+    if buf.len() < length + 2 {
+        return None;
+    }
+
+    buf.advance(2);
+    let mut packet = buf.split_to(length);
+    if let Some(swap_multiple) = swap_multiple {
+        decrypt_packet(&mut packet, swap_multiple);
+    }
+
+    Some(packet.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameDecoder;
+    use crate::{
+        packet::PacketBuilder,
+        protocol::net::{PacketAction, PacketFamily},
+    };
+
+    #[test]
+    fn byte_at_a_time() {
+        let packet = PacketBuilder::new(PacketFamily::Talk, PacketAction::Report)
+            .add_string("Hello, world!")
+            .encrypt(7)
+            .build()
+            .unwrap();
+
+        let mut decoder = FrameDecoder::new();
+        decoder.set_swap_multiple(7);
+
+        for byte in &packet[..packet.len() - 1] {
+            decoder.extend(&[*byte]);
+            assert_eq!(decoder.decode(), None);
+        }
+
+        decoder.extend(&packet[packet.len() - 1..]);
+        let decoded = decoder.decode().unwrap();
+        assert_eq!(decoded[..2], [21, 18]);
+        assert_eq!(&decoded[2..], b"Hello, world!");
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    fn several_packets_in_one_read() {
+        let mut decoder = FrameDecoder::new();
+        decoder.extend(&[1, 254, 2, 254, 3, 1]);
+
+        assert_eq!(decoder.decode().unwrap()[..], []);
+        assert_eq!(decoder.decode().unwrap()[..], [3]);
+        assert_eq!(decoder.decode(), None);
+        assert_eq!(decoder.buffered(), 1);
+    }
+}
@@ -0,0 +1,248 @@
+use bytes::Bytes;
+use thiserror::Error;
+
+use crate::{
+    data::{CHAR_MAX, SHORT_MAX},
+    encrypt::{server_verification_hash, SwapMultipleError, SwapMultiples, MAX_CHALLENGE},
+    protocol::net::{
+        server::{
+            InitInitServerPacket, InitInitServerPacketReplyCodeData,
+            InitInitServerPacketReplyCodeDataOk, InitReply,
+        },
+        PacketAction, PacketFamily,
+    },
+};
+
+use super::{get_init_sequence_bytes, PacketBuilder, PacketBuilderError};
+
+/// The largest sequence start that can be sent in the INIT_INIT server packet
+pub const MAX_INIT_SEQUENCE_START: i32 = CHAR_MAX - 10;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum InitReplyError {
+    #[error("Invalid challenge {0} must be between 0 and {}", MAX_CHALLENGE)]
+    InvalidChallenge(i32),
+    #[error("Invalid player id {0} must be between 0 and {}", SHORT_MAX - 1)]
+    InvalidPlayerId(i32),
+    #[error(
+        "Invalid sequence start {0} must be between 0 and {}",
+        MAX_INIT_SEQUENCE_START
+    )]
+    InvalidSequenceStart(i32),
+    #[error("{0}")]
+    SwapMultipleError(SwapMultipleError),
+    #[error("{0}")]
+    PacketBuilderError(PacketBuilderError),
+}
+
+impl From<SwapMultipleError> for InitReplyError {
+    fn from(e: SwapMultipleError) -> Self {
+        Self::SwapMultipleError(e)
+    }
+}
+
+impl From<PacketBuilderError> for InitReplyError {
+    fn from(e: PacketBuilderError) -> Self {
+        Self::PacketBuilderError(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Builds the INIT_INIT server packet that accepts a client's connection
+///
+/// The reply holds the sequence start (as [get_init_sequence_bytes]), the swap multiples
+/// for both directions, the player id and the response to the client's challenge.
+/// Every value is range checked by [build](InitReplyBuilder::build) so the client never
+/// receives one that wraps around.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     encrypt::{server_verification_hash, SwapMultipleError, SwapMultiples},
+///     packet::{get_init_sequence_start, InitReplyBuilder, InitReplyError},
+///     protocol::net::server::InitInitServerPacketReplyCodeData,
+/// };
+///
+/// let reply = InitReplyBuilder::new(123456, 1)
+///     .sequence_start(100)
+///     .swap_multiples(SwapMultiples::new(6, 8).unwrap())
+///     .to_packet()
+///     .unwrap();
+///
+/// match reply.reply_code_data {
+///     Some(InitInitServerPacketReplyCodeData::OK(ok)) => {
+///         let start = get_init_sequence_start(ok.seq1 as i32, ok.seq2 as i32);
+///         assert_eq!(start, 100);
+///         assert_eq!(ok.challenge_response, server_verification_hash(123456));
+///     }
+///     _ => unreachable!(),
+/// }
+///
+/// // the fields of SwapMultiples are public, so they're checked again when building
+/// let multiples = SwapMultiples {
+///     server: 6,
+///     client: 13,
+/// };
+/// assert_eq!(
+///     InitReplyBuilder::new(123456, 1).swap_multiples(multiples).build(),
+///     Err(InitReplyError::SwapMultipleError(
+///         SwapMultipleError::InvalidSwapMultiple(13)
+///     ))
+/// );
+/// ```
+pub struct InitReplyBuilder {
+    challenge: i32,
+    player_id: i32,
+    sequence_start: i32,
+    swap_multiples: SwapMultiples,
+}
+
+impl InitReplyBuilder {
+    /// creates a new [InitReplyBuilder] for the challenge sent by the client
+    ///
+    /// the sequence start defaults to `0` and both swap multiples to `6`
+    pub fn new(challenge: i32, player_id: i32) -> Self {
+        Self {
+            challenge,
+            player_id,
+            sequence_start: 0,
+            swap_multiples: SwapMultiples {
+                server: 6,
+                client: 6,
+            },
+        }
+    }
+
+    /// sets the sequence start, see [generate_sequence_start](super::generate_sequence_start)
+    pub fn sequence_start(mut self, sequence_start: i32) -> Self {
+        self.sequence_start = sequence_start;
+        self
+    }
+
+    /// sets the swap multiples the server encrypts and the client encrypts packets with, see
+    /// [negotiate_swap_multiples](crate::encrypt::negotiate_swap_multiples)
+    pub fn swap_multiples(mut self, swap_multiples: SwapMultiples) -> Self {
+        self.swap_multiples = swap_multiples;
+        self
+    }
+
+    /// returns the reply as an [InitInitServerPacket]
+    pub fn to_packet(&self) -> Result<InitInitServerPacket, InitReplyError> {
+        if !(0..=MAX_CHALLENGE).contains(&self.challenge) {
+            return Err(InitReplyError::InvalidChallenge(self.challenge));
+        }
+
+        if !(0..SHORT_MAX).contains(&self.player_id) {
+            return Err(InitReplyError::InvalidPlayerId(self.player_id));
+        }
+
+        if !(0..=MAX_INIT_SEQUENCE_START).contains(&self.sequence_start) {
+            return Err(InitReplyError::InvalidSequenceStart(self.sequence_start));
+        }
+
+        let swap_multiples =
+            SwapMultiples::new(self.swap_multiples.server, self.swap_multiples.client)?;
+
+        let [seq1, seq2] = get_init_sequence_bytes(self.sequence_start);
+
+        Ok(InitInitServerPacket {
+            reply_code: InitReply::OK,
+            reply_code_data: Some(InitInitServerPacketReplyCodeData::OK(
+                InitInitServerPacketReplyCodeDataOk {
+                    seq1: seq1 as u8,
+                    seq2: seq2 as u8,
+                    server_encryption_multiple: swap_multiples.server,
+                    client_encryption_multiple: swap_multiples.client,
+                    player_id: self.player_id,
+                    challenge_response: server_verification_hash(self.challenge),
+                },
+            )),
+        })
+    }
+
+    /// returns the reply as a packet ready to send, with its length prefix
+    ///
+    /// INIT_INIT packets are never encrypted
+    pub fn build(&self) -> Result<Bytes, InitReplyError> {
+        let packet = self.to_packet()?;
+        Ok(PacketBuilder::new(PacketFamily::Init, PacketAction::Init)
+            .add_struct(&packet)
+            .build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InitReplyBuilder, InitReplyError, MAX_INIT_SEQUENCE_START};
+    use crate::{
+        data::{EoReader, EoSerialize, SHORT_MAX},
+        encrypt::{server_verification_hash, SwapMultipleError, SwapMultiples, MAX_CHALLENGE},
+        packet::get_init_sequence_start,
+        protocol::net::server::{InitInitServerPacket, InitInitServerPacketReplyCodeData},
+    };
+
+    #[test]
+    fn layout() {
+        let packet = InitReplyBuilder::new(MAX_CHALLENGE, SHORT_MAX - 1)
+            .sequence_start(MAX_INIT_SEQUENCE_START)
+            .swap_multiples(SwapMultiples::new(12, 7).unwrap())
+            .build()
+            .unwrap();
+
+        // length, action and family
+        assert_eq!(packet[2..4], [255, 255]);
+
+        let reader = EoReader::new(packet.slice(4..));
+        let reply = InitInitServerPacket::deserialize(&reader).unwrap();
+        assert_eq!(reader.remaining(), 0);
+
+        match reply.reply_code_data {
+            Some(InitInitServerPacketReplyCodeData::OK(ok)) => {
+                assert_eq!(
+                    get_init_sequence_start(ok.seq1 as i32, ok.seq2 as i32),
+                    MAX_INIT_SEQUENCE_START
+                );
+                assert_eq!(ok.server_encryption_multiple, 12);
+                assert_eq!(ok.client_encryption_multiple, 7);
+                assert_eq!(ok.player_id, SHORT_MAX - 1);
+                assert_eq!(
+                    ok.challenge_response,
+                    server_verification_hash(MAX_CHALLENGE)
+                );
+            }
+            data => panic!("unexpected reply data {:?}", data),
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            InitReplyBuilder::new(-1, 1).build(),
+            Err(InitReplyError::InvalidChallenge(-1))
+        );
+        assert_eq!(
+            InitReplyBuilder::new(1, SHORT_MAX).build(),
+            Err(InitReplyError::InvalidPlayerId(SHORT_MAX))
+        );
+        assert_eq!(
+            InitReplyBuilder::new(1, 1)
+                .sequence_start(MAX_INIT_SEQUENCE_START + 1)
+                .build(),
+            Err(InitReplyError::InvalidSequenceStart(
+                MAX_INIT_SEQUENCE_START + 1
+            ))
+        );
+        assert_eq!(
+            InitReplyBuilder::new(1, 1)
+                .swap_multiples(SwapMultiples {
+                    server: 5,
+                    client: 6
+                })
+                .build(),
+            Err(InitReplyError::SwapMultipleError(
+                SwapMultipleError::InvalidSwapMultiple(5)
+            ))
+        );
+    }
+}
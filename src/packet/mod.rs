@@ -1,5 +1,40 @@
 mod sequencer;
 pub use sequencer::{
     generate_sequence_start, get_init_sequence_bytes, get_init_sequence_start,
-    get_ping_sequence_bytes, get_ping_sequence_start, Sequencer,
+    get_ping_sequence_bytes, get_ping_sequence_start, SequenceCheck, SequenceMismatch,
+    SequenceReset, Sequencer, SequencerState,
 };
+mod packet_length;
+pub use packet_length::{
+    decode_packet_length, encode_packet_length, PacketLengthError, MAX_PACKET_SIZE,
+};
+mod frame_decoder;
+pub use frame_decoder::FrameDecoder;
+mod codec;
+pub use codec::Codec;
+mod packet_builder;
+pub use packet_builder::{PacketBuilder, PacketBuilderError};
+mod init_reply_builder;
+pub use init_reply_builder::{InitReplyBuilder, InitReplyError, MAX_INIT_SEQUENCE_START};
+#[cfg(target_has_atomic = "64")]
+mod shared_sequencer;
+#[cfg(target_has_atomic = "64")]
+pub use shared_sequencer::SharedSequencer;
+mod replay_guard;
+pub use replay_guard::{ReplayGuard, MAX_REPLAY_WINDOW};
+mod rate_limiter;
+pub use rate_limiter::{RateLimit, RateLimiter, DEFAULT_RATE_LIMITS};
+mod packet_size;
+pub use packet_size::{PacketSize, PacketSizeError};
+mod connection;
+pub use connection::{Connection, ConnectionError, ConnectionEvent, SessionState};
+mod client_session;
+pub use client_session::{ClientSession, ClientSessionError};
+mod raw_packet;
+pub use raw_packet::RawPacket;
+mod packet_registry;
+pub use packet_registry::{PacketParser, PacketRegistry};
+mod reliable_queue;
+pub use reliable_queue::{ReliableEvent, ReliableQueue};
+mod script_api;
+pub use script_api::{ScriptApi, ScriptPacket};
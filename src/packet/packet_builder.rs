@@ -0,0 +1,292 @@
+use bytes::Bytes;
+use thiserror::Error;
+
+use crate::{
+    data::{EoSerialize, EoSerializeError, EoWriter, EoWriterError, StringSanitizationPolicy},
+    encrypt::encrypt_packet,
+    protocol::net::{PacketAction, PacketFamily},
+};
+
+use super::{encode_packet_length, PacketLengthError, Sequencer};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PacketBuilderError {
+    #[error("{0}")]
+    SerializeError(EoSerializeError),
+    #[error("{0}")]
+    LengthError(PacketLengthError),
+}
+
+impl From<EoSerializeError> for PacketBuilderError {
+    fn from(e: EoSerializeError) -> Self {
+        Self::SerializeError(e)
+    }
+}
+
+impl From<EoWriterError> for PacketBuilderError {
+    fn from(e: EoWriterError) -> Self {
+        Self::SerializeError(EoSerializeError::WriteError(e))
+    }
+}
+
+impl From<PacketLengthError> for PacketBuilderError {
+    fn from(e: PacketLengthError) -> Self {
+        Self::LengthError(e)
+    }
+}
+
+#[derive(Debug)]
+/// Builds a ready-to-send packet without defining a struct for it
+///
+/// The first error from any of the `add_` methods is kept and returned by
+/// [build](PacketBuilder::build), every call after it is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     packet::PacketBuilder,
+///     protocol::net::{PacketAction, PacketFamily},
+/// };
+///
+/// let packet = PacketBuilder::new(PacketFamily::Talk, PacketAction::Player)
+///     .add_short(1)
+///     .add_string("hello")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(&packet[..], [10, 254, 8, 18, 2, 254, 104, 101, 108, 108, 111]);
+/// ```
+pub struct PacketBuilder {
+    family: PacketFamily,
+    action: PacketAction,
+    sequence: Option<i32>,
+    swap_multiple: Option<u8>,
+    writer: EoWriter,
+    error: Option<PacketBuilderError>,
+}
+
+impl PacketBuilder {
+    /// creates a new [PacketBuilder] for the specified family and action
+    pub fn new(family: PacketFamily, action: PacketAction) -> Self {
+        Self {
+            family,
+            action,
+            sequence: None,
+            swap_multiple: None,
+            writer: EoWriter::new(),
+            error: None,
+        }
+    }
+
+    /// writes a sequence value after the packet header
+    ///
+    /// only client packets are sequenced, see [Sequencer](super::Sequencer)
+    pub fn sequence(mut self, sequence: i32) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// encrypts the packet with the specified swap multiple when it is built
+    pub fn encrypt(mut self, swap_multiple: u8) -> Self {
+        self.swap_multiple = Some(swap_multiple);
+        self
+    }
+
+    /// adds a raw byte to the packet
+    pub fn add_byte(mut self, byte: u8) -> Self {
+        self.writer.add_byte(byte);
+        self
+    }
+
+    /// adds an array of raw bytes to the packet
+    pub fn add_bytes(mut self, bytes: &[u8]) -> Self {
+        self.writer.add_bytes(bytes);
+        self
+    }
+
+    /// adds an encoded 1-byte integer to the packet
+    pub fn add_char(self, char: i32) -> Self {
+        self.try_write(|writer| writer.add_char(char))
+    }
+
+    /// adds an encoded 2-byte integer to the packet
+    pub fn add_short(self, short: i32) -> Self {
+        self.try_write(|writer| writer.add_short(short))
+    }
+
+    /// adds an encoded 3-byte integer to the packet
+    pub fn add_three(self, three: i32) -> Self {
+        self.try_write(|writer| writer.add_three(three))
+    }
+
+    /// adds an encoded 4-byte integer to the packet
+    pub fn add_int(self, int: i32) -> Self {
+        self.try_write(|writer| writer.add_int(int))
+    }
+
+    /// adds a string to the packet
+    pub fn add_string(self, string: &str) -> Self {
+        self.try_write(|writer| writer.add_string(string))
+    }
+
+    /// adds a sanitized string followed by a break to the packet
+    pub fn add_string_with_break(self, string: &str) -> Self {
+        self.try_write(|writer| writer.add_string_with_break(string))
+    }
+
+    /// adds a fixed length string to the packet
+    pub fn add_fixed_string(self, string: &str, length: usize, padded: bool) -> Self {
+        self.try_write(|writer| writer.add_fixed_string(string, length, padded))
+    }
+
+    /// encodes a string and adds it to the packet
+    pub fn add_encoded_string(self, string: &str) -> Self {
+        self.try_write(|writer| writer.add_encoded_string(string))
+    }
+
+    /// encodes a fixed length string and adds it to the packet
+    pub fn add_fixed_encoded_string(self, string: &str, length: usize) -> Self {
+        self.try_write(|writer| writer.add_fixed_encoded_string(string, length))
+    }
+
+    /// serializes a protocol struct into the packet
+    pub fn add_struct<T: EoSerialize>(mut self, data: &T) -> Self {
+        if self.error.is_none() {
+            if let Err(e) = data.serialize(&mut self.writer) {
+                self.error = Some(e.into());
+            }
+        }
+        self
+    }
+
+    /// sets the string sanitization mode for the rest of the packet
+    pub fn string_sanitization_mode(mut self, mode: bool) -> Self {
+        self.writer.set_string_sanitization_mode(mode);
+        self
+    }
+
+    /// sets the string sanitization policy for the rest of the packet
+    pub fn string_sanitization_policy(mut self, policy: StringSanitizationPolicy) -> Self {
+        self.writer.set_string_sanitization_policy(policy);
+        self
+    }
+
+    /// sets the strict int mode for the rest of the packet, see
+    /// [set_strict_int_mode](EoWriter::set_strict_int_mode)
+    pub fn strict_int_mode(mut self, mode: bool) -> Self {
+        self.writer.set_strict_int_mode(mode);
+        self
+    }
+
+    /// returns the header, sequence and data as an encrypted packet with its length prefix
+    pub fn build(self) -> Result<Bytes, PacketBuilderError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let data = self.writer.to_byte_array();
+
+        let mut writer = EoWriter::with_capacity(data.len() + 4);
+        writer.add_byte(self.action.into());
+        writer.add_byte(self.family.into());
+
+        match self.sequence {
+            Some(sequence) if Sequencer::sequence_is_short(sequence) => {
+                writer.add_short(sequence)?
+            }
+            Some(sequence) => writer.add_char(sequence)?,
+            None => {}
+        }
+
+        writer.add_bytes(&data);
+
+        let mut buf = writer.to_byte_array().to_vec();
+        if let Some(swap_multiple) = self.swap_multiple {
+            encrypt_packet(&mut buf, swap_multiple);
+        }
+
+        let mut packet = Vec::with_capacity(buf.len() + 2);
+        packet.extend_from_slice(&encode_packet_length(buf.len())?);
+        packet.extend_from_slice(&buf);
+        Ok(Bytes::from(packet))
+    }
+
+    fn try_write(mut self, write: impl FnOnce(&mut EoWriter) -> Result<(), EoWriterError>) -> Self {
+        if self.error.is_none() {
+            if let Err(e) = write(&mut self.writer) {
+                self.error = Some(e.into());
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PacketBuilder, PacketBuilderError};
+    use crate::{
+        data::{EoSerializeError, EoWriterError, CHAR_MAX},
+        encrypt::decrypt_packet,
+        packet::{encode_packet_length, PacketLengthError, MAX_PACKET_SIZE},
+        protocol::net::{PacketAction, PacketFamily},
+    };
+
+    #[test]
+    fn sequence() {
+        let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+            .sequence(5)
+            .build()
+            .unwrap();
+        assert_eq!(&packet[..], [4, 254, 8, 6, 6]);
+
+        let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+            .sequence(CHAR_MAX)
+            .build()
+            .unwrap();
+        assert_eq!(&packet[..], [5, 254, 8, 6, 1, 2]);
+    }
+
+    #[test]
+    fn encrypt() {
+        let packet = PacketBuilder::new(PacketFamily::Talk, PacketAction::Report)
+            .sequence(10)
+            .add_string("Hello, world!")
+            .encrypt(6)
+            .build()
+            .unwrap();
+
+        assert_eq!(packet[..2], encode_packet_length(packet.len() - 2).unwrap());
+
+        let mut buf = packet[2..].to_vec();
+        decrypt_packet(&mut buf, 6);
+        assert_eq!(&buf[..4], [21, 18, 11, b'H']);
+    }
+
+    #[test]
+    fn first_error_is_returned() {
+        let result = PacketBuilder::new(PacketFamily::Talk, PacketAction::Report)
+            .add_char(CHAR_MAX + 1)
+            .add_short(-1)
+            .build();
+        assert_eq!(
+            result,
+            Err(PacketBuilderError::SerializeError(
+                EoSerializeError::WriteError(EoWriterError::InvalidCharValue(CHAR_MAX + 1))
+            ))
+        );
+    }
+
+    #[test]
+    fn too_large() {
+        let result = PacketBuilder::new(PacketFamily::Talk, PacketAction::Report)
+            .add_bytes(&vec![0; MAX_PACKET_SIZE - 1])
+            .build();
+        assert_eq!(
+            result,
+            Err(PacketBuilderError::LengthError(
+                PacketLengthError::TooLarge(MAX_PACKET_SIZE + 1)
+            ))
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use thiserror::Error;
+
+use crate::data::{decode_number, encode_number, SHORT_MAX};
+
+/// The maximum size of a packet (not including the 2-byte length prefix)
+///
+/// Packet lengths are sent as an EO short, which can represent values up to `SHORT_MAX - 1`.
+pub const MAX_PACKET_SIZE: usize = SHORT_MAX as usize - 1;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PacketLengthError {
+    #[error("Packet length {0} must be less than or equal to {}", MAX_PACKET_SIZE)]
+    TooLarge(usize),
+}
+
+/// returns the 2-byte length prefix for a packet of `length` bytes
+///
+/// # Examples
+///
+/// ```
+/// use eolib::packet::{encode_packet_length, PacketLengthError, MAX_PACKET_SIZE};
+///
+/// assert_eq!(encode_packet_length(16), Ok([17, 254]));
+/// assert_eq!(
+///     encode_packet_length(MAX_PACKET_SIZE + 1),
+///     Err(PacketLengthError::TooLarge(MAX_PACKET_SIZE + 1))
+/// );
+/// ```
+pub fn encode_packet_length(length: usize) -> Result<[u8; 2], PacketLengthError> {
+    if length > MAX_PACKET_SIZE {
+        return Err(PacketLengthError::TooLarge(length));
+    }
+
+    let encoded = encode_number(length as i32).expect("length is in range of an EO short");
+    Ok([encoded[0], encoded[1]])
+}
+
+/// returns the packet length from a 2-byte length prefix
+///
+/// # Examples
+///
+/// ```
+/// use eolib::packet::decode_packet_length;
+///
+/// assert_eq!(decode_packet_length(&[17, 254]), 16);
+/// ```
+pub fn decode_packet_length(bytes: &[u8; 2]) -> usize {
+    decode_number(bytes) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_packet_length, encode_packet_length, MAX_PACKET_SIZE};
+
+    #[test]
+    fn round_trip() {
+        for length in [0, 1, 252, 253, 254, 1000, MAX_PACKET_SIZE] {
+            let encoded = encode_packet_length(length).unwrap();
+            assert_eq!(decode_packet_length(&encoded), length);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    data::{EoReader, EoReaderError},
+    protocol::net::{PacketAction, PacketFamily},
+};
+
+use super::RawPacket;
+
+/// Reads the data of one kind of packet into a `T`
+pub type PacketParser<T> = fn(&EoReader) -> Result<T, EoReaderError>;
+
+/// Parsers for packets, looked up by family and action at runtime
+///
+/// Forks with extra packets can register their own family and action ids here, next to the
+/// generated packets they also handle, without adding them to the protocol XML. `T` is
+/// usually an enum with a variant for each packet.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     data::{EoReader, EoReaderError, EoSerialize},
+///     packet::{PacketBuilder, PacketRegistry, RawPacket},
+///     protocol::{
+///         net::{client::WalkPlayerClientPacket, PacketAction, PacketFamily},
+///         Coords,
+///     },
+/// };
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Packet {
+///     Walk(WalkPlayerClientPacket),
+///     Teleport(Coords),
+/// }
+///
+/// fn teleport(reader: &EoReader) -> Result<Packet, EoReaderError> {
+///     Ok(Packet::Teleport(Coords::deserialize(reader)?))
+/// }
+///
+/// let mut registry = PacketRegistry::new();
+/// registry.register(PacketFamily::Walk, PacketAction::Player, |reader| {
+///     Ok(Packet::Walk(WalkPlayerClientPacket::deserialize(reader)?))
+/// });
+/// registry.register_custom(230, 1, teleport);
+///
+/// let bytes = PacketBuilder::new(PacketFamily::from(230), PacketAction::from(1))
+///     .add_struct(&Coords { x: 4, y: 2 })
+///     .build()
+///     .unwrap();
+/// let packet = RawPacket::from_bytes(&bytes.slice(2..)).unwrap();
+///
+/// assert_eq!(
+///     registry.parse_raw(&packet).unwrap().unwrap(),
+///     Packet::Teleport(Coords { x: 4, y: 2 })
+/// );
+/// assert!(registry
+///     .parse(PacketFamily::Walk, PacketAction::Spec, &packet.reader())
+///     .is_none());
+/// ```
+pub struct PacketRegistry<T> {
+    parsers: HashMap<(PacketFamily, PacketAction), PacketParser<T>>,
+}
+
+impl<T> PacketRegistry<T> {
+    /// creates a new empty [PacketRegistry]
+    pub fn new() -> Self {
+        Self {
+            parsers: HashMap::new(),
+        }
+    }
+
+    /// registers `parser` for packets with `family` and `action`, returning the parser it
+    /// replaced
+    pub fn register(
+        &mut self,
+        family: PacketFamily,
+        action: PacketAction,
+        parser: PacketParser<T>,
+    ) -> Option<PacketParser<T>> {
+        self.parsers.insert((family, action), parser)
+    }
+
+    /// registers `parser` for packets with the numeric `family_id` and `action_id`, returning
+    /// the parser it replaced
+    ///
+    /// ids the protocol doesn't define become `Unrecognized` families and actions
+    pub fn register_custom(
+        &mut self,
+        family_id: u8,
+        action_id: u8,
+        parser: PacketParser<T>,
+    ) -> Option<PacketParser<T>> {
+        self.register(family_id.into(), action_id.into(), parser)
+    }
+
+    /// removes the parser for packets with `family` and `action`, returning it
+    pub fn unregister(
+        &mut self,
+        family: PacketFamily,
+        action: PacketAction,
+    ) -> Option<PacketParser<T>> {
+        self.parsers.remove(&(family, action))
+    }
+
+    /// returns true if a parser is registered for `family` and `action`
+    pub fn is_registered(&self, family: PacketFamily, action: PacketAction) -> bool {
+        self.parsers.contains_key(&(family, action))
+    }
+
+    /// reads a packet's data with the parser for `family` and `action`, or returns [None] if
+    /// there isn't one
+    pub fn parse(
+        &self,
+        family: PacketFamily,
+        action: PacketAction,
+        reader: &EoReader,
+    ) -> Option<Result<T, EoReaderError>> {
+        self.parsers
+            .get(&(family, action))
+            .map(|parser| parser(reader))
+    }
+
+    /// like [parse](PacketRegistry::parse) for a [RawPacket]
+    pub fn parse_raw(&self, packet: &RawPacket) -> Option<Result<T, EoReaderError>> {
+        self.parse(packet.family, packet.action, &packet.reader())
+    }
+}
+
+impl<T> fmt::Debug for PacketRegistry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.parsers.keys()).finish()
+    }
+}
+
+impl<T> Default for PacketRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PacketRegistry;
+    use crate::{
+        data::EoReader,
+        protocol::net::{PacketAction, PacketFamily},
+    };
+
+    #[test]
+    fn custom_ids_match_unrecognized_values() {
+        let mut registry = PacketRegistry::new();
+        assert!(registry
+            .register_custom(230, 1, |reader| Ok(reader.get_char()))
+            .is_none());
+        assert!(registry.is_registered(PacketFamily::Unrecognized(230), PacketAction::from(1)));
+
+        // registering the same ids again replaces the parser
+        assert!(registry
+            .register_custom(230, 1, |reader| Ok(reader.get_short()))
+            .is_some());
+
+        let reader = EoReader::from(vec![11, 2]);
+        let result = registry.parse(PacketFamily::from(230), PacketAction::from(1), &reader);
+        assert_eq!(result.unwrap().unwrap(), 263);
+
+        assert!(registry
+            .unregister(PacketFamily::from(230), PacketAction::from(1))
+            .is_some());
+        assert!(registry
+            .parse(PacketFamily::from(230), PacketAction::from(1), &reader)
+            .is_none());
+    }
+}
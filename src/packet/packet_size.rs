@@ -0,0 +1,117 @@
+use thiserror::Error;
+
+use crate::protocol::net::{PacketAction, PacketFamily};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PacketSizeError {
+    #[error("Unknown packet {0:?}_{1:?}")]
+    UnknownPacket(PacketFamily, PacketAction),
+    #[error("Packet length {0} must be at least {1}")]
+    TooShort(usize, usize),
+    #[error("Packet length {0} must be at most {1}")]
+    TooLong(usize, usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The smallest and largest size of a packet's data, not counting its family, action and
+/// sequence bytes
+///
+/// Generated for every packet, see `protocol::net::client::packet_size` and
+/// `protocol::net::server::packet_size`. Checking the size is much cheaper than deserializing,
+/// so servers can use it to drop forged packets early.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     packet::{PacketSize, PacketSizeError},
+///     protocol::net::{client, PacketAction, PacketFamily},
+/// };
+///
+/// let size = client::packet_size(PacketFamily::Walk, PacketAction::Player).unwrap();
+/// assert!(size.contains(size.min));
+///
+/// assert_eq!(
+///     client::validate_size(PacketFamily::Walk, PacketAction::Player, 1000),
+///     Err(PacketSizeError::TooLong(1000, size.max.unwrap()))
+/// );
+/// ```
+pub struct PacketSize {
+    /// the smallest size
+    pub min: usize,
+    /// the largest size, [None] if the packet has strings or arrays without a fixed length
+    pub max: Option<usize>,
+}
+
+impl PacketSize {
+    /// returns true if a packet's data can be `length` bytes long
+    pub fn contains(&self, length: usize) -> bool {
+        self.validate(length).is_ok()
+    }
+
+    /// returns an error if a packet's data can't be `length` bytes long
+    pub fn validate(&self, length: usize) -> Result<(), PacketSizeError> {
+        if length < self.min {
+            return Err(PacketSizeError::TooShort(length, self.min));
+        }
+
+        match self.max {
+            Some(max) if length > max => Err(PacketSizeError::TooLong(length, max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PacketSize, PacketSizeError};
+    use crate::{
+        data::EoSerialize,
+        protocol::net::{
+            client::{self, CharacterCreateClientPacket, TalkReportClientPacket},
+            server::{self, WelcomeReplyServerPacket},
+            PacketAction, PacketFamily,
+        },
+    };
+
+    fn assert_in_bounds<T: EoSerialize>(size: Option<PacketSize>, packet: &T) {
+        let length = packet.to_bytes().unwrap().len();
+        let size = size.unwrap();
+        assert!(size.contains(length), "{} not in {:?}", length, size);
+    }
+
+    #[test]
+    fn serialized_packets_are_in_bounds() {
+        let create = CharacterCreateClientPacket {
+            name: "vulture".to_owned(),
+            ..Default::default()
+        };
+        assert_in_bounds(
+            client::packet_size(PacketFamily::Character, PacketAction::Create),
+            &create,
+        );
+        assert_in_bounds(
+            client::packet_size(PacketFamily::Talk, PacketAction::Report),
+            &TalkReportClientPacket::default(),
+        );
+        assert_in_bounds(
+            server::packet_size(PacketFamily::Welcome, PacketAction::Reply),
+            &WelcomeReplyServerPacket::default(),
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let size = PacketSize { min: 2, max: None };
+        assert_eq!(size.validate(1), Err(PacketSizeError::TooShort(1, 2)));
+        assert!(size.contains(usize::MAX));
+
+        assert_eq!(
+            client::validate_size(PacketFamily::Welcome, PacketAction::Reply, 2),
+            Err(PacketSizeError::UnknownPacket(
+                PacketFamily::Welcome,
+                PacketAction::Reply
+            ))
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::protocol::net::{PacketAction, PacketFamily};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How often a packet may be received, see [RateLimiter]
+pub struct RateLimit {
+    /// how many packets may be received back to back
+    pub burst: u32,
+    /// how long it takes to allow one more packet, up to `burst`
+    pub interval: Duration,
+}
+
+impl RateLimit {
+    /// creates a new [RateLimit]
+    pub const fn new(burst: u32, interval: Duration) -> Self {
+        Self { burst, interval }
+    }
+}
+
+/// The limits set by [RateLimiter::with_default_limits]
+///
+/// Walking and attacking are limited to how often the official client can send them. Every
+/// limit has a burst of two so a packet delayed by the network isn't counted against the one
+/// after it.
+// Walk and attack intervals are the packet queue delays EOSERV registers for the Walk
+// (0.46s) and Attack_Use (0.58s) handlers, which match how long a step and a swing take to
+// animate in the official client. Facing, sitting and emotes aren't animated for long, so
+// they get short intervals that leave room for normal play while stopping floods.
+pub const DEFAULT_RATE_LIMITS: [(PacketFamily, PacketAction, RateLimit); 8] = [
+    (PacketFamily::Walk, PacketAction::Player, WALK_LIMIT),
+    (PacketFamily::Walk, PacketAction::Spec, WALK_LIMIT),
+    (PacketFamily::Walk, PacketAction::Admin, WALK_LIMIT),
+    (
+        PacketFamily::Attack,
+        PacketAction::Use,
+        RateLimit::new(2, Duration::from_millis(580)),
+    ),
+    (
+        PacketFamily::Face,
+        PacketAction::Player,
+        RateLimit::new(2, Duration::from_millis(100)),
+    ),
+    (PacketFamily::Sit, PacketAction::Request, SIT_LIMIT),
+    (PacketFamily::Chair, PacketAction::Request, SIT_LIMIT),
+    (
+        PacketFamily::Emote,
+        PacketAction::Report,
+        RateLimit::new(2, Duration::from_millis(500)),
+    ),
+];
+
+const WALK_LIMIT: RateLimit = RateLimit::new(2, Duration::from_millis(460));
+const SIT_LIMIT: RateLimit = RateLimit::new(2, Duration::from_millis(500));
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: u32,
+    updated: Instant,
+}
+
+#[derive(Debug, Default)]
+/// Token buckets for the packets received on a connection, keyed by family and action
+///
+/// Each packet with a [RateLimit] starts with `burst` tokens and regains one every
+/// `interval`. Receiving the packet uses a token, and a packet with no tokens left should be
+/// dropped. Packets without a limit are always allowed.
+///
+/// [RateLimiter::with_default_limits] starts with [DEFAULT_RATE_LIMITS], which can be
+/// changed or removed packet by packet like any other limit.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use eolib::{
+///     packet::{RateLimit, RateLimiter},
+///     protocol::net::{PacketAction, PacketFamily},
+/// };
+///
+/// let mut limiter = RateLimiter::new();
+/// limiter.set_limit(
+///     PacketFamily::Talk,
+///     PacketAction::Report,
+///     RateLimit::new(2, Duration::from_secs(1)),
+/// );
+///
+/// let now = Instant::now();
+/// assert!(limiter.check(PacketFamily::Talk, PacketAction::Report, now));
+/// assert!(limiter.check(PacketFamily::Talk, PacketAction::Report, now));
+/// assert!(!limiter.check(PacketFamily::Talk, PacketAction::Report, now));
+///
+/// let later = now + Duration::from_secs(1);
+/// assert!(limiter.check(PacketFamily::Talk, PacketAction::Report, later));
+/// ```
+pub struct RateLimiter {
+    limits: HashMap<(PacketFamily, PacketAction), RateLimit>,
+    default_limit: Option<RateLimit>,
+    buckets: HashMap<(PacketFamily, PacketAction), Bucket>,
+}
+
+impl RateLimiter {
+    /// creates a new [RateLimiter] with no limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// creates a new [RateLimiter] with [DEFAULT_RATE_LIMITS]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use eolib::{
+    ///     packet::{RateLimit, RateLimiter},
+    ///     protocol::net::{PacketAction, PacketFamily},
+    /// };
+    ///
+    /// let mut limiter = RateLimiter::with_default_limits();
+    /// let now = Instant::now();
+    /// assert!(limiter.check(PacketFamily::Attack, PacketAction::Use, now));
+    /// assert!(limiter.check(PacketFamily::Attack, PacketAction::Use, now));
+    /// assert!(!limiter.check(PacketFamily::Attack, PacketAction::Use, now));
+    ///
+    /// // a server with faster attacks can override the default
+    /// limiter.set_limit(
+    ///     PacketFamily::Attack,
+    ///     PacketAction::Use,
+    ///     RateLimit::new(4, Duration::from_millis(250)),
+    /// );
+    /// assert!(limiter.check(PacketFamily::Attack, PacketAction::Use, now));
+    /// ```
+    pub fn with_default_limits() -> Self {
+        let mut limiter = Self::new();
+        for (family, action, limit) in DEFAULT_RATE_LIMITS {
+            limiter.set_limit(family, action, limit);
+        }
+        limiter
+    }
+
+    /// sets the limit for a packet, refilling its tokens
+    pub fn set_limit(&mut self, family: PacketFamily, action: PacketAction, limit: RateLimit) {
+        self.limits.insert((family, action), limit);
+        self.buckets.remove(&(family, action));
+    }
+
+    /// removes the limit for a packet, so it falls back to the default limit
+    pub fn remove_limit(&mut self, family: PacketFamily, action: PacketAction) {
+        self.limits.remove(&(family, action));
+        self.buckets.remove(&(family, action));
+    }
+
+    /// sets the limit for packets without their own, or [None] to allow them
+    pub fn set_default_limit(&mut self, limit: Option<RateLimit>) {
+        self.default_limit = limit;
+        self.buckets
+            .retain(|packet, _| self.limits.contains_key(packet));
+    }
+
+    /// returns the limit for a packet
+    pub fn limit(&self, family: PacketFamily, action: PacketAction) -> Option<RateLimit> {
+        self.limits
+            .get(&(family, action))
+            .copied()
+            .or(self.default_limit)
+    }
+
+    /// records a packet received at `now`
+    ///
+    /// returns false if the packet has no tokens left and should be dropped
+    pub fn check(&mut self, family: PacketFamily, action: PacketAction, now: Instant) -> bool {
+        let limit = match self.limit(family, action) {
+            Some(limit) => limit,
+            None => return true,
+        };
+
+        let bucket = self.buckets.entry((family, action)).or_insert(Bucket {
+            tokens: limit.burst,
+            updated: now,
+        });
+
+        if limit.interval.is_zero() {
+            return true;
+        }
+
+        // keep the time towards the next token when refilling
+        let elapsed = now.saturating_duration_since(bucket.updated);
+        let regained = elapsed.as_nanos() / limit.interval.as_nanos();
+        if regained > 0 {
+            let missing = limit.burst.saturating_sub(bucket.tokens);
+            if regained >= missing as u128 {
+                bucket.tokens = limit.burst;
+                bucket.updated = now;
+            } else {
+                bucket.tokens += regained as u32;
+                bucket.updated += limit.interval * regained as u32;
+            }
+        }
+
+        if bucket.tokens == 0 {
+            return false;
+        }
+
+        bucket.tokens -= 1;
+        true
+    }
+
+    /// forgets every received packet, refilling all tokens
+    pub fn reset(&mut self) {
+        self.buckets.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{RateLimit, RateLimiter, DEFAULT_RATE_LIMITS};
+    use crate::protocol::net::{PacketAction, PacketFamily};
+
+    #[test]
+    fn refills_one_token_per_interval() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_limit(
+            PacketFamily::Walk,
+            PacketAction::Player,
+            RateLimit::new(1, Duration::from_millis(400)),
+        );
+
+        let start = Instant::now();
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, start));
+        for millis in [100, 300, 399] {
+            let now = start + Duration::from_millis(millis);
+            assert!(!limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+        }
+
+        let now = start + Duration::from_millis(400);
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+
+        // a long pause only refills up to the burst
+        let now = start + Duration::from_secs(10);
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+        assert!(!limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+    }
+
+    #[test]
+    fn keeps_partial_intervals() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_default_limit(Some(RateLimit::new(2, Duration::from_secs(1))));
+
+        let start = Instant::now();
+        assert!(limiter.check(PacketFamily::Face, PacketAction::Player, start));
+        assert!(limiter.check(PacketFamily::Face, PacketAction::Player, start));
+
+        // one token back at 1s, the next is due at 2s not 2.5s
+        let now = start + Duration::from_millis(1_500);
+        assert!(limiter.check(PacketFamily::Face, PacketAction::Player, now));
+        let now = start + Duration::from_secs(2);
+        assert!(limiter.check(PacketFamily::Face, PacketAction::Player, now));
+        assert!(!limiter.check(PacketFamily::Face, PacketAction::Player, now));
+    }
+
+    #[test]
+    fn limits_are_per_packet() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_default_limit(Some(RateLimit::new(1, Duration::from_secs(1))));
+        limiter.set_limit(
+            PacketFamily::Talk,
+            PacketAction::Report,
+            RateLimit::new(0, Duration::from_secs(1)),
+        );
+
+        let now = Instant::now();
+        assert!(!limiter.check(PacketFamily::Talk, PacketAction::Report, now));
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+        assert!(limiter.check(PacketFamily::Face, PacketAction::Player, now));
+        assert!(!limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+
+        limiter.set_default_limit(None);
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+
+        limiter.remove_limit(PacketFamily::Talk, PacketAction::Report);
+        assert!(limiter.check(PacketFamily::Talk, PacketAction::Report, now));
+    }
+
+    #[test]
+    fn default_limits() {
+        let mut limiter = RateLimiter::with_default_limits();
+        for (family, action, limit) in DEFAULT_RATE_LIMITS {
+            assert_eq!(limiter.limit(family, action), Some(limit));
+        }
+        assert_eq!(
+            limiter.limit(PacketFamily::Talk, PacketAction::Report),
+            None
+        );
+
+        // two steps can arrive together, a third has to wait for the walk interval
+        let start = Instant::now();
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, start));
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, start));
+        let now = start + Duration::from_millis(459);
+        assert!(!limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+        let now = start + Duration::from_millis(460);
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+
+        limiter.remove_limit(PacketFamily::Walk, PacketAction::Player);
+        assert!(limiter.check(PacketFamily::Walk, PacketAction::Player, now));
+    }
+}
@@ -0,0 +1,140 @@
+use bytes::Bytes;
+
+use crate::{
+    data::{EoReader, EoReaderError, EoSerialize, EoSerializeError, EoWriter},
+    protocol::net::{PacketAction, PacketFamily},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A packet kept as its family, action and the bytes after them, exactly as they were received
+///
+/// Proxies and capture tools can use it to forward packets that have no generated type, or
+/// that shouldn't be re-encoded, without losing anything. Families and actions this crate
+/// doesn't know are kept as `Unrecognized`, and the data of a client packet still starts with
+/// its sequence.
+///
+/// It (de)serializes the same bytes a [Codec](super::Codec) or
+/// [FrameDecoder](super::FrameDecoder) works with: the action, the family and the data,
+/// without the length prefix.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+/// use eolib::{
+///     data::EoSerialize,
+///     packet::RawPacket,
+///     protocol::net::{PacketAction, PacketFamily},
+/// };
+///
+/// let bytes = Bytes::from_static(&[21, 200, 1, 2, 255, 3]);
+/// let packet = RawPacket::from_bytes(&bytes).unwrap();
+/// assert_eq!(packet.family, PacketFamily::Unrecognized(200));
+/// assert_eq!(packet.action, PacketAction::Report);
+/// assert_eq!(packet.data[..], [1, 2, 255, 3]);
+///
+/// assert_eq!(packet.to_bytes().unwrap(), bytes);
+/// ```
+pub struct RawPacket {
+    pub family: PacketFamily,
+    pub action: PacketAction,
+    pub data: Bytes,
+}
+
+impl RawPacket {
+    /// creates a new [RawPacket]
+    pub fn new(family: PacketFamily, action: PacketAction, data: impl Into<Bytes>) -> Self {
+        Self {
+            family,
+            action,
+            data: data.into(),
+        }
+    }
+
+    /// returns a reader over the packet's data
+    pub fn reader(&self) -> EoReader {
+        EoReader::new(self.data.clone())
+    }
+}
+
+impl EoSerialize for RawPacket {
+    /// reads the action and family, then every remaining byte as the data
+    fn deserialize(reader: &EoReader) -> Result<Self, EoReaderError> {
+        reader.check_remaining(2)?;
+        let action = PacketAction::from(reader.get_byte());
+        let family = PacketFamily::from(reader.get_byte());
+        let data = reader.get_remaining_bytes();
+        Ok(Self {
+            family,
+            action,
+            data,
+        })
+    }
+
+    fn serialize(&self, writer: &mut EoWriter) -> Result<(), EoSerializeError> {
+        writer.add_byte(u8::from(self.action));
+        writer.add_byte(u8::from(self.family));
+        writer.add_bytes(&self.data);
+        Ok(())
+    }
+
+    fn encoded_size(&self) -> usize {
+        2 + self.data.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Bytes, BytesMut};
+
+    use super::RawPacket;
+    use crate::{
+        data::{EoReaderError, EoSerialize},
+        encrypt::SwapMultiples,
+        packet::{Codec, PacketBuilder},
+        protocol::net::{client::WalkPlayerClientPacket, PacketAction, PacketFamily},
+    };
+
+    #[test]
+    fn forwards_packets_unchanged() {
+        let multiples = SwapMultiples::new(6, 8).unwrap();
+        let mut codec = Codec::new();
+        codec.set_swap_multiples(multiples);
+
+        let walk = WalkPlayerClientPacket::default();
+        let packet = PacketBuilder::new(PacketFamily::Walk, PacketAction::Player)
+            .sequence(5)
+            .add_struct(&walk)
+            .encrypt(multiples.client)
+            .build()
+            .unwrap();
+
+        let mut buf = BytesMut::from(&packet[..]);
+        let raw = RawPacket::from_bytes(&codec.decode_packet(&mut buf).unwrap()).unwrap();
+        assert_eq!(
+            (raw.family, raw.action),
+            (PacketFamily::Walk, PacketAction::Player)
+        );
+
+        // the sequence is kept in the data
+        let reader = raw.reader();
+        assert_eq!(reader.get_char(), 5);
+        assert_eq!(WalkPlayerClientPacket::deserialize(&reader).unwrap(), walk);
+
+        let bytes = raw.to_bytes().unwrap();
+        assert_eq!(raw.encoded_size(), bytes.len());
+        assert_eq!(RawPacket::from_bytes(&bytes).unwrap(), raw);
+    }
+
+    #[test]
+    fn requires_family_and_action() {
+        assert!(matches!(
+            RawPacket::from_bytes(&Bytes::from_static(&[1])),
+            Err(EoReaderError::UnexpectedEof(2, 1, _))
+        ));
+        assert_eq!(
+            RawPacket::from_bytes(&Bytes::from_static(&[1, 2])).unwrap(),
+            RawPacket::new(PacketFamily::from(2), PacketAction::from(1), Bytes::new())
+        );
+    }
+}
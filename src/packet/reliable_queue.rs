@@ -0,0 +1,206 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::protocol::net::{PacketAction, PacketFamily};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// What to do about a packet whose reply didn't arrive in time, see [ReliableQueue::poll]
+pub enum ReliableEvent<T> {
+    /// the packet should be sent again. it stays in the queue with a new deadline
+    Retry(T),
+    /// the packet was tried [max_attempts](ReliableQueue::max_attempts) times and removed
+    /// from the queue. the server should give up on the client, e.g. by disconnecting it
+    Escalate(T),
+}
+
+#[derive(Debug)]
+struct Pending<T> {
+    reply_family: PacketFamily,
+    reply_action: PacketAction,
+    packet: T,
+    attempts: u32,
+    deadline: Instant,
+}
+
+#[derive(Debug)]
+/// Server packets waiting for a particular reply from the client
+///
+/// EO has no acknowledgements, but some packets expect a reply: a warp request is answered
+/// with a warp accept, for example. The queue remembers each packet until the reply arrives
+/// and reports the ones that time out, so they can be sent again or the client given up on.
+///
+/// `T` is whatever is needed to send the packet again, usually its bytes. The queue doesn't
+/// send or read anything itself.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use eolib::{
+///     packet::{ReliableEvent, ReliableQueue},
+///     protocol::net::{PacketAction, PacketFamily},
+/// };
+///
+/// let mut queue = ReliableQueue::new(Duration::from_secs(5), 2);
+/// let now = Instant::now();
+/// queue.push(PacketFamily::Warp, PacketAction::Accept, "warp to 5", now);
+/// assert_eq!(queue.poll(now), None);
+///
+/// let later = now + Duration::from_secs(5);
+/// assert_eq!(queue.poll(later), Some(ReliableEvent::Retry("warp to 5")));
+///
+/// let much_later = later + Duration::from_secs(5);
+/// assert_eq!(queue.poll(much_later), Some(ReliableEvent::Escalate("warp to 5")));
+/// assert!(queue.is_empty());
+///
+/// queue.push(PacketFamily::Warp, PacketAction::Accept, "warp to 6", now);
+/// assert_eq!(
+///     queue.acknowledge(PacketFamily::Warp, PacketAction::Accept),
+///     Some("warp to 6")
+/// );
+/// ```
+pub struct ReliableQueue<T> {
+    timeout: Duration,
+    max_attempts: u32,
+    pending: VecDeque<Pending<T>>,
+}
+
+impl<T: Clone> ReliableQueue<T> {
+    /// creates a new [ReliableQueue] giving each packet `timeout` to be answered and sending it
+    /// at most `max_attempts` times
+    pub fn new(timeout: Duration, max_attempts: u32) -> Self {
+        Self {
+            timeout,
+            max_attempts,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// returns how long a packet has to be answered
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// returns how many times a packet is sent before it's escalated
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// records `packet` sent at `now`, waiting for a reply with `reply_family` and
+    /// `reply_action`
+    pub fn push(
+        &mut self,
+        reply_family: PacketFamily,
+        reply_action: PacketAction,
+        packet: T,
+        now: Instant,
+    ) {
+        self.pending.push_back(Pending {
+            reply_family,
+            reply_action,
+            packet,
+            attempts: 1,
+            deadline: now + self.timeout,
+        });
+    }
+
+    /// records a packet received from the client, removing and returning the oldest packet
+    /// that was waiting for it
+    pub fn acknowledge(&mut self, family: PacketFamily, action: PacketAction) -> Option<T> {
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| (pending.reply_family, pending.reply_action) == (family, action))?;
+        self.pending.remove(index).map(|pending| pending.packet)
+    }
+
+    /// returns the next packet whose reply is overdue at `now`, or [None] if there isn't one
+    ///
+    /// call it until it returns [None]
+    pub fn poll(&mut self, now: Instant) -> Option<ReliableEvent<T>> {
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| pending.deadline <= now)?;
+
+        if self.pending[index].attempts >= self.max_attempts {
+            let pending = self.pending.remove(index)?;
+            return Some(ReliableEvent::Escalate(pending.packet));
+        }
+
+        let pending = &mut self.pending[index];
+        pending.attempts += 1;
+        pending.deadline = now + self.timeout;
+        Some(ReliableEvent::Retry(pending.packet.clone()))
+    }
+
+    /// returns the earliest deadline in the queue, for scheduling the next [poll](ReliableQueue::poll)
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|pending| pending.deadline).min()
+    }
+
+    /// returns the number of packets waiting for a reply
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// returns true if no packets are waiting for a reply
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// forgets every packet, e.g. when the client disconnects
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{ReliableEvent, ReliableQueue};
+    use crate::protocol::net::{PacketAction, PacketFamily};
+
+    #[test]
+    fn replies_are_matched_oldest_first() {
+        let mut queue = ReliableQueue::new(Duration::from_secs(1), 3);
+        let now = Instant::now();
+        queue.push(PacketFamily::Warp, PacketAction::Accept, 1, now);
+        queue.push(PacketFamily::Trade, PacketAction::Agree, 2, now);
+        queue.push(PacketFamily::Warp, PacketAction::Accept, 3, now);
+
+        assert_eq!(
+            queue.acknowledge(PacketFamily::Warp, PacketAction::Accept),
+            Some(1)
+        );
+        assert_eq!(
+            queue.acknowledge(PacketFamily::Walk, PacketAction::Player),
+            None
+        );
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn retries_until_max_attempts() {
+        let timeout = Duration::from_secs(1);
+        let mut queue = ReliableQueue::new(timeout, 3);
+        let start = Instant::now();
+        queue.push(PacketFamily::Warp, PacketAction::Accept, 1, start);
+        assert_eq!(queue.next_deadline(), Some(start + timeout));
+
+        let mut now = start;
+        for _ in 0..2 {
+            now += timeout;
+            assert_eq!(queue.poll(now), Some(ReliableEvent::Retry(1)));
+            assert_eq!(queue.poll(now), None);
+        }
+
+        now += timeout;
+        assert_eq!(queue.poll(now), Some(ReliableEvent::Escalate(1)));
+        assert_eq!(queue.next_deadline(), None);
+    }
+}
@@ -0,0 +1,141 @@
+use std::{cmp, collections::VecDeque};
+
+use crate::protocol::net::{PacketAction, PacketFamily};
+
+/// The largest window a [ReplayGuard] can use
+///
+/// the sequence counter wraps every 10 packets, so a legitimate client repeats a sequence
+/// value on its 10th packet after
+pub const MAX_REPLAY_WINDOW: usize = 9;
+
+#[derive(Debug)]
+/// Tracks the sequence, family and action of the last few packets received on a connection
+///
+/// A [Sequencer](super::Sequencer) only checks that the sequence moves forward modulo 10,
+/// so with a drift tolerance a duplicated packet can still be accepted. A client can't send
+/// the same sequence for the same family and action twice within [MAX_REPLAY_WINDOW] packets,
+/// so a repeat inside the window is a replay.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     packet::ReplayGuard,
+///     protocol::net::{PacketAction, PacketFamily},
+/// };
+///
+/// let mut guard = ReplayGuard::new(3);
+/// assert!(guard.insert(21, PacketFamily::Walk, PacketAction::Player));
+/// assert!(guard.insert(22, PacketFamily::Walk, PacketAction::Player));
+/// assert!(!guard.insert(21, PacketFamily::Walk, PacketAction::Player));
+/// assert!(guard.insert(21, PacketFamily::Face, PacketAction::Player));
+/// ```
+pub struct ReplayGuard {
+    window: usize,
+    seen: VecDeque<(i32, PacketFamily, PacketAction)>,
+}
+
+impl ReplayGuard {
+    /// creates a new [ReplayGuard] remembering the last `window` packets
+    ///
+    /// the window is capped at [MAX_REPLAY_WINDOW]
+    pub fn new(window: usize) -> Self {
+        let window = cmp::min(window, MAX_REPLAY_WINDOW);
+        Self {
+            window,
+            seen: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// gets how many packets are remembered
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// sets how many packets are remembered, forgetting the oldest if the window shrinks
+    ///
+    /// the window is capped at [MAX_REPLAY_WINDOW]
+    pub fn set_window(&mut self, window: usize) {
+        self.window = cmp::min(window, MAX_REPLAY_WINDOW);
+        while self.seen.len() > self.window {
+            self.seen.pop_front();
+        }
+    }
+
+    /// records a received packet
+    ///
+    /// returns false if the same sequence, family and action were received within the window
+    pub fn insert(&mut self, sequence: i32, family: PacketFamily, action: PacketAction) -> bool {
+        let packet = (sequence, family, action);
+        if self.seen.contains(&packet) {
+            return false;
+        }
+
+        if self.window == 0 {
+            return true;
+        }
+
+        if self.seen.len() == self.window {
+            self.seen.pop_front();
+        }
+        self.seen.push_back(packet);
+        true
+    }
+
+    /// forgets every received packet
+    ///
+    /// should be called when the sequence start is reset
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+}
+
+impl Default for ReplayGuard {
+    fn default() -> Self {
+        Self::new(MAX_REPLAY_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplayGuard, MAX_REPLAY_WINDOW};
+    use crate::{
+        packet::Sequencer,
+        protocol::net::{PacketAction, PacketFamily},
+    };
+
+    #[test]
+    fn sequence_wrap_is_not_a_replay() {
+        let mut client = Sequencer::new(20);
+        let mut guard = ReplayGuard::default();
+        for _ in 0..30 {
+            let sequence = client.next_sequence();
+            assert!(guard.insert(sequence, PacketFamily::Walk, PacketAction::Player));
+        }
+    }
+
+    #[test]
+    fn oldest_packet_is_forgotten() {
+        let mut guard = ReplayGuard::new(2);
+        assert!(guard.insert(1, PacketFamily::Talk, PacketAction::Report));
+        assert!(guard.insert(2, PacketFamily::Talk, PacketAction::Report));
+        assert!(guard.insert(3, PacketFamily::Talk, PacketAction::Report));
+        assert!(guard.insert(1, PacketFamily::Talk, PacketAction::Report));
+        assert!(!guard.insert(3, PacketFamily::Talk, PacketAction::Report));
+
+        guard.set_window(1);
+        assert!(guard.insert(3, PacketFamily::Talk, PacketAction::Report));
+
+        guard.clear();
+        assert!(guard.insert(1, PacketFamily::Talk, PacketAction::Report));
+    }
+
+    #[test]
+    fn window_is_capped() {
+        assert_eq!(ReplayGuard::new(100).window(), MAX_REPLAY_WINDOW);
+
+        let mut guard = ReplayGuard::new(0);
+        assert!(guard.insert(1, PacketFamily::Talk, PacketAction::Report));
+        assert!(guard.insert(1, PacketFamily::Talk, PacketAction::Report));
+    }
+}
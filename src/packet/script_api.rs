@@ -0,0 +1,235 @@
+use crate::data::{EoReader, EoReaderError, EoWriter, EoWriterError};
+
+/// A small, object safe interface for building and reading packets from scripts
+///
+/// Every method takes and returns plain numbers, strings and byte vectors, so it can be bound
+/// to a Lua or Rhai engine as it is, or used as a `Box<dyn ScriptApi>`. Families and actions
+/// are their numeric ids, and numbers are read and written as EO numbers. The methods are
+/// kept stable; new ones only get added with a default implementation.
+///
+/// [ScriptPacket] implements it for a single packet.
+pub trait ScriptApi {
+    /// returns the family id of the packet
+    fn family(&self) -> u8;
+    /// returns the action id of the packet
+    fn action(&self) -> u8;
+
+    /// adds a raw byte to the packet
+    fn add_byte(&mut self, byte: u8);
+    /// adds a char to the packet
+    fn add_char(&mut self, char: i32) -> Result<(), EoWriterError>;
+    /// adds a short to the packet
+    fn add_short(&mut self, short: i32) -> Result<(), EoWriterError>;
+    /// adds a three to the packet
+    fn add_three(&mut self, three: i32) -> Result<(), EoWriterError>;
+    /// adds an int to the packet
+    fn add_int(&mut self, int: i32) -> Result<(), EoWriterError>;
+    /// adds a string to the packet
+    fn add_string(&mut self, string: &str) -> Result<(), EoWriterError>;
+    /// adds a `0xFF` break byte to the packet
+    fn add_break(&mut self);
+
+    /// returns the next raw byte of the received data
+    fn get_byte(&self) -> u8;
+    /// returns the next char of the received data
+    fn get_char(&self) -> i32;
+    /// returns the next short of the received data
+    fn get_short(&self) -> i32;
+    /// returns the next three of the received data
+    fn get_three(&self) -> i32;
+    /// returns the next int of the received data
+    fn get_int(&self) -> i32;
+    /// returns a string of `length` bytes from the received data
+    fn get_fixed_string(&self, length: usize) -> String;
+    /// returns the rest of the received data, or of the chunk, as a string
+    fn get_string(&self) -> String;
+    /// turns chunked reading of the received data on or off
+    fn set_chunked_reading_mode(&self, enabled: bool);
+    /// moves to the next chunk of the received data
+    fn next_chunk(&self) -> Result<(), EoReaderError>;
+    /// returns the number of bytes left in the received data, or in the chunk
+    fn remaining(&self) -> usize;
+
+    /// returns the action, family and added data, ready for
+    /// [Codec::encode_packet](super::Codec::encode_packet)
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+#[derive(Debug)]
+/// A packet being built or read by a script, see [ScriptApi]
+///
+/// # Examples
+///
+/// ```
+/// use eolib::packet::{ScriptApi, ScriptPacket};
+///
+/// let mut packet: Box<dyn ScriptApi> = Box::new(ScriptPacket::new(21, 4));
+/// packet.add_short(1).unwrap();
+/// packet.add_string("hello").unwrap();
+/// let bytes = packet.to_bytes();
+///
+/// let received = ScriptPacket::received(&bytes).unwrap();
+/// assert_eq!((received.family(), received.action()), (21, 4));
+/// assert_eq!(received.get_short(), 1);
+/// assert_eq!(received.get_string(), "hello");
+/// ```
+pub struct ScriptPacket {
+    family: u8,
+    action: u8,
+    reader: EoReader,
+    writer: EoWriter,
+}
+
+impl ScriptPacket {
+    /// creates an empty packet to add data to
+    pub fn new(family: u8, action: u8) -> Self {
+        Self {
+            family,
+            action,
+            reader: EoReader::from(Vec::new()),
+            writer: EoWriter::new(),
+        }
+    }
+
+    /// creates a packet to read from the action, family and data of a received packet
+    pub fn received(packet: &[u8]) -> Result<Self, EoReaderError> {
+        let reader = EoReader::from_slice(packet);
+        reader.check_remaining(2)?;
+        let action = reader.get_byte();
+        let family = reader.get_byte();
+        Ok(Self {
+            family,
+            action,
+            reader: reader.slice(2..),
+            writer: EoWriter::new(),
+        })
+    }
+}
+
+impl ScriptApi for ScriptPacket {
+    fn family(&self) -> u8 {
+        self.family
+    }
+
+    fn action(&self) -> u8 {
+        self.action
+    }
+
+    fn add_byte(&mut self, byte: u8) {
+        self.writer.add_byte(byte);
+    }
+
+    fn add_char(&mut self, char: i32) -> Result<(), EoWriterError> {
+        self.writer.add_char(char)
+    }
+
+    fn add_short(&mut self, short: i32) -> Result<(), EoWriterError> {
+        self.writer.add_short(short)
+    }
+
+    fn add_three(&mut self, three: i32) -> Result<(), EoWriterError> {
+        self.writer.add_three(three)
+    }
+
+    fn add_int(&mut self, int: i32) -> Result<(), EoWriterError> {
+        self.writer.add_int(int)
+    }
+
+    fn add_string(&mut self, string: &str) -> Result<(), EoWriterError> {
+        self.writer.add_string(string)
+    }
+
+    fn add_break(&mut self) {
+        self.writer.add_byte(0xFF);
+    }
+
+    fn get_byte(&self) -> u8 {
+        self.reader.get_byte()
+    }
+
+    fn get_char(&self) -> i32 {
+        self.reader.get_char()
+    }
+
+    fn get_short(&self) -> i32 {
+        self.reader.get_short()
+    }
+
+    fn get_three(&self) -> i32 {
+        self.reader.get_three()
+    }
+
+    fn get_int(&self) -> i32 {
+        self.reader.get_int()
+    }
+
+    fn get_fixed_string(&self, length: usize) -> String {
+        self.reader.get_fixed_string(length)
+    }
+
+    fn get_string(&self) -> String {
+        self.reader.get_string()
+    }
+
+    fn set_chunked_reading_mode(&self, enabled: bool) {
+        self.reader.set_chunked_reading_mode(enabled);
+    }
+
+    fn next_chunk(&self) -> Result<(), EoReaderError> {
+        self.reader.next_chunk()
+    }
+
+    fn remaining(&self) -> usize {
+        self.reader.remaining()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let data = self.writer.as_bytes();
+        let mut bytes = Vec::with_capacity(2 + data.len());
+        bytes.push(self.action);
+        bytes.push(self.family);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScriptApi, ScriptPacket};
+    use crate::{
+        data::EoReaderError,
+        packet::PacketBuilder,
+        protocol::net::{PacketAction, PacketFamily},
+    };
+
+    #[test]
+    fn matches_packet_builder() {
+        let mut packet = ScriptPacket::new(PacketFamily::Talk.into(), PacketAction::Player.into());
+        packet.add_short(1).unwrap();
+        packet.add_break();
+        packet.add_string("hello").unwrap();
+
+        let built = PacketBuilder::new(PacketFamily::Talk, PacketAction::Player)
+            .add_short(1)
+            .add_byte(0xFF)
+            .add_string("hello")
+            .build()
+            .unwrap();
+        assert_eq!(packet.to_bytes(), built[2..]);
+
+        let received = ScriptPacket::received(&built[2..]).unwrap();
+        received.set_chunked_reading_mode(true);
+        assert_eq!(received.get_short(), 1);
+        received.next_chunk().unwrap();
+        assert_eq!(received.get_string(), "hello");
+        assert_eq!(received.remaining(), 0);
+    }
+
+    #[test]
+    fn received_needs_family_and_action() {
+        assert!(matches!(
+            ScriptPacket::received(&[1]),
+            Err(EoReaderError::UnexpectedEof(2, 1, _))
+        ));
+    }
+}
@@ -1,6 +1,8 @@
-use std::cmp;
+use std::{cmp, ops::RangeInclusive};
 
 use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::data::CHAR_MAX;
 
@@ -19,12 +21,88 @@ use crate::data::CHAR_MAX;
 pub struct Sequencer {
     start: i32,
     counter: i32,
+    previous_start: Option<i32>,
+    last_reset: Option<SequenceReset>,
+    tolerance: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// The places in the original game protocol where the sequence start is reset
+pub enum SequenceReset {
+    /// Init_Init handshake
+    Init,
+    /// Account_Reply during account creation
+    Account,
+    /// Connection_Player server ping
+    Ping,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// A snapshot of a [Sequencer] that can be stored and restored with [Sequencer::from_state]
+pub struct SequencerState {
+    pub start: i32,
+    pub counter: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The result of [Sequencer::verify]
+pub enum SequenceCheck {
+    /// the sequence matched, or was within the drift tolerance
+    Ok,
+    /// the sequence is behind the expected value
+    TooLow {
+        expected: i32,
+        /// every value the current start can produce
+        window: RangeInclusive<i32>,
+    },
+    /// the sequence is ahead of the expected value
+    TooHigh {
+        expected: i32,
+        /// every value the current start can produce
+        window: RangeInclusive<i32>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The likely cause of a received sequence not matching, see [Sequencer::diagnose_mismatch]
+pub enum SequenceMismatch {
+    /// the sequence uses the current start but a different counter, usually a dropped or
+    /// duplicated packet
+    CounterDesync { expected: i32, received: i32 },
+    /// the sequence uses the start from before the last reset, so the other side never
+    /// applied it
+    ///
+    /// the reset is `None` if it was made with [Sequencer::set_start]
+    MissedReset(Option<SequenceReset>),
+    /// the sequence doesn't match the current or previous start
+    Unknown,
 }
 
 impl Sequencer {
     /// creates a new [Sequencer] with the specified starting value
     pub fn new(start: i32) -> Self {
-        Self { start, counter: 0 }
+        Self {
+            start,
+            counter: 0,
+            previous_start: None,
+            last_reset: None,
+            tolerance: 0,
+        }
+    }
+
+    /// creates a new [Sequencer] from a previously saved [SequencerState]
+    ///
+    /// the counter only runs from 0 to 9, so one outside that range is wrapped into it
+    pub fn from_state(state: SequencerState) -> Self {
+        Self {
+            start: state.start,
+            counter: state.counter.rem_euclid(10),
+            previous_start: None,
+            last_reset: None,
+            tolerance: 0,
+        }
     }
 
     /// returns the next sequence value
@@ -33,15 +111,158 @@ impl Sequencer {
         self.start + self.counter
     }
 
+    /// returns the next sequence value without advancing the sequencer
+    pub fn peek_sequence(&self) -> i32 {
+        self.start + (self.counter + 1) % 10
+    }
+
+    /// returns true if `sequence` is sent as a 2 byte short instead of a 1 byte char
+    ///
+    /// the width depends on the value itself, so a start just below
+    /// [CHAR_MAX] switches between the two as the counter wraps
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::packet::Sequencer;
+    ///
+    /// assert!(!Sequencer::sequence_is_short(252));
+    /// assert!(Sequencer::sequence_is_short(253));
+    /// ```
+    pub fn sequence_is_short(sequence: i32) -> bool {
+        sequence >= CHAR_MAX
+    }
+
     /// sets a new starting value for the sequencer
     pub fn set_start(&mut self, start: i32) {
+        self.previous_start = Some(self.start);
+        self.last_reset = None;
         self.start = start;
     }
 
+    /// sets a new starting value for the sequencer and records why it was reset
+    ///
+    /// the reason is reported by [diagnose_mismatch](Sequencer::diagnose_mismatch)
+    pub fn reset(&mut self, start: i32, reason: SequenceReset) {
+        self.set_start(start);
+        self.last_reset = Some(reason);
+    }
+
     /// gets the current starting value for the sequencer
     pub fn get_start(&self) -> i32 {
         self.start
     }
+
+    /// sets how many packets the other side may drift ahead or behind before
+    /// [verify](Sequencer::verify) rejects a sequence
+    ///
+    /// the counter wraps every 10 packets so the tolerance is capped at 4
+    pub fn set_tolerance(&mut self, tolerance: u8) {
+        self.tolerance = cmp::min(tolerance, 4) as i32;
+    }
+
+    /// advances the sequencer and checks a received sequence against it
+    ///
+    /// a sequence within the drift tolerance is accepted and the counter is moved to match it,
+    /// so a single dropped packet doesn't desync every packet after it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::packet::{SequenceCheck, Sequencer};
+    ///
+    /// let mut sequencer = Sequencer::new(20);
+    /// assert_eq!(sequencer.verify(21), SequenceCheck::Ok);
+    /// assert_eq!(
+    ///     sequencer.verify(24),
+    ///     SequenceCheck::TooHigh {
+    ///         expected: 22,
+    ///         window: 20..=29
+    ///     }
+    /// );
+    ///
+    /// sequencer.set_tolerance(2);
+    /// assert_eq!(sequencer.verify(25), SequenceCheck::Ok);
+    /// assert_eq!(sequencer.verify(26), SequenceCheck::Ok);
+    /// ```
+    pub fn verify(&mut self, received: i32) -> SequenceCheck {
+        let expected = self.next_sequence();
+        if received == expected {
+            return SequenceCheck::Ok;
+        }
+
+        let window = self.start..=self.start + 9;
+        let drift = if window.contains(&received) {
+            // signed distance between the counters, accounting for the wrap from 9 to 0
+            let drift = (received - expected).rem_euclid(10);
+            if drift > 5 {
+                drift - 10
+            } else {
+                drift
+            }
+        } else {
+            received - expected
+        };
+
+        if window.contains(&received) && drift.abs() <= self.tolerance {
+            self.counter = received - self.start;
+            SequenceCheck::Ok
+        } else if drift < 0 {
+            SequenceCheck::TooLow { expected, window }
+        } else {
+            SequenceCheck::TooHigh { expected, window }
+        }
+    }
+
+    /// returns the current state of the sequencer
+    pub fn state(&self) -> SequencerState {
+        SequencerState {
+            start: self.start,
+            counter: self.counter,
+        }
+    }
+
+    /// returns the likely cause of a received sequence not matching the last value returned
+    /// by [next_sequence](Sequencer::next_sequence), or `None` if it matches
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::packet::{SequenceMismatch, SequenceReset, Sequencer};
+    ///
+    /// let mut sequencer = Sequencer::new(20);
+    /// sequencer.reset(100, SequenceReset::Ping);
+    ///
+    /// // the client never received the ping and is still using the old start
+    /// let expected = sequencer.next_sequence();
+    /// assert_eq!(expected, 101);
+    /// assert_eq!(sequencer.diagnose_mismatch(expected), None);
+    /// assert_eq!(
+    ///     sequencer.diagnose_mismatch(21),
+    ///     Some(SequenceMismatch::MissedReset(Some(SequenceReset::Ping)))
+    /// );
+    /// ```
+    pub fn diagnose_mismatch(&self, received: i32) -> Option<SequenceMismatch> {
+        let expected = self.start + self.counter;
+        if received == expected {
+            return None;
+        }
+
+        let counter = received - self.start;
+        if (0..10).contains(&counter) {
+            return Some(SequenceMismatch::CounterDesync {
+                expected: self.counter,
+                received: counter,
+            });
+        }
+
+        match self.previous_start {
+            Some(previous_start) if (0..10).contains(&(received - previous_start)) => {
+                Some(SequenceMismatch::MissedReset(self.last_reset))
+            }
+            _ => Some(SequenceMismatch::Unknown),
+        }
+    }
 }
 
 /// returns a random sequence start value
@@ -87,3 +308,106 @@ pub fn get_ping_sequence_bytes(start: i32) -> [i32; 2] {
 pub fn get_ping_sequence_start(s1: i32, s2: i32) -> i32 {
     s1 - s2
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SequenceCheck, SequenceMismatch, SequenceReset, Sequencer, SequencerState};
+
+    #[test]
+    fn state_round_trip() {
+        let mut sequencer = Sequencer::new(42);
+        sequencer.next_sequence();
+        sequencer.next_sequence();
+
+        let mut restored = Sequencer::from_state(sequencer.state());
+        assert_eq!(restored.next_sequence(), sequencer.next_sequence());
+    }
+
+    #[test]
+    fn restored_counter_is_wrapped() {
+        for (counter, expected) in [(i32::MAX, 8), (-1, 0), (12, 3)] {
+            let mut sequencer = Sequencer::from_state(SequencerState { start: 20, counter });
+            assert_eq!(sequencer.next_sequence(), 20 + expected);
+        }
+    }
+
+    #[test]
+    fn counter_desync() {
+        let mut sequencer = Sequencer::new(42);
+        sequencer.next_sequence();
+        sequencer.next_sequence();
+        assert_eq!(
+            sequencer.diagnose_mismatch(43),
+            Some(SequenceMismatch::CounterDesync {
+                expected: 2,
+                received: 1
+            })
+        );
+    }
+
+    #[test]
+    fn missed_reset() {
+        let mut sequencer = Sequencer::new(42);
+        sequencer.set_start(100);
+        sequencer.next_sequence();
+        assert_eq!(
+            sequencer.diagnose_mismatch(43),
+            Some(SequenceMismatch::MissedReset(None))
+        );
+
+        sequencer.reset(200, SequenceReset::Account);
+        assert_eq!(
+            sequencer.diagnose_mismatch(101),
+            Some(SequenceMismatch::MissedReset(Some(SequenceReset::Account)))
+        );
+        assert_eq!(
+            sequencer.diagnose_mismatch(43),
+            Some(SequenceMismatch::Unknown)
+        );
+    }
+
+    #[test]
+    fn verify_wraps() {
+        let mut sequencer = Sequencer::new(20);
+        for _ in 0..8 {
+            sequencer.next_sequence();
+        }
+
+        // expected 29, 20 is one packet ahead after the counter wraps
+        assert_eq!(
+            sequencer.verify(20),
+            SequenceCheck::TooHigh {
+                expected: 29,
+                window: 20..=29
+            }
+        );
+
+        // expected 20, 29 is one packet behind before the counter wraps
+        assert_eq!(
+            sequencer.verify(29),
+            SequenceCheck::TooLow {
+                expected: 20,
+                window: 20..=29
+            }
+        );
+    }
+
+    #[test]
+    fn verify_tolerance() {
+        let mut sequencer = Sequencer::new(20);
+        sequencer.set_tolerance(1);
+
+        // expected 21, one dropped packet
+        assert_eq!(sequencer.verify(22), SequenceCheck::Ok);
+        assert_eq!(sequencer.verify(23), SequenceCheck::Ok);
+
+        // expected 24, outside of the start's window
+        assert_eq!(
+            sequencer.verify(5),
+            SequenceCheck::TooLow {
+                expected: 24,
+                window: 20..=29
+            }
+        );
+    }
+}
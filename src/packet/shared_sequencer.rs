@@ -0,0 +1,165 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::SequencerState;
+
+#[derive(Debug)]
+/// A [Sequencer](super::Sequencer) that can be shared between threads or tasks without a
+/// lock
+///
+/// The start and counter are packed in a single [AtomicU64] so every method is one atomic
+/// operation: two tasks calling [next_sequence](SharedSequencer::next_sequence) at once
+/// always get different values, and a new start is never mixed with an old counter.
+///
+/// Updates use [Ordering::AcqRel] and reads [Ordering::Acquire], so anything a task wrote
+/// before calling [set_start](SharedSequencer::set_start) is visible to a task that gets a
+/// sequence from the new start.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, thread};
+///
+/// use eolib::packet::SharedSequencer;
+///
+/// let sequencer = Arc::new(SharedSequencer::new(20));
+///
+/// let handles: Vec<_> = (0..3)
+///     .map(|_| {
+///         let sequencer = Arc::clone(&sequencer);
+///         thread::spawn(move || sequencer.next_sequence())
+///     })
+///     .collect();
+///
+/// let mut sequences: Vec<i32> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+/// sequences.sort();
+/// assert_eq!(sequences, [21, 22, 23]);
+/// ```
+pub struct SharedSequencer {
+    state: AtomicU64,
+}
+
+impl SharedSequencer {
+    /// creates a new [SharedSequencer] with the specified starting value
+    pub fn new(start: i32) -> Self {
+        Self {
+            state: AtomicU64::new(pack(start, 0)),
+        }
+    }
+
+    /// creates a new [SharedSequencer] from a previously saved [SequencerState]
+    ///
+    /// the counter only runs from 0 to 9, so one outside that range is wrapped into it
+    pub fn from_state(state: SequencerState) -> Self {
+        Self {
+            state: AtomicU64::new(pack(state.start, state.counter.rem_euclid(10))),
+        }
+    }
+
+    /// returns the next sequence value
+    pub fn next_sequence(&self) -> i32 {
+        let previous = self
+            .state
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+                let (start, counter) = unpack(state);
+                Some(pack(start, (counter + 1) % 10))
+            })
+            .unwrap();
+
+        let (start, counter) = unpack(previous);
+        start + (counter + 1) % 10
+    }
+
+    /// sets a new starting value for the sequencer
+    ///
+    /// the counter is kept, like [Sequencer::set_start](super::Sequencer::set_start)
+    pub fn set_start(&self, start: i32) {
+        self.state
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+                let (_, counter) = unpack(state);
+                Some(pack(start, counter))
+            })
+            .unwrap();
+    }
+
+    /// gets the current starting value for the sequencer
+    pub fn get_start(&self) -> i32 {
+        unpack(self.state.load(Ordering::Acquire)).0
+    }
+
+    /// returns the current state of the sequencer
+    pub fn state(&self) -> SequencerState {
+        let (start, counter) = unpack(self.state.load(Ordering::Acquire));
+        SequencerState { start, counter }
+    }
+}
+
+fn pack(start: i32, counter: i32) -> u64 {
+    (start as u32 as u64) << 32 | counter as u32 as u64
+}
+
+fn unpack(state: u64) -> (i32, i32) {
+    ((state >> 32) as u32 as i32, state as u32 as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::SharedSequencer;
+    use crate::packet::{Sequencer, SequencerState};
+
+    #[test]
+    fn matches_sequencer() {
+        let mut sequencer = Sequencer::new(-5);
+        let shared = SharedSequencer::new(-5);
+
+        for i in 0..25 {
+            if i == 12 {
+                sequencer.set_start(100);
+                shared.set_start(100);
+            }
+            assert_eq!(shared.next_sequence(), sequencer.next_sequence());
+        }
+
+        assert_eq!(shared.state(), sequencer.state());
+        assert_eq!(shared.get_start(), 100);
+    }
+
+    #[test]
+    fn restored_counter_is_wrapped() {
+        let state = SequencerState {
+            start: 20,
+            counter: i32::MAX,
+        };
+        assert_eq!(
+            SharedSequencer::from_state(state).next_sequence(),
+            Sequencer::from_state(state).next_sequence()
+        );
+    }
+
+    #[test]
+    fn concurrent_calls_use_every_counter() {
+        let sequencer = Arc::new(SharedSequencer::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let sequencer = Arc::clone(&sequencer);
+                thread::spawn(move || {
+                    (0..250)
+                        .map(|_| sequencer.next_sequence())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut counts = [0; 10];
+        for handle in handles {
+            for sequence in handle.join().unwrap() {
+                counts[sequence as usize] += 1;
+            }
+        }
+
+        // 1000 calls cycle through the counters exactly 100 times
+        assert_eq!(counts, [100; 10]);
+        assert_eq!(sequencer.state().counter, 0);
+    }
+}
@@ -2,3 +2,179 @@
 #![allow(clippy::single_match)]
 #![allow(clippy::large_enum_variant)]
 include!(concat!(env!("OUT_DIR"), "/mod.rs"));
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{
+        map::Emf,
+        net::{
+            client::{self, CharacterCreateClientPacket},
+            server::{
+                self, InitInitServerPacket, InitInitServerPacketReplyCodeData,
+                InitInitServerPacketReplyCodeDataOutOfDate, TalkReply, TalkReplyServerPacket,
+                WelcomeReplyServerPacket, WelcomeReplyServerPacketWelcomeCodeData,
+                WelcomeReplyServerPacketWelcomeCodeDataEnterGame,
+            },
+            CharacterMapInfo, ItemMapInfo, NearbyInfo, NpcMapInfo, PacketAction, PacketFamily,
+        },
+        BigCoords, Coords, Direction, Gender,
+    };
+    use crate::data::EoSerialize;
+
+    fn assert_encoded_size<T: EoSerialize>(value: &T) {
+        assert_eq!(value.encoded_size(), value.to_bytes().unwrap().len());
+    }
+
+    #[test]
+    fn generated_types_hash() {
+        let mut set = HashSet::new();
+        set.insert(Coords { x: 1, y: 2 });
+        set.insert(Coords { x: 1, y: 2 });
+        set.insert(Coords { x: 2, y: 1 });
+        assert_eq!(set.len(), 2);
+
+        let directions: HashSet<Direction> = [Direction::Up, Direction::Up].into();
+        assert_eq!(directions.len(), 1);
+    }
+
+    #[test]
+    fn switch_data_defaults_to_first_case() {
+        assert_eq!(
+            InitInitServerPacketReplyCodeData::default(),
+            InitInitServerPacketReplyCodeData::OutOfDate(
+                InitInitServerPacketReplyCodeDataOutOfDate::default()
+            )
+        );
+
+        let mut set = HashSet::new();
+        set.insert(InitInitServerPacket::default());
+        assert!(set.contains(&InitInitServerPacket::default()));
+    }
+
+    #[test]
+    fn builder_validates_fields() {
+        let packet = CharacterCreateClientPacket::builder()
+            .session_id(1234)
+            .gender(Gender::Male)
+            .hair_style(3)
+            .name("vulture")
+            .build()
+            .unwrap();
+        assert_eq!(
+            packet,
+            CharacterCreateClientPacket {
+                session_id: 1234,
+                gender: Gender::Male,
+                hair_style: 3,
+                name: "vulture".to_owned(),
+                ..Default::default()
+            }
+        );
+
+        assert!(CharacterCreateClientPacket::builder()
+            .session_id(-1)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn encoded_size_matches_serialized_length() {
+        assert_encoded_size(&Coords { x: 1, y: 2 });
+        assert_encoded_size(&CharacterCreateClientPacket {
+            name: "naïve".to_owned(),
+            ..Default::default()
+        });
+        assert_encoded_size(&InitInitServerPacket::default());
+        assert_encoded_size(&WelcomeReplyServerPacket::default());
+        assert_encoded_size(&WelcomeReplyServerPacket {
+            welcome_code_data: Some(WelcomeReplyServerPacketWelcomeCodeData::EnterGame(
+                WelcomeReplyServerPacketWelcomeCodeDataEnterGame::default(),
+            )),
+            ..Default::default()
+        });
+        assert_encoded_size(&Emf::default());
+    }
+
+    #[test]
+    fn nearby_info_round_trips() {
+        let character = |name: &str, player_id| CharacterMapInfo {
+            name: name.to_owned(),
+            player_id,
+            map_id: 5,
+            coords: BigCoords { x: 10, y: 300 },
+            direction: Direction::Right,
+            guild_tag: "ABC".to_owned(),
+            level: 20,
+            hit_points: 100,
+            max_hit_points: 120,
+            ..Default::default()
+        };
+        let nearby = NearbyInfo {
+            characters: vec![character("vulture", 1), character("sorokya", 2)],
+            npcs: vec![NpcMapInfo {
+                index: 1,
+                id: 170,
+                coords: Coords { x: 4, y: 6 },
+                direction: Direction::Up,
+            }],
+            items: vec![ItemMapInfo {
+                uid: 7,
+                id: 1,
+                coords: Coords { x: 8, y: 9 },
+                amount: 50_000,
+            }],
+        };
+
+        let bytes = nearby.to_bytes().unwrap();
+        assert_eq!(NearbyInfo::from_bytes(&bytes).unwrap(), nearby);
+        assert_encoded_size(&nearby);
+
+        let empty = NearbyInfo::default();
+        assert_eq!(
+            NearbyInfo::from_bytes(&empty.to_bytes().unwrap()).unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn unrecognized_enum_values_round_trip() {
+        assert_eq!(PacketFamily::from(200), PacketFamily::Unrecognized(200));
+        assert_eq!(u8::from(PacketFamily::Unrecognized(200)), 200);
+
+        // a reply code only a custom server sends
+        let packet = TalkReplyServerPacket {
+            reply_code: TalkReply::Unrecognized(1000),
+            name: "vulture".to_owned(),
+        };
+        let bytes = packet.to_bytes().unwrap();
+        let read = TalkReplyServerPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(read.reply_code, TalkReply::Unrecognized(1000));
+        assert_eq!(i32::from(read.reply_code), 1000);
+        assert_eq!(read.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn packet_names() {
+        assert_eq!(
+            client::packet_name(PacketFamily::Walk, PacketAction::Player),
+            Some("Walk_Player")
+        );
+        assert_eq!(
+            server::packet_name(PacketFamily::Welcome, PacketAction::Reply),
+            Some("Welcome_Reply")
+        );
+        assert_eq!(
+            client::packet_name(PacketFamily::Welcome, PacketAction::Reply),
+            None
+        );
+
+        assert_eq!(
+            client::packet_id("Walk_Player"),
+            Some((PacketFamily::Walk, PacketAction::Player))
+        );
+        assert_eq!(server::packet_id("Walk_Player"), None);
+        assert_eq!(client::packet_id("walk_player"), None);
+    }
+}
@@ -0,0 +1,4 @@
+mod validate;
+pub use validate::{validate, validate_protocols, SchemaError};
+mod xml;
+pub use xml::*;
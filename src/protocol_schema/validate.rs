@@ -0,0 +1,523 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use super::{is_primitive, parse_protocol, Element, Enum, Protocol, StructElement, Switch};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+/// A problem with protocol XML files that would stop the code generator
+pub enum SchemaError {
+    #[error("{}: {message}", .path.display())]
+    Io { path: PathBuf, message: String },
+    #[error("{}: failed to parse: {message}", .path.display())]
+    Parse { path: PathBuf, message: String },
+    #[error("{}: {name} is defined more than once", .path.display())]
+    DuplicateType { path: PathBuf, name: String },
+    #[error("{}: {name} uses unknown type {data_type}", .path.display())]
+    UnknownType {
+        path: PathBuf,
+        name: String,
+        data_type: String,
+    },
+    #[error("{}: {name} uses length {length} which isn't defined", .path.display())]
+    LengthNotFound {
+        path: PathBuf,
+        name: String,
+        length: String,
+    },
+    #[error("{}: length {length} in {name} isn't used by any field or array", .path.display())]
+    UnusedLength {
+        path: PathBuf,
+        name: String,
+        length: String,
+    },
+    #[error("{}: {name} has a chunked element inside a chunked element", .path.display())]
+    NestedChunked { path: PathBuf, name: String },
+    #[error("{}: {name} switches on field {field} which isn't defined", .path.display())]
+    SwitchFieldNotFound {
+        path: PathBuf,
+        name: String,
+        field: String,
+    },
+    #[error("{}: {name} switches on field {field} which isn't an enum", .path.display())]
+    SwitchFieldNotEnum {
+        path: PathBuf,
+        name: String,
+        field: String,
+    },
+    #[error("{}: {name} has an optional field without a name", .path.display())]
+    UnnamedOptionalField { path: PathBuf, name: String },
+    #[error(
+        "{}: {name} has a case without a value for {field} that isn't the default",
+        .path.display()
+    )]
+    InvalidSwitchCase {
+        path: PathBuf,
+        name: String,
+        field: String,
+    },
+}
+
+/// checks protocol XML files against the rules the code generator enforces
+///
+/// `path` is either a single protocol.xml file or a directory that is searched recursively for
+/// protocol.xml files, like eo-protocol's `xml` directory. types can be used across every file
+/// that is found, so a file that uses types from another one should be checked with its
+/// directory.
+///
+/// returns every problem found, or an empty [Vec] if the files are valid
+///
+/// # Examples
+///
+/// ```no_run
+/// use eolib::protocol_schema::validate;
+///
+/// for error in validate("eo-protocol/xml") {
+///     eprintln!("{}", error);
+/// }
+/// ```
+pub fn validate<P: AsRef<Path>>(path: P) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    let mut paths = Vec::new();
+    find_protocol_files(path.as_ref(), &mut paths, &mut errors);
+    paths.sort();
+
+    let mut protocols = Vec::new();
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(xml) => match parse_protocol(&xml) {
+                Ok(protocol) => protocols.push((protocol, path)),
+                Err(e) => errors.push(SchemaError::Parse {
+                    path,
+                    message: e.to_string(),
+                }),
+            },
+            Err(e) => errors.push(SchemaError::Io {
+                path,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    errors.extend(validate_protocols(&protocols));
+    errors
+}
+
+/// checks parsed protocol files, with the path each one was read from, against the rules the
+/// code generator enforces
+///
+/// # Examples
+///
+/// ```
+/// use eolib::protocol_schema::{parse_protocol, validate_protocols, SchemaError};
+///
+/// let protocol = parse_protocol(
+///     r#"<protocol>
+///         <struct name="Coords">
+///             <field name="x" type="char"/>
+///             <field name="y" type="Direction"/>
+///         </struct>
+///     </protocol>"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     validate_protocols(&[(protocol, "protocol.xml".into())]),
+///     [SchemaError::UnknownType {
+///         path: "protocol.xml".into(),
+///         name: "Coords".to_owned(),
+///         data_type: "Direction".to_owned(),
+///     }]
+/// );
+/// ```
+pub fn validate_protocols(protocols: &[(Protocol, PathBuf)]) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    let mut enums = HashMap::new();
+    let mut structs = HashSet::new();
+
+    for (protocol, path) in protocols {
+        for element in &protocol.elements {
+            let name = match element {
+                Element::Enum(protocol_enum) => &protocol_enum.name,
+                Element::Struct(protocol_struct) => &protocol_struct.name,
+                Element::Packet(_) => continue,
+            };
+
+            if enums.contains_key(name.as_str()) || structs.contains(name.as_str()) {
+                errors.push(SchemaError::DuplicateType {
+                    path: path.to_owned(),
+                    name: name.to_owned(),
+                });
+                continue;
+            }
+
+            match element {
+                Element::Enum(protocol_enum) => {
+                    enums.insert(name.as_str(), protocol_enum);
+                }
+                _ => {
+                    structs.insert(name.as_str());
+                }
+            }
+        }
+    }
+
+    for (protocol, path) in protocols {
+        let mut checker = Checker {
+            path,
+            enums: &enums,
+            structs: &structs,
+            errors: &mut errors,
+        };
+
+        for element in &protocol.elements {
+            match element {
+                Element::Enum(_) => {}
+                Element::Struct(protocol_struct) => {
+                    checker.check_elements(&protocol_struct.name, &protocol_struct.elements)
+                }
+                Element::Packet(packet) => checker.check_elements(
+                    &format!("{}_{}", packet.family, packet.action),
+                    &packet.elements,
+                ),
+            }
+        }
+    }
+
+    errors
+}
+
+fn find_protocol_files(path: &Path, paths: &mut Vec<PathBuf>, errors: &mut Vec<SchemaError>) {
+    if path.is_file() {
+        paths.push(path.to_owned());
+        return;
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push(SchemaError::Io {
+                path: path.to_owned(),
+                message: e.to_string(),
+            });
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_protocol_files(&path, paths, errors);
+        } else if path.file_name().is_some_and(|name| name == "protocol.xml") {
+            paths.push(path);
+        }
+    }
+}
+
+struct Checker<'a> {
+    path: &'a Path,
+    enums: &'a HashMap<&'a str, &'a Enum>,
+    structs: &'a HashSet<&'a str>,
+    errors: &'a mut Vec<SchemaError>,
+}
+
+impl Checker<'_> {
+    // Checks a struct, packet or switch case. Lengths and switch fields are looked up in the
+    // same elements and their chunked element, like the generator does
+    fn check_elements(&mut self, name: &str, elements: &[StructElement]) {
+        let mut flattened = Vec::new();
+        for element in elements {
+            match element {
+                StructElement::Chunked(chunked) => {
+                    if chunked
+                        .elements
+                        .iter()
+                        .any(|e| matches!(e, StructElement::Chunked(_)))
+                    {
+                        self.push(|path| SchemaError::NestedChunked {
+                            path,
+                            name: name.to_owned(),
+                        });
+                    }
+                    flattened.extend(&chunked.elements);
+                }
+                _ => flattened.push(element),
+            }
+        }
+
+        let lengths: Vec<&str> = flattened
+            .iter()
+            .filter_map(|e| match e {
+                StructElement::Length(length) => Some(length.name.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let mut used_lengths = HashSet::new();
+
+        for element in &flattened {
+            let length = match element {
+                StructElement::Field(field) => {
+                    if field.optional == Some(true) && field.name.is_none() {
+                        self.push(|path| SchemaError::UnnamedOptionalField {
+                            path,
+                            name: name.to_owned(),
+                        });
+                    }
+                    self.check_type(name, &field.data_type, true);
+                    field.length.as_deref()
+                }
+                StructElement::Array(array) => {
+                    self.check_type(name, &array.data_type, true);
+                    array.length.as_deref()
+                }
+                StructElement::Length(length) => {
+                    self.check_type(name, &length.data_type, false);
+                    None
+                }
+                StructElement::Dummy(dummy) => {
+                    self.check_type(name, &dummy.data_type, false);
+                    None
+                }
+                StructElement::Switch(switch) => {
+                    self.check_switch(name, switch, &flattened);
+                    None
+                }
+                _ => None,
+            };
+
+            if let Some(length) = length {
+                if length.chars().all(|c| c.is_numeric()) {
+                    continue;
+                }
+
+                if lengths.contains(&length) {
+                    used_lengths.insert(length);
+                } else {
+                    self.push(|path| SchemaError::LengthNotFound {
+                        path,
+                        name: name.to_owned(),
+                        length: length.to_owned(),
+                    });
+                }
+            }
+        }
+
+        for length in lengths {
+            if !used_lengths.contains(length) {
+                self.push(|path| SchemaError::UnusedLength {
+                    path,
+                    name: name.to_owned(),
+                    length: length.to_owned(),
+                });
+            }
+        }
+    }
+
+    fn check_switch(&mut self, name: &str, switch: &Switch, elements: &[&StructElement]) {
+        let field = elements.iter().find_map(|e| match e {
+            StructElement::Field(field) if field.name.as_deref() == Some(&switch.field) => {
+                Some(field)
+            }
+            _ => None,
+        });
+
+        match field {
+            Some(field) => {
+                let data_type = field.data_type.split(':').next().unwrap_or_default();
+                if !self.enums.contains_key(data_type) {
+                    self.push(|path| SchemaError::SwitchFieldNotEnum {
+                        path,
+                        name: name.to_owned(),
+                        field: switch.field.clone(),
+                    });
+                }
+            }
+            None => self.push(|path| SchemaError::SwitchFieldNotFound {
+                path,
+                name: name.to_owned(),
+                field: switch.field.clone(),
+            }),
+        }
+
+        for case in &switch.cases {
+            if case.value.is_none() && case.default != Some(true) {
+                self.push(|path| SchemaError::InvalidSwitchCase {
+                    path,
+                    name: name.to_owned(),
+                    field: switch.field.clone(),
+                });
+            }
+
+            if let Some(elements) = &case.elements {
+                self.check_elements(name, elements);
+            }
+        }
+    }
+
+    // `type:underlying` is allowed for fields and arrays, where `type` is an enum and
+    // `underlying` the primitive it's written as
+    fn check_type(&mut self, name: &str, data_type: &str, allow_defined: bool) {
+        let (base, underlying) = match data_type.split_once(':') {
+            Some((base, underlying)) => (base, Some(underlying)),
+            None => (data_type, None),
+        };
+
+        let valid = match underlying {
+            Some(underlying) => {
+                allow_defined && self.enums.contains_key(base) && is_primitive(underlying)
+            }
+            None => {
+                is_primitive(base)
+                    || (allow_defined
+                        && (self.enums.contains_key(base) || self.structs.contains(base)))
+            }
+        };
+
+        if !valid {
+            self.push(|path| SchemaError::UnknownType {
+                path,
+                name: name.to_owned(),
+                data_type: data_type.to_owned(),
+            });
+        }
+    }
+
+    fn push<F: FnOnce(PathBuf) -> SchemaError>(&mut self, error: F) {
+        self.errors.push(error(self.path.to_owned()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{validate, validate_protocols, SchemaError};
+    use crate::protocol_schema::parse_protocol;
+
+    fn check(xml: &str) -> Vec<SchemaError> {
+        validate_protocols(&[(parse_protocol(xml).unwrap(), PathBuf::from("protocol.xml"))])
+    }
+
+    #[test]
+    fn valid_protocol() {
+        let errors = check(
+            r#"<protocol>
+                <enum name="Kind" type="char">
+                    <value name="A">0</value>
+                    <value name="B">1</value>
+                </enum>
+                <struct name="Item">
+                    <length name="name_length" type="char"/>
+                    <field name="name" type="string" length="name_length"/>
+                    <field name="kind" type="Kind:short"/>
+                    <array name="values" type="int" length="4"/>
+                </struct>
+                <packet family="Item" action="Use">
+                    <chunked>
+                        <field name="kind" type="Kind"/>
+                        <break/>
+                        <switch field="kind">
+                            <case value="A">
+                                <field name="item" type="Item"/>
+                            </case>
+                            <case default="true">
+                                <dummy type="byte">0</dummy>
+                            </case>
+                        </switch>
+                    </chunked>
+                </packet>
+            </protocol>"#,
+        );
+        assert_eq!(errors, []);
+    }
+
+    #[test]
+    fn lengths() {
+        let errors = check(
+            r#"<protocol>
+                <struct name="Item">
+                    <length name="unused" type="char"/>
+                    <field name="name" type="string" length="missing"/>
+                </struct>
+            </protocol>"#,
+        );
+        assert_eq!(
+            errors,
+            [
+                SchemaError::LengthNotFound {
+                    path: "protocol.xml".into(),
+                    name: "Item".to_owned(),
+                    length: "missing".to_owned(),
+                },
+                SchemaError::UnusedLength {
+                    path: "protocol.xml".into(),
+                    name: "Item".to_owned(),
+                    length: "unused".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn switches() {
+        let errors = check(
+            r#"<protocol>
+                <struct name="Item">
+                    <field name="id" type="short"/>
+                    <switch field="id">
+                        <case>
+                            <field type="byte" optional="true"/>
+                        </case>
+                    </switch>
+                    <switch field="missing"/>
+                </struct>
+            </protocol>"#,
+        );
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(errors[0], SchemaError::SwitchFieldNotEnum { .. }));
+        assert!(matches!(errors[1], SchemaError::InvalidSwitchCase { .. }));
+        assert!(matches!(
+            errors[2],
+            SchemaError::UnnamedOptionalField { .. }
+        ));
+        assert!(matches!(errors[3], SchemaError::SwitchFieldNotFound { .. }));
+    }
+
+    #[test]
+    fn duplicates_and_nested_chunks() {
+        let errors = check(
+            r#"<protocol>
+                <struct name="Item">
+                    <chunked>
+                        <chunked/>
+                    </chunked>
+                </struct>
+                <enum name="Item" type="char"/>
+            </protocol>"#,
+        );
+        assert_eq!(
+            errors,
+            [
+                SchemaError::DuplicateType {
+                    path: "protocol.xml".into(),
+                    name: "Item".to_owned(),
+                },
+                SchemaError::NestedChunked {
+                    path: "protocol.xml".into(),
+                    name: "Item".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_path() {
+        let errors = validate("does/not/exist");
+        assert!(matches!(errors.as_slice(), [SchemaError::Io { .. }]));
+    }
+}
@@ -0,0 +1,198 @@
+// This file is also included by build.rs, so it can only use std, serde and quick-xml
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+/// The contents of a protocol.xml file
+pub struct Protocol {
+    #[serde(rename = "$value", default)]
+    pub elements: Vec<Element>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// A top level element of a protocol.xml file
+pub enum Element {
+    Enum(Enum),
+    Struct(Struct),
+    Packet(Packet),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// An `<enum>` element
+pub struct Enum {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@type")]
+    pub data_type: String,
+    #[serde(rename = "$value", default)]
+    pub elements: Vec<EnumElement>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A child element of an `<enum>`
+pub enum EnumElement {
+    #[serde(rename = "comment")]
+    Comment(String),
+    #[serde(rename = "value")]
+    Value(EnumValue),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<value>` element of an `<enum>`
+pub struct EnumValue {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "comment")]
+    pub comment: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: i32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<struct>` element
+pub struct Struct {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "$value", default)]
+    pub elements: Vec<StructElement>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+/// A child element of a `<struct>`, `<packet>`, `<chunked>` or `<case>`
+pub enum StructElement {
+    Break,
+    Chunked(Chunked),
+    Comment(String),
+    Dummy(Dummy),
+    Field(Field),
+    Array(Array),
+    Length(Length),
+    Switch(Switch),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<chunked>` element
+pub struct Chunked {
+    #[serde(rename = "$value", default)]
+    pub elements: Vec<StructElement>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<field>` element
+pub struct Field {
+    #[serde(rename = "@name")]
+    pub name: Option<String>,
+    #[serde(rename = "@type")]
+    pub data_type: String,
+    #[serde(rename = "$value", default)]
+    pub value: Option<String>,
+    pub comment: Option<String>,
+    #[serde(rename = "@padded")]
+    pub padded: Option<bool>,
+    #[serde(rename = "@optional")]
+    pub optional: Option<bool>,
+    #[serde(rename = "@length")]
+    pub length: Option<String>,
+}
+
+fn default_as_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// An `<array>` element
+pub struct Array {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@type")]
+    pub data_type: String,
+    #[serde(rename = "@length")]
+    pub length: Option<String>,
+    #[serde(rename = "@optional")]
+    pub optional: Option<bool>,
+    #[serde(rename = "@delimited")]
+    pub delimited: Option<bool>,
+    #[serde(rename = "@trailing-delimiter")]
+    #[serde(default = "default_as_true")]
+    pub trailing_delimiter: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<length>` element
+pub struct Length {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@type")]
+    pub data_type: String,
+    #[serde(rename = "@optional")]
+    pub optional: Option<bool>,
+    #[serde(rename = "@offset")]
+    pub offset: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<dummy>` element
+pub struct Dummy {
+    #[serde(rename = "@type")]
+    pub data_type: String,
+    #[serde(rename = "$value", default)]
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<switch>` element
+pub struct Switch {
+    #[serde(rename = "@field")]
+    pub field: String,
+    #[serde(rename = "$value", default)]
+    pub cases: Vec<Case>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename = "case")]
+/// A `<case>` element of a `<switch>`
+pub struct Case {
+    #[serde(rename = "@default")]
+    pub default: Option<bool>,
+    #[serde(rename = "@value")]
+    pub value: Option<String>,
+    #[serde(rename = "$value", default)]
+    pub elements: Option<Vec<StructElement>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+/// A `<packet>` element
+pub struct Packet {
+    #[serde(rename = "@action")]
+    pub action: String,
+    #[serde(rename = "@family")]
+    pub family: String,
+    #[serde(rename = "$value", default)]
+    pub elements: Vec<StructElement>,
+}
+
+/// The primitive types a field can have
+pub static PRIMITIVE_TYPES: [&str; 9] = [
+    "byte",
+    "char",
+    "short",
+    "three",
+    "int",
+    "bool",
+    "string",
+    "encoded_string",
+    "blob",
+];
+
+/// returns true if `data_type` is one of [PRIMITIVE_TYPES]
+pub fn is_primitive(data_type: &str) -> bool {
+    PRIMITIVE_TYPES.contains(&data_type)
+}
+
+/// parses the contents of a protocol.xml file
+pub fn parse_protocol(xml: &str) -> Result<Protocol, quick_xml::DeError> {
+    quick_xml::de::from_str(xml)
+}
@@ -0,0 +1,164 @@
+use crate::{
+    data::{
+        decode_number, decode_string, encode_number, encode_string, CHAR_MAX, SHORT_MAX, THREE_MAX,
+    },
+    encrypt::{
+        decrypt_packet, encrypt_packet, server_verification_hash, validate_init_challenge_hash,
+        MAX_CHALLENGE,
+    },
+    packet::{
+        get_init_sequence_bytes, get_init_sequence_start, get_ping_sequence_bytes,
+        get_ping_sequence_start,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The outcome of a single check run by [self_test]
+pub struct SelfTestCheck {
+    /// short description of what was checked
+    pub name: &'static str,
+    /// true if the check passed
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Results of [self_test]
+pub struct SelfTestReport {
+    /// every check that was run, in order
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// returns true if every check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// returns an iterator over the checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &SelfTestCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+}
+
+/// Runs quick internal checks of the wire format
+///
+/// Covers number and string encoding round trips, encryption symmetry for every swap
+/// multiple, sequence byte math and init challenge hashing.
+///
+/// Intended to be run once at startup so a broken build or platform fails fast instead of
+/// silently producing packets other clients can't read.
+///
+/// # Examples
+///
+/// ```
+/// let report = eolib::self_test();
+///
+/// for failure in report.failures() {
+///     eprintln!("self test failed: {}", failure.name);
+/// }
+///
+/// assert!(report.passed());
+/// ```
+pub fn self_test() -> SelfTestReport {
+    let checks = [
+        ("number encoding round trips", check_numbers as fn() -> bool),
+        ("string encoding round trips", check_strings),
+        ("packet encryption is symmetric", check_encryption),
+        ("init sequence bytes round trip", check_init_sequence),
+        ("ping sequence bytes round trip", check_ping_sequence),
+        ("init challenge hashes validate", check_init_challenge),
+    ];
+
+    SelfTestReport {
+        checks: checks
+            .iter()
+            .map(|(name, check)| SelfTestCheck {
+                name,
+                passed: check(),
+            })
+            .collect(),
+    }
+}
+
+fn check_numbers() -> bool {
+    [
+        0,
+        1,
+        CHAR_MAX - 1,
+        CHAR_MAX,
+        SHORT_MAX - 1,
+        SHORT_MAX,
+        THREE_MAX - 1,
+        THREE_MAX,
+        i32::MAX,
+    ]
+    .iter()
+    .all(|number| match encode_number(*number) {
+        Ok(bytes) => decode_number(&bytes) == *number,
+        Err(_) => false,
+    })
+}
+
+fn check_strings() -> bool {
+    let original: Vec<u8> = (0..=u8::MAX).collect();
+
+    // encoding depends on the parity of the length
+    [original.len(), original.len() - 1].iter().all(|length| {
+        let mut buf = original[..*length].to_vec();
+        encode_string(&mut buf);
+        decode_string(&mut buf);
+        buf == original[..*length]
+    })
+}
+
+fn check_encryption() -> bool {
+    // the header must not be 0xFF 0xFF or the packet is left unencrypted
+    let original: Vec<u8> = (0..=u8::MAX).rev().skip(1).collect();
+
+    (0..=u8::MAX).all(|multiple| {
+        [original.len(), original.len() - 1].iter().all(|length| {
+            let mut buf = original[..*length].to_vec();
+            encrypt_packet(&mut buf, multiple);
+            decrypt_packet(&mut buf, multiple);
+            buf == original[..*length]
+        })
+    })
+}
+
+fn check_init_sequence() -> bool {
+    (0..=CHAR_MAX - 10).all(|start| {
+        let [seq1, seq2] = get_init_sequence_bytes(start);
+        (0..CHAR_MAX).contains(&seq1)
+            && (0..CHAR_MAX).contains(&seq2)
+            && get_init_sequence_start(seq1, seq2) == start
+    })
+}
+
+fn check_ping_sequence() -> bool {
+    (0..=CHAR_MAX - 10).all(|start| {
+        let [seq1, seq2] = get_ping_sequence_bytes(start);
+        (0..SHORT_MAX).contains(&seq1)
+            && (0..CHAR_MAX).contains(&seq2)
+            && get_ping_sequence_start(seq1, seq2) == start
+    })
+}
+
+fn check_init_challenge() -> bool {
+    [1, 2, MAX_CHALLENGE / 2, MAX_CHALLENGE]
+        .iter()
+        .all(|challenge| {
+            validate_init_challenge_hash(*challenge, server_verification_hash(*challenge))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::self_test;
+
+    #[test]
+    fn self_test_passes() {
+        let report = self_test();
+        let failures: Vec<_> = report.failures().map(|check| check.name).collect();
+        assert!(failures.is_empty(), "{:?}", failures);
+    }
+}
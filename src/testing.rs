@@ -0,0 +1,202 @@
+use std::fmt::Debug;
+
+use proptest::{collection::SizeRange, strategy::Strategy};
+
+use crate::{
+    data::{EoSerialize, CHAR_MAX, SHORT_MAX, THREE_MAX},
+    Error,
+};
+
+/// Numbers on either side of the limits of each EO integer type
+pub const NUMBER_EDGE_CASES: [i32; 9] = [
+    0,
+    1,
+    CHAR_MAX - 1,
+    CHAR_MAX,
+    SHORT_MAX - 1,
+    SHORT_MAX,
+    THREE_MAX - 1,
+    THREE_MAX,
+    i32::MAX,
+];
+
+/// serializes `value` and returns the result of deserializing it again
+pub fn round_trip<T: EoSerialize>(value: &T) -> Result<T, Error> {
+    let bytes = value.to_bytes()?;
+    Ok(T::from_bytes(&bytes)?)
+}
+
+/// asserts that `value` is unchanged after being serialized and deserialized
+///
+/// # Panics
+///
+/// Panics if serializing or deserializing fails, or if the deserialized value is different
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{protocol::Coords, testing::assert_round_trip};
+///
+/// assert_round_trip(&Coords { x: 5, y: 10 });
+/// ```
+pub fn assert_round_trip<T: EoSerialize + PartialEq + Debug>(value: &T) {
+    match round_trip(value) {
+        Ok(result) => assert_eq!(&result, value, "value changed after a round trip"),
+        Err(e) => panic!("round trip of {:?} failed: {}", value, e),
+    }
+}
+
+/// generates any value of an EO byte
+pub fn eo_byte() -> impl Strategy<Value = u8> {
+    0..=u8::MAX
+}
+
+/// generates values that fit in an EO char
+pub fn eo_char() -> impl Strategy<Value = i32> {
+    0..CHAR_MAX
+}
+
+/// generates values that fit in an EO short
+pub fn eo_short() -> impl Strategy<Value = i32> {
+    0..SHORT_MAX
+}
+
+/// generates values that fit in an EO three
+pub fn eo_three() -> impl Strategy<Value = i32> {
+    0..THREE_MAX
+}
+
+/// generates values that fit in an EO int
+///
+/// the int range goes past [i32::MAX], which is where generated values stop
+pub fn eo_int() -> impl Strategy<Value = i32> {
+    0..=i32::MAX
+}
+
+/// picks one of [NUMBER_EDGE_CASES]
+pub fn number_edge_case() -> impl Strategy<Value = i32> {
+    proptest::sample::select(NUMBER_EDGE_CASES.to_vec())
+}
+
+/// generates strings of printable ASCII characters with a length in `size`
+///
+/// these survive WINDOWS-1252 and string encoding, and never contain a `0xFF` break
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     protocol::net::OnlinePlayer,
+///     testing::{assert_round_trip, eo_char, eo_string},
+/// };
+/// use proptest::{prelude::*, test_runner::TestRunner};
+///
+/// let strategy = (eo_string(0..=12), eo_string(0..=32), eo_char());
+/// TestRunner::default()
+///     .run(&strategy, |(name, title, level)| {
+///         assert_round_trip(&OnlinePlayer {
+///             name,
+///             title,
+///             level,
+///             ..Default::default()
+///         });
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+pub fn eo_string(size: impl Into<SizeRange>) -> impl Strategy<Value = String> {
+    proptest::collection::vec(b' '..=b'~', size)
+        .prop_map(|bytes| bytes.into_iter().map(char::from).collect())
+}
+
+/// generates blobs with a length in `size`
+///
+/// `0xFF` is left out so blobs can't end a chunk early
+pub fn eo_blob(size: impl Into<SizeRange>) -> impl Strategy<Value = Vec<u8>> {
+    proptest::collection::vec(0..u8::MAX, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{
+        assert_round_trip, eo_blob, eo_char, eo_int, eo_short, eo_string, eo_three,
+        number_edge_case, round_trip,
+    };
+    use crate::{
+        data::{EoSerialize, THREE_MAX},
+        protocol::{
+            map::Emf,
+            net::{
+                client::{CharacterCreateClientPacket, TalkReportClientPacket},
+                server::{
+                    InitInitServerPacket, RefreshReplyServerPacket, WelcomeReplyServerPacket,
+                },
+                Item,
+            },
+            r#pub::Eif,
+            Coords,
+        },
+    };
+
+    proptest! {
+        #[test]
+        fn numbers_round_trip(x in eo_char(), y in eo_char(), id in eo_short(), amount in eo_int()) {
+            assert_round_trip(&Coords { x, y });
+            assert_round_trip(&Item { id, amount });
+        }
+
+        #[test]
+        fn threes_fit(three in eo_three()) {
+            prop_assert!(three < THREE_MAX);
+        }
+
+        #[test]
+        fn edge_cases(amount in number_edge_case()) {
+            assert_round_trip(&Item { id: 1, amount });
+        }
+
+        #[test]
+        fn strings_are_printable(string in eo_string(0..=64)) {
+            prop_assert!(string.len() <= 64);
+            prop_assert!(string.chars().all(|c| (' '..='~').contains(&c)));
+        }
+
+        #[test]
+        fn blobs_have_no_breaks(blob in eo_blob(0..64)) {
+            prop_assert!(!blob.contains(&0xFF));
+        }
+
+        #[test]
+        fn generated_packets_round_trip(
+            init in any::<InitInitServerPacket>(),
+            welcome in any::<WelcomeReplyServerPacket>(),
+            refresh in any::<RefreshReplyServerPacket>(),
+            talk in any::<TalkReportClientPacket>(),
+            create in any::<CharacterCreateClientPacket>(),
+        ) {
+            assert_round_trip(&init);
+            assert_round_trip(&welcome);
+            assert_round_trip(&refresh);
+            assert_round_trip(&talk);
+            assert_round_trip(&create);
+        }
+
+        #[test]
+        fn generated_files_round_trip(mut emf in any::<Emf>(), eif in any::<Eif>()) {
+            prop_assert_eq!(emf.encoded_size(), emf.to_bytes().unwrap().len());
+
+            // sign text is an encoded string without a break, so its last character is
+            // dropped when it's read back
+            emf.signs.clear();
+            assert_round_trip(&emf);
+            assert_round_trip(&eif);
+        }
+    }
+
+    #[test]
+    fn out_of_range_fails() {
+        assert!(round_trip(&Coords { x: 300, y: 0 }).is_err());
+    }
+}
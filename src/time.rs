@@ -0,0 +1,308 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// The number of timestamp units (hundredths of a second) in a day
+///
+/// Timestamps wrap back to 0 at midnight.
+pub const TIMESTAMP_MAX: i32 = 24 * 60 * 60 * 100;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// A time of day as sent in packet timestamps
+///
+/// The client sends the time it performed an action (attacks, spells, etc.) as an EO three
+/// holding the number of hundredths of a second since midnight:
+///
+/// `hour * 360000 + minute * 6000 + second * 100 + hundredths`
+///
+/// # Examples
+///
+/// ```
+/// use eolib::time::Timestamp;
+///
+/// let timestamp = Timestamp::from_three(4_512_345);
+/// assert_eq!(timestamp, Timestamp::new(12, 32, 3, 45).unwrap());
+/// assert_eq!(timestamp.to_three(), 4_512_345);
+/// ```
+pub struct Timestamp {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    hundredths: u8,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TimestampError {
+    #[error("Hour {0} must be less than 24")]
+    InvalidHour(u8),
+    #[error("Minute {0} must be less than 60")]
+    InvalidMinute(u8),
+    #[error("Second {0} must be less than 60")]
+    InvalidSecond(u8),
+    #[error("Hundredths {0} must be less than 100")]
+    InvalidHundredths(u8),
+    #[cfg(feature = "chrono")]
+    #[error("Leap seconds can't be sent as a timestamp")]
+    LeapSecond,
+}
+
+impl Timestamp {
+    /// creates a [Timestamp], checking that each part is in range
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eolib::time::{Timestamp, TimestampError};
+    ///
+    /// let timestamp = Timestamp::new(23, 59, 59, 99).unwrap();
+    /// assert_eq!(timestamp.hour(), 23);
+    ///
+    /// assert_eq!(Timestamp::new(24, 0, 0, 0), Err(TimestampError::InvalidHour(24)));
+    /// assert_eq!(Timestamp::new(0, 0, 0, 100), Err(TimestampError::InvalidHundredths(100)));
+    /// ```
+    pub fn new(hour: u8, minute: u8, second: u8, hundredths: u8) -> Result<Self, TimestampError> {
+        if hour >= 24 {
+            return Err(TimestampError::InvalidHour(hour));
+        }
+        if minute >= 60 {
+            return Err(TimestampError::InvalidMinute(minute));
+        }
+        if second >= 60 {
+            return Err(TimestampError::InvalidSecond(second));
+        }
+        if hundredths >= 100 {
+            return Err(TimestampError::InvalidHundredths(hundredths));
+        }
+
+        Ok(Self {
+            hour,
+            minute,
+            second,
+            hundredths,
+        })
+    }
+
+    /// returns the hour, from 0 to 23
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// returns the minute, from 0 to 59
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// returns the second, from 0 to 59
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// returns the hundredths of a second, from 0 to 99
+    pub fn hundredths(&self) -> u8 {
+        self.hundredths
+    }
+
+    /// creates a [Timestamp] from a decoded three, wrapping values past midnight
+    pub fn from_three(value: i32) -> Self {
+        let value = value.rem_euclid(TIMESTAMP_MAX);
+        Self {
+            hour: (value / 360_000) as u8,
+            minute: (value / 6_000 % 60) as u8,
+            second: (value / 100 % 60) as u8,
+            hundredths: (value % 100) as u8,
+        }
+    }
+
+    /// returns the timestamp as a value that can be written as a three
+    pub fn to_three(&self) -> i32 {
+        self.hour as i32 * 360_000
+            + self.minute as i32 * 6_000
+            + self.second as i32 * 100
+            + self.hundredths as i32
+    }
+
+    /// creates a [Timestamp] from the time since midnight, wrapping durations longer than a day
+    pub fn from_duration(duration: Duration) -> Self {
+        let hundredths = duration.as_millis() / 10 % TIMESTAMP_MAX as u128;
+        Self::from_three(hundredths as i32)
+    }
+
+    /// returns the time since midnight
+    pub fn to_duration(&self) -> Duration {
+        Duration::from_millis(self.to_three() as u64 * 10)
+    }
+
+    /// returns the time between an earlier timestamp and this one
+    ///
+    /// timestamps wrap at midnight so `earlier` is assumed to be less than a day before this
+    /// timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use eolib::time::Timestamp;
+    ///
+    /// let before_midnight = Timestamp::new(23, 59, 59, 50).unwrap();
+    /// let after_midnight = Timestamp::new(0, 0, 0, 30).unwrap();
+    ///
+    /// assert_eq!(
+    ///     after_midnight.duration_since(before_midnight),
+    ///     Duration::from_millis(800)
+    /// );
+    /// ```
+    pub fn duration_since(&self, earlier: Timestamp) -> Duration {
+        let hundredths = (self.to_three() - earlier.to_three()).rem_euclid(TIMESTAMP_MAX);
+        Duration::from_millis(hundredths as u64 * 10)
+    }
+
+    /// returns the date and time of this timestamp on `date`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use eolib::time::Timestamp;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2008, 5, 28).unwrap();
+    /// let timestamp = Timestamp::new(18, 30, 15, 25).unwrap();
+    /// assert_eq!(
+    ///     timestamp.on(date),
+    ///     date.and_hms_milli_opt(18, 30, 15, 250).unwrap()
+    /// );
+    /// assert_eq!(Timestamp::try_from(timestamp.on(date)), Ok(timestamp));
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn on(&self, date: chrono::NaiveDate) -> chrono::NaiveDateTime {
+        date.and_time(chrono::NaiveTime::from(*self))
+    }
+}
+
+impl From<Duration> for Timestamp {
+    fn from(duration: Duration) -> Self {
+        Self::from_duration(duration)
+    }
+}
+
+impl From<Timestamp> for Duration {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.to_duration()
+    }
+}
+
+/// drops anything shorter than a hundredth of a second
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::NaiveTime> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(time: chrono::NaiveTime) -> Result<Self, Self::Error> {
+        use chrono::Timelike;
+
+        // chrono stores a leap second as a nanosecond count past the 59th second
+        if time.nanosecond() >= 1_000_000_000 {
+            return Err(TimestampError::LeapSecond);
+        }
+
+        Self::new(
+            time.hour() as u8,
+            time.minute() as u8,
+            time.second() as u8,
+            (time.nanosecond() / 10_000_000) as u8,
+        )
+    }
+}
+
+/// uses the time of day, dropping the date
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::NaiveDateTime> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(date_time: chrono::NaiveDateTime) -> Result<Self, Self::Error> {
+        Self::try_from(date_time.time())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::NaiveTime {
+    fn from(timestamp: Timestamp) -> Self {
+        let seconds = timestamp.to_three() as u32 / 100;
+        let nanoseconds = timestamp.hundredths as u32 * 10_000_000;
+        match Self::from_num_seconds_from_midnight_opt(seconds, nanoseconds) {
+            Some(time) => time,
+            None => unreachable!("timestamps are checked to be within a day"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Timestamp, TimestampError, TIMESTAMP_MAX};
+
+    #[test]
+    fn fits_in_three() {
+        assert_eq!(
+            Timestamp::from_three(TIMESTAMP_MAX - 1).to_three(),
+            TIMESTAMP_MAX - 1
+        );
+        assert_eq!(Timestamp::from_three(TIMESTAMP_MAX), Timestamp::default());
+    }
+
+    #[test]
+    fn duration_round_trip() {
+        let duration = Duration::from_millis(((13 * 60 + 7) * 60 + 42) * 1000 + 990);
+        let timestamp = Timestamp::from(duration);
+        assert_eq!(timestamp, Timestamp::new(13, 7, 42, 99).unwrap());
+        assert_eq!(Duration::from(timestamp), duration);
+    }
+
+    #[test]
+    fn duration_wraps_at_midnight() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        assert_eq!(
+            Timestamp::from_duration(day + Duration::from_millis(20)),
+            Timestamp::from_three(2)
+        );
+    }
+
+    #[test]
+    fn parts_are_checked() {
+        assert!(Timestamp::new(23, 59, 59, 99).is_ok());
+        assert_eq!(
+            Timestamp::new(0, 60, 0, 0),
+            Err(TimestampError::InvalidMinute(60))
+        );
+        assert_eq!(
+            Timestamp::new(0, 0, 60, 0),
+            Err(TimestampError::InvalidSecond(60))
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_round_trip() {
+        use chrono::{NaiveDate, NaiveTime};
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let date_time = date.and_hms_milli_opt(7, 45, 30, 129).unwrap();
+        let timestamp = Timestamp::try_from(date_time).unwrap();
+        assert_eq!(timestamp, Timestamp::new(7, 45, 30, 12).unwrap());
+        assert_eq!(
+            timestamp.on(date),
+            date.and_hms_milli_opt(7, 45, 30, 120).unwrap()
+        );
+
+        let time = NaiveTime::from(Timestamp::from_three(TIMESTAMP_MAX - 1));
+        assert_eq!(
+            time,
+            NaiveTime::from_hms_milli_opt(23, 59, 59, 990).unwrap()
+        );
+
+        let leap_second = NaiveTime::from_hms_milli_opt(23, 59, 59, 1_500).unwrap();
+        assert_eq!(
+            Timestamp::try_from(leap_second),
+            Err(TimestampError::LeapSecond)
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use std::ops::RangeInclusive;
+
+use encoding_rs::WINDOWS_1252;
+use thiserror::Error;
+
+use crate::protocol::net::Version;
+
+// The protocol doesn't limit any of these lengths, strings are sent with whatever length
+// they have. They are the defaults of [ValidationLimits] and servers are free to change them.
+
+/// Default allowed length of a character name
+pub const NAME_LENGTH: RangeInclusive<usize> = 4..=12;
+
+/// Default allowed length of a guild tag
+pub const GUILD_TAG_LENGTH: RangeInclusive<usize> = 2..=3;
+
+/// Default allowed length of a guild name
+pub const GUILD_NAME_LENGTH: RangeInclusive<usize> = 4..=24;
+
+/// Default allowed length of a board post subject
+pub const BOARD_SUBJECT_LENGTH: RangeInclusive<usize> = 1..=32;
+
+/// Default allowed length of a board post body
+pub const BOARD_BODY_LENGTH: RangeInclusive<usize> = 1..=2048;
+
+/// Default allowed length of a chat message
+pub const CHAT_MESSAGE_LENGTH: RangeInclusive<usize> = 1..=128;
+
+/// Allowed length of an hdid, the decimal digits of a 32-bit number
+pub const HDID_LENGTH: RangeInclusive<usize> = 1..=10;
+
+/// Version sent by the last official client, 0.0.28
+pub const CLIENT_VERSION: Version = Version {
+    major: 0,
+    minor: 0,
+    patch: 28,
+};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+/// Input that breaks the length or character restrictions of [ValidationLimits]
+pub enum ValidationError {
+    #[error("Length {0} must be at least {1}")]
+    TooShort(usize, usize),
+    #[error("Length {0} must be at most {1}")]
+    TooLong(usize, usize),
+    #[error("Invalid character {0:?}")]
+    InvalidCharacter(char),
+    #[error("Number {0} is too large")]
+    NumberTooLarge(String),
+    #[error("Unsupported client version {}.{}.{}", .0.major, .0.minor, .0.patch)]
+    UnsupportedVersion(Version),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The lengths a server allows for names and text sent by players
+///
+/// The defaults are the `*_LENGTH` constants. The free functions in this module validate
+/// against them, a server with other limits calls the methods here instead.
+///
+/// # Examples
+///
+/// ```
+/// use eolib::validation::{ValidationError, ValidationLimits};
+///
+/// let limits = ValidationLimits {
+///     chat_message: 1..=64,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     limits.validate_chat_message(&"a".repeat(100)),
+///     Err(ValidationError::TooLong(100, 64))
+/// );
+/// ```
+pub struct ValidationLimits {
+    pub name: RangeInclusive<usize>,
+    pub guild_tag: RangeInclusive<usize>,
+    pub guild_name: RangeInclusive<usize>,
+    pub board_subject: RangeInclusive<usize>,
+    pub board_body: RangeInclusive<usize>,
+    pub chat_message: RangeInclusive<usize>,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        Self {
+            name: NAME_LENGTH,
+            guild_tag: GUILD_TAG_LENGTH,
+            guild_name: GUILD_NAME_LENGTH,
+            board_subject: BOARD_SUBJECT_LENGTH,
+            board_body: BOARD_BODY_LENGTH,
+            chat_message: CHAT_MESSAGE_LENGTH,
+        }
+    }
+}
+
+impl ValidationLimits {
+    /// validates a character name of lowercase letters
+    pub fn validate_name(&self, name: &str) -> Result<(), ValidationError> {
+        validate_length(name, self.name.clone())?;
+        validate_characters(name, |c| c.is_ascii_lowercase())
+    }
+
+    /// validates a guild tag of uppercase letters
+    pub fn validate_guild_tag(&self, tag: &str) -> Result<(), ValidationError> {
+        validate_length(tag, self.guild_tag.clone())?;
+        validate_characters(tag, |c| c.is_ascii_uppercase())
+    }
+
+    /// validates a guild name of letters and spaces
+    pub fn validate_guild_name(&self, name: &str) -> Result<(), ValidationError> {
+        validate_length(name, self.guild_name.clone())?;
+        validate_characters(name, |c| c.is_ascii_alphabetic() || c == ' ')
+    }
+
+    /// validates the subject and body of a board post, both printable text the client can
+    /// encode
+    pub fn validate_board_post(&self, subject: &str, body: &str) -> Result<(), ValidationError> {
+        validate_length(subject, self.board_subject.clone())?;
+        validate_text(subject)?;
+        validate_length(body, self.board_body.clone())?;
+        validate_text(body)
+    }
+
+    /// validates a chat message of printable text the client can encode
+    pub fn validate_chat_message(&self, message: &str) -> Result<(), ValidationError> {
+        validate_length(message, self.chat_message.clone())?;
+        validate_text(message)
+    }
+}
+
+/// validates a character name with the default [ValidationLimits]
+///
+/// names are 4 to 12 lowercase letters
+///
+/// # Examples
+///
+/// ```
+/// use eolib::validation::{validate_name, ValidationError};
+///
+/// assert_eq!(validate_name("vulture"), Ok(()));
+/// assert_eq!(validate_name("bob"), Err(ValidationError::TooShort(3, 4)));
+/// assert_eq!(validate_name("Vulture"), Err(ValidationError::InvalidCharacter('V')));
+/// ```
+pub fn validate_name(name: &str) -> Result<(), ValidationError> {
+    ValidationLimits::default().validate_name(name)
+}
+
+/// validates a guild tag with the default [ValidationLimits]
+///
+/// tags are 2 to 3 uppercase letters
+pub fn validate_guild_tag(tag: &str) -> Result<(), ValidationError> {
+    ValidationLimits::default().validate_guild_tag(tag)
+}
+
+/// validates a guild name with the default [ValidationLimits]
+///
+/// names are 4 to 24 letters and spaces
+pub fn validate_guild_name(name: &str) -> Result<(), ValidationError> {
+    ValidationLimits::default().validate_guild_name(name)
+}
+
+/// validates the subject and body of a board post with the default [ValidationLimits]
+///
+/// both must be printable text the client can encode, up to 32 and 2048 characters
+pub fn validate_board_post(subject: &str, body: &str) -> Result<(), ValidationError> {
+    ValidationLimits::default().validate_board_post(subject, body)
+}
+
+/// validates a chat message with the default [ValidationLimits]
+///
+/// messages must be printable text the client can encode, up to 128 characters
+///
+/// # Examples
+///
+/// ```
+/// use eolib::validation::{validate_chat_message, ValidationError};
+///
+/// assert_eq!(validate_chat_message("Hello, world!"), Ok(()));
+/// assert_eq!(validate_chat_message(""), Err(ValidationError::TooShort(0, 1)));
+/// assert_eq!(validate_chat_message("ÿ"), Err(ValidationError::InvalidCharacter('ÿ')));
+/// ```
+pub fn validate_chat_message(message: &str) -> Result<(), ValidationError> {
+    ValidationLimits::default().validate_chat_message(message)
+}
+
+/// parses the hdid sent in the INIT_INIT client packet
+///
+/// the official client sends the serial number of the drive it runs from as a decimal
+/// string. it is used by servers to ban machines rather than accounts
+///
+/// # Examples
+///
+/// ```
+/// use eolib::validation::{parse_hdid, ValidationError};
+///
+/// assert_eq!(parse_hdid("2882400018"), Ok(0xABCDEF12));
+/// assert_eq!(parse_hdid(""), Err(ValidationError::TooShort(0, 1)));
+/// assert_eq!(parse_hdid("-1"), Err(ValidationError::InvalidCharacter('-')));
+/// ```
+pub fn parse_hdid(hdid: &str) -> Result<u32, ValidationError> {
+    validate_length(hdid, HDID_LENGTH)?;
+    validate_characters(hdid, |c| c.is_ascii_digit())?;
+    hdid.parse()
+        .map_err(|_| ValidationError::NumberTooLarge(hdid.to_owned()))
+}
+
+/// returns the hdid string the official client sends for a drive serial number
+pub fn format_hdid(serial: u32) -> String {
+    serial.to_string()
+}
+
+/// validates the version sent in the INIT_INIT client packet
+///
+/// stock clients send [CLIENT_VERSION], servers for modified clients can expect another one
+///
+/// # Examples
+///
+/// ```
+/// use eolib::{
+///     protocol::net::Version,
+///     validation::{validate_version, ValidationError, CLIENT_VERSION},
+/// };
+///
+/// assert_eq!(validate_version(&CLIENT_VERSION, &CLIENT_VERSION), Ok(()));
+///
+/// let old = Version { major: 0, minor: 0, patch: 27 };
+/// assert_eq!(
+///     validate_version(&old, &CLIENT_VERSION),
+///     Err(ValidationError::UnsupportedVersion(old.clone()))
+/// );
+/// ```
+pub fn validate_version(version: &Version, expected: &Version) -> Result<(), ValidationError> {
+    if version == expected {
+        Ok(())
+    } else {
+        Err(ValidationError::UnsupportedVersion(version.clone()))
+    }
+}
+
+fn validate_length(text: &str, range: RangeInclusive<usize>) -> Result<(), ValidationError> {
+    let length = text.chars().count();
+    if length < *range.start() {
+        Err(ValidationError::TooShort(length, *range.start()))
+    } else if length > *range.end() {
+        Err(ValidationError::TooLong(length, *range.end()))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_characters(text: &str, valid: impl Fn(char) -> bool) -> Result<(), ValidationError> {
+    match text.chars().find(|c| !valid(*c)) {
+        Some(c) => Err(ValidationError::InvalidCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+// free text must survive WINDOWS-1252 encoding and can't contain 0xFF (ÿ) since it is the
+// break byte
+fn validate_text(text: &str) -> Result<(), ValidationError> {
+    validate_characters(text, |c| {
+        let mut buf = [0; 4];
+        let (encoded, _, unmappable) = WINDOWS_1252.encode(c.encode_utf8(&mut buf));
+        !c.is_control() && !unmappable && encoded[..] != [0xff]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_hdid, parse_hdid, validate_board_post, validate_chat_message, validate_guild_name,
+        validate_guild_tag, validate_name, ValidationError, ValidationLimits,
+    };
+
+    #[test]
+    fn name() {
+        assert_eq!(validate_name("abcdefghijkl"), Ok(()));
+        assert_eq!(
+            validate_name("abcdefghijklm"),
+            Err(ValidationError::TooLong(13, 12))
+        );
+        assert_eq!(
+            validate_name("bob1"),
+            Err(ValidationError::InvalidCharacter('1'))
+        );
+    }
+
+    #[test]
+    fn guild() {
+        assert_eq!(validate_guild_tag("AB"), Ok(()));
+        assert_eq!(
+            validate_guild_tag("ab"),
+            Err(ValidationError::InvalidCharacter('a'))
+        );
+        assert_eq!(validate_guild_name("The Guild"), Ok(()));
+        assert_eq!(
+            validate_guild_name("G_d!"),
+            Err(ValidationError::InvalidCharacter('_'))
+        );
+    }
+
+    #[test]
+    fn board_post() {
+        assert_eq!(validate_board_post("Hi", "Selling armor €5"), Ok(()));
+        assert_eq!(
+            validate_board_post("", "body"),
+            Err(ValidationError::TooShort(0, 1))
+        );
+        assert_eq!(
+            validate_board_post("Hi", "line\nbreak"),
+            Err(ValidationError::InvalidCharacter('\n'))
+        );
+    }
+
+    #[test]
+    fn chat_message() {
+        assert_eq!(validate_chat_message(&"a".repeat(128)), Ok(()));
+        assert_eq!(
+            validate_chat_message(&"a".repeat(129)),
+            Err(ValidationError::TooLong(129, 128))
+        );
+        assert_eq!(
+            validate_chat_message("☃"),
+            Err(ValidationError::InvalidCharacter('☃'))
+        );
+    }
+
+    #[test]
+    fn hdid() {
+        assert_eq!(parse_hdid(&format_hdid(u32::MAX)), Ok(u32::MAX));
+        assert_eq!(parse_hdid("0"), Ok(0));
+        assert_eq!(
+            parse_hdid("4294967296"),
+            Err(ValidationError::NumberTooLarge("4294967296".to_string()))
+        );
+        assert_eq!(
+            parse_hdid("12345678901"),
+            Err(ValidationError::TooLong(11, 10))
+        );
+        assert_eq!(
+            parse_hdid("12a"),
+            Err(ValidationError::InvalidCharacter('a'))
+        );
+    }
+
+    #[test]
+    fn custom_limits() {
+        let limits = ValidationLimits {
+            name: 2..=20,
+            board_body: 1..=10,
+            ..Default::default()
+        };
+        assert_eq!(limits.validate_name("al"), Ok(()));
+        assert_eq!(
+            limits.validate_name("Al"),
+            Err(ValidationError::InvalidCharacter('A'))
+        );
+        assert_eq!(
+            limits.validate_board_post("Hi", "too long a post"),
+            Err(ValidationError::TooLong(15, 10))
+        );
+        assert_eq!(limits.validate_chat_message(&"a".repeat(128)), Ok(()));
+    }
+}